@@ -6,10 +6,14 @@ use std::{
 use crate::{
     app_state::ManagedState,
     cluster_discovery::{ClusterDiscovery, ClusterState},
+    cluster_metrics::ClusterMetrics,
     frontend_commands::KubeContextSource,
     frontend_types::BackendError,
+    related_resources::RelatedResources,
     scripting::{
+        dashboard_facade::DashboardFacade, diagnostics::ScriptDiagnostics,
         resource_context_menu_facade::ResourceContextMenuFacade,
+        resource_details_facade::ResourceDetailsFacade,
         resource_presentation_facade::ResourcePresentationFacade,
     },
 };
@@ -78,6 +82,44 @@ impl ClusterStateRegistry {
             .clone())
     }
 
+    pub fn details_scripting_for(
+        &self,
+        context_source: &KubeContextSource,
+    ) -> Result<Arc<ResourceDetailsFacade>, BackendError> {
+        Ok(self
+            .get_state(context_source)?
+            .resource_details_facade
+            .clone())
+    }
+
+    pub fn dashboard_scripting_for(
+        &self,
+        context_source: &KubeContextSource,
+    ) -> Result<Arc<DashboardFacade>, BackendError> {
+        Ok(self.get_state(context_source)?.dashboard_facade.clone())
+    }
+
+    pub fn metrics_for(
+        &self,
+        context_source: &KubeContextSource,
+    ) -> Result<Arc<ClusterMetrics>, BackendError> {
+        Ok(self.get_state(context_source)?.metrics.clone())
+    }
+
+    pub fn related_for(
+        &self,
+        context_source: &KubeContextSource,
+    ) -> Result<Arc<RelatedResources>, BackendError> {
+        Ok(self.get_state(context_source)?.related.clone())
+    }
+
+    pub fn diagnostics_for(
+        &self,
+        context_source: &KubeContextSource,
+    ) -> Result<Arc<ScriptDiagnostics>, BackendError> {
+        Ok(self.get_state(context_source)?.diagnostics.clone())
+    }
+
     pub fn discovery_for(
         &self,
         context_source: &KubeContextSource,
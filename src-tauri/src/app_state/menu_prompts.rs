@@ -0,0 +1,87 @@
+use std::{
+    collections::HashMap,
+    sync::{
+        mpsc::{sync_channel, Receiver, SyncSender},
+        Arc, Mutex,
+    },
+};
+
+use serde::Deserialize;
+use uuid::Uuid;
+
+use crate::app_state::ManagedState;
+
+pub type PromptId = Uuid;
+
+#[derive(thiserror::Error, Debug)]
+pub enum MenuPromptError {
+    #[error("Prompt with ID {0} not found, it may have timed out")]
+    PromptNotFound(PromptId),
+}
+
+impl serde::Serialize for MenuPromptError {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::ser::Serializer,
+    {
+        serializer.serialize_str(self.to_string().as_ref())
+    }
+}
+
+/// What the user did with a prompt that a menu action showed.
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase", tag = "outcome", content = "values")]
+pub enum PromptAnswer {
+    Submitted(serde_json::Map<String, serde_json::Value>),
+    Cancelled,
+}
+
+/// Prompts that menu actions are waiting on, keyed by the ID the frontend answers with.
+///
+/// Menu actions run on blocking threads, hence they wait on a plain channel instead of an async one.
+#[derive(Default)]
+pub struct MenuPrompts {
+    pending: Mutex<HashMap<PromptId, SyncSender<PromptAnswer>>>,
+}
+
+impl ManagedState for MenuPrompts {
+    type WrappedState = Arc<MenuPrompts>;
+
+    fn build(_: tauri::AppHandle) -> Self::WrappedState {
+        Arc::new(Self::default())
+    }
+}
+
+impl MenuPrompts {
+    pub fn register(&self) -> (PromptId, Receiver<PromptAnswer>) {
+        let uuid = Uuid::new_v4();
+        let (sender, receiver) = sync_channel(1);
+
+        self.pending.lock().unwrap().insert(uuid, sender);
+
+        (uuid, receiver)
+    }
+
+    pub fn answer(
+        &self,
+        prompt_id: &PromptId,
+        answer: PromptAnswer,
+    ) -> Result<(), MenuPromptError> {
+        let sender = self
+            .pending
+            .lock()
+            .unwrap()
+            .remove(prompt_id)
+            .ok_or(MenuPromptError::PromptNotFound(*prompt_id))?;
+
+        // The action may have given up waiting in the meantime
+        let _ = sender.send(answer);
+
+        Ok(())
+    }
+
+    /// Drops a prompt that will not be waited on anymore.
+    pub fn forget(&self, prompt_id: &PromptId) {
+        self.pending.lock().unwrap().remove(prompt_id);
+    }
+}
@@ -2,8 +2,10 @@ mod channel_tasks;
 mod cluster_state_registry;
 mod exec_sessions;
 mod manager_ext;
+mod menu_prompts;
 
 pub use channel_tasks::*;
 pub use cluster_state_registry::*;
 pub use exec_sessions::*;
 pub use manager_ext::*;
+pub use menu_prompts::*;
@@ -0,0 +1,107 @@
+//! Runs the test specs of a script package against a fake cluster, e.g. in the CI of an extension repository.
+//!
+//! ```text
+//! hyprkube-script-test --core-lib <dir> <package> [<spec.yaml>...]
+//! ```
+//!
+//! `--core-lib` is the `lib` directory of the core package that scripts import from, e.g. `scripts/core/lib` of a
+//! Hyprkube checkout. Without specs, all `*.yaml` files in the `tests` directory of the package are run. The exit
+//! code is non-zero if any test failed.
+
+use std::{path::PathBuf, process::ExitCode};
+
+use hyprkube_lib::harness::{spec::TestSpec, ScriptEnvironment};
+
+const USAGE: &str = "usage: hyprkube-script-test --core-lib <dir> <package> [<spec.yaml>...]";
+
+fn main() -> ExitCode {
+    let mut core_lib = None;
+    let mut paths = Vec::new();
+
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--core-lib" => match args.next() {
+                Some(dir) => core_lib = Some(PathBuf::from(dir)),
+                None => return usage_error(),
+            },
+            "-h" | "--help" => {
+                println!("{USAGE}");
+                return ExitCode::SUCCESS;
+            }
+            _ => paths.push(PathBuf::from(arg)),
+        }
+    }
+
+    let (Some(core_lib), false) = (core_lib, paths.is_empty()) else {
+        return usage_error();
+    };
+
+    let package = paths.remove(0);
+    let specs = if paths.is_empty() {
+        specs_in(&package.join("tests"))
+    } else {
+        paths
+    };
+
+    if specs.is_empty() {
+        eprintln!("No test specs found in {}", package.join("tests").display());
+        return ExitCode::FAILURE;
+    }
+
+    let environment = ScriptEnvironment::new(Some(core_lib));
+    let (mut passed, mut failed) = (0, 0);
+
+    for path in specs {
+        let spec = match TestSpec::read(&path) {
+            Ok(spec) => spec,
+            Err(e) => {
+                eprintln!("{}: {e}", path.display());
+                failed += 1;
+                continue;
+            }
+        };
+
+        for result in spec.run(&package, &environment) {
+            if result.passed() {
+                println!("ok      {} :: {}", path.display(), result.name);
+                passed += 1;
+            } else {
+                println!("FAILED  {} :: {}", path.display(), result.name);
+                for failure in &result.failures {
+                    println!("        {failure}");
+                }
+                failed += 1;
+            }
+        }
+    }
+
+    println!("\n{passed} passed, {failed} failed");
+
+    if failed > 0 {
+        ExitCode::FAILURE
+    } else {
+        ExitCode::SUCCESS
+    }
+}
+
+fn specs_in(dir: &std::path::Path) -> Vec<PathBuf> {
+    let mut specs: Vec<PathBuf> = std::fs::read_dir(dir)
+        .into_iter()
+        .flatten()
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| {
+            path.extension()
+                .is_some_and(|ext| ext == "yaml" || ext == "yml")
+        })
+        .collect();
+
+    specs.sort();
+    specs
+}
+
+fn usage_error() -> ExitCode {
+    eprintln!("{USAGE}");
+    ExitCode::from(2)
+}
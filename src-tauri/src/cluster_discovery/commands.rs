@@ -17,14 +17,20 @@ use crate::{
         ApiGroupSource, ClusterDiscovery, ClusterState, CompletedDiscovery, DiscoveredResource,
         InflightDiscovery,
     },
+    cluster_metrics::ClusterMetrics,
+    cluster_profiles::ClusterProfileId,
     frontend_commands::KubeContextSource,
     persistence::{
         apiserver_version_cache_service::{ApiserverVersionCacheService, CachedApiserverVersion},
         discovery_cache_service::DiscoveryCacheService,
         repository::Repository,
     },
+    related_resources::RelatedResources,
     scripting::{
+        dashboard_facade::DashboardFacade, diagnostics::ScriptDiagnostics,
+        environment::ScriptEnvironment, hot_reload,
         resource_context_menu_facade::ResourceContextMenuFacade,
+        resource_details_facade::ResourceDetailsFacade,
         resource_presentation_facade::ResourcePresentationFacade,
         scripts_provider::ScriptsProvider,
     },
@@ -70,9 +76,13 @@ pub async fn connect_cluster(
     app: tauri::AppHandle,
     channel: tauri::ipc::Channel<FrontendDiscoveryEvent>,
     context_source: KubeContextSource,
+    cluster_profile: Option<ClusterProfileId>,
 ) -> Result<(), String> {
     crate::internal::tracing::set_span_request_id();
 
+    // Determines which script packages are enabled
+    let cluster_profile = cluster_profile.unwrap_or_default();
+
     let clusters = app.state::<ClusterStateRegistry>();
     let background_tasks = Arc::clone(&app.state::<ChannelTasks>());
     let scripts_provider = app.state::<ScriptsProvider>();
@@ -113,11 +123,51 @@ pub async fn connect_cluster(
 
         let client = make_client(&context_source).await?;
 
-        let resource_presentation_facade = ResourcePresentationFacade::new(app.clone());
-        resource_presentation_facade.evaluate(&scripts_provider)?;
+        let metrics = ClusterMetrics::new();
+        let related = RelatedResources::new();
+        let diagnostics = ScriptDiagnostics::new();
+        let environment = ScriptEnvironment {
+            storage: Arc::clone(&repository) as _,
+            cluster: Some(context_source.context.clone()),
+            builtin_packages: scripts_provider.get_builtin_packages_dir().ok(),
+            ..ScriptEnvironment::new(scripts_provider.get_core_lib_dir().ok())
+        };
+
+        let resource_presentation_facade = ResourcePresentationFacade::new(
+            Arc::clone(&metrics),
+            Arc::clone(&related),
+            Arc::clone(&diagnostics),
+            environment.clone(),
+        );
+        resource_presentation_facade.evaluate(&scripts_provider, &cluster_profile)?;
+
+        let resource_details_facade = ResourceDetailsFacade::new(
+            Arc::clone(&metrics),
+            Arc::clone(&related),
+            Arc::clone(&diagnostics),
+            environment.clone(),
+        );
+        resource_details_facade.evaluate(&scripts_provider, &cluster_profile)?;
+
+        let dashboard_facade = DashboardFacade::new(Arc::clone(&diagnostics), environment.clone());
+        dashboard_facade.evaluate(&scripts_provider, &cluster_profile)?;
 
-        let context_menu_facade = ResourceContextMenuFacade::new(app.clone(), client.clone());
-        context_menu_facade.evaluate(&scripts_provider)?;
+        let context_menu_facade = ResourceContextMenuFacade::new(
+            Arc::new(app.clone()),
+            client.clone(),
+            Arc::clone(&diagnostics),
+            environment,
+        );
+        context_menu_facade.evaluate(&scripts_provider, &cluster_profile)?;
+
+        hot_reload::watch(
+            Arc::clone(&scripts_provider),
+            cluster_profile,
+            &resource_presentation_facade,
+            &resource_details_facade,
+            &dashboard_facade,
+            &context_menu_facade,
+        );
 
         // Attachable stuff
         let inflight = Arc::new(InflightDiscovery::new());
@@ -128,6 +178,11 @@ pub async fn connect_cluster(
             Arc::new(ClusterDiscovery::Inflight(Arc::clone(&inflight))),
             context_menu_facade,
             resource_presentation_facade,
+            resource_details_facade,
+            dashboard_facade,
+            metrics,
+            related,
+            diagnostics,
         ));
 
         // Cached part
@@ -11,9 +11,13 @@ use tokio::sync::broadcast;
 
 use crate::{
     cluster_discovery::FrontendDiscoveryEvent,
+    cluster_metrics::ClusterMetrics,
     frontend_commands::KubeContextSource,
+    related_resources::RelatedResources,
     scripting::{
+        dashboard_facade::DashboardFacade, diagnostics::ScriptDiagnostics,
         resource_context_menu_facade::ResourceContextMenuFacade,
+        resource_details_facade::ResourceDetailsFacade,
         resource_presentation_facade::ResourcePresentationFacade,
     },
 };
@@ -70,15 +74,26 @@ pub struct ClusterState {
     kube_discovery: RwLock<Option<Arc<kube::Discovery>>>,
     pub context_menu_facade: Arc<ResourceContextMenuFacade>,
     pub resource_presentation_facade: Arc<ResourcePresentationFacade>,
+    pub resource_details_facade: Arc<ResourceDetailsFacade>,
+    pub dashboard_facade: Arc<DashboardFacade>,
+    pub metrics: Arc<ClusterMetrics>,
+    pub related: Arc<RelatedResources>,
+    pub diagnostics: Arc<ScriptDiagnostics>,
 }
 
 impl ClusterState {
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         context_source: KubeContextSource,
         client: kube::Client,
         discovery: Arc<ClusterDiscovery>,
         context_menu_facade: Arc<ResourceContextMenuFacade>,
         resource_presentation_facade: Arc<ResourcePresentationFacade>,
+        resource_details_facade: Arc<ResourceDetailsFacade>,
+        dashboard_facade: Arc<DashboardFacade>,
+        metrics: Arc<ClusterMetrics>,
+        related: Arc<RelatedResources>,
+        diagnostics: Arc<ScriptDiagnostics>,
     ) -> Self {
         Self {
             context_source,
@@ -87,6 +102,11 @@ impl ClusterState {
             kube_discovery: RwLock::new(None),
             context_menu_facade,
             resource_presentation_facade,
+            resource_details_facade,
+            dashboard_facade,
+            metrics,
+            related,
+            diagnostics,
         }
     }
 
@@ -105,6 +125,9 @@ impl ClusterState {
     ) {
         if let Some(discovery) = &kube_discovery {
             self.context_menu_facade.set_discovery(discovery.clone());
+            self.metrics.start_polling(self.client.clone(), discovery);
+            self.related
+                .start_resolving(self.client.clone(), Arc::clone(discovery));
         }
 
         *self.kube_discovery.write().unwrap() = kube_discovery;
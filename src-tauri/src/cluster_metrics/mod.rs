@@ -0,0 +1,6 @@
+mod model;
+mod poller;
+mod store;
+
+pub use model::*;
+pub use store::*;
@@ -0,0 +1,153 @@
+use std::collections::HashMap;
+
+use serde::Deserialize;
+
+use crate::internal::quantity::{parse_cpu_millis, parse_memory_bytes};
+
+/// A single usage sample, normalized to millicores and bytes.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct ResourceUsage {
+    pub cpu_millis: i64,
+    pub memory_bytes: i64,
+}
+
+impl ResourceUsage {
+    fn from_raw(raw: &RawUsage) -> Self {
+        Self {
+            cpu_millis: raw
+                .cpu
+                .as_deref()
+                .map(parse_cpu_millis)
+                .and_then(Result::ok)
+                .unwrap_or_default(),
+            memory_bytes: raw
+                .memory
+                .as_deref()
+                .map(parse_memory_bytes)
+                .and_then(Result::ok)
+                .unwrap_or_default(),
+        }
+    }
+}
+
+impl std::ops::Add for ResourceUsage {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        Self {
+            cpu_millis: self.cpu_millis + rhs.cpu_millis,
+            memory_bytes: self.memory_bytes + rhs.memory_bytes,
+        }
+    }
+}
+
+/// Latest usage sample of a Pod, as reported by `metrics.k8s.io/v1beta1` PodMetrics.
+#[derive(Clone, Debug, PartialEq)]
+pub struct PodUsage {
+    pub timestamp: Option<String>,
+    pub total: ResourceUsage,
+    pub containers: HashMap<String, ResourceUsage>,
+}
+
+/// Latest usage sample of a Node, as reported by `metrics.k8s.io/v1beta1` NodeMetrics.
+#[derive(Clone, Debug, PartialEq)]
+pub struct NodeUsage {
+    pub timestamp: Option<String>,
+    pub total: ResourceUsage,
+}
+
+#[derive(Deserialize, Default)]
+struct RawUsage {
+    cpu: Option<String>,
+    memory: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct RawContainerMetrics {
+    name: String,
+    #[serde(default)]
+    usage: RawUsage,
+}
+
+/// The non-metadata part of a PodMetrics object
+#[derive(Deserialize)]
+pub(super) struct RawPodMetrics {
+    timestamp: Option<String>,
+    #[serde(default)]
+    containers: Vec<RawContainerMetrics>,
+}
+
+/// The non-metadata part of a NodeMetrics object
+#[derive(Deserialize)]
+pub(super) struct RawNodeMetrics {
+    timestamp: Option<String>,
+    #[serde(default)]
+    usage: RawUsage,
+}
+
+impl From<RawPodMetrics> for PodUsage {
+    fn from(value: RawPodMetrics) -> Self {
+        let containers: HashMap<String, ResourceUsage> = value
+            .containers
+            .iter()
+            .map(|c| (c.name.clone(), ResourceUsage::from_raw(&c.usage)))
+            .collect();
+
+        Self {
+            timestamp: value.timestamp,
+            total: containers
+                .values()
+                .fold(ResourceUsage::default(), |sum, usage| sum + *usage),
+            containers,
+        }
+    }
+}
+
+impl From<RawNodeMetrics> for NodeUsage {
+    fn from(value: RawNodeMetrics) -> Self {
+        Self {
+            timestamp: value.timestamp,
+            total: ResourceUsage::from_raw(&value.usage),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn test_pod_usage_sums_containers() {
+        let raw: RawPodMetrics = serde_json::from_value(json!({
+            "timestamp": "2024-01-01T00:00:00Z",
+            "window": "15s",
+            "containers": [
+                { "name": "app", "usage": { "cpu": "120m", "memory": "64Mi" } },
+                { "name": "sidecar", "usage": { "cpu": "5000000n", "memory": "16Mi" } }
+            ]
+        }))
+        .unwrap();
+
+        let usage = PodUsage::from(raw);
+
+        assert_eq!(125, usage.total.cpu_millis);
+        assert_eq!(80 * 1024 * 1024, usage.total.memory_bytes);
+        assert_eq!(120, usage.containers["app"].cpu_millis);
+    }
+
+    #[test]
+    fn test_node_usage() {
+        let raw: RawNodeMetrics = serde_json::from_value(json!({
+            "timestamp": "2024-01-01T00:00:00Z",
+            "usage": { "cpu": "1500m", "memory": "2Gi" }
+        }))
+        .unwrap();
+
+        let usage = NodeUsage::from(raw);
+
+        assert_eq!(1500, usage.total.cpu_millis);
+        assert_eq!(2 * 1024 * 1024 * 1024, usage.total.memory_bytes);
+    }
+}
@@ -0,0 +1,142 @@
+use std::{
+    collections::HashMap,
+    sync::{Arc, Weak},
+    time::Duration,
+};
+
+use kube::{
+    api::{ApiResource, DynamicObject, ListParams},
+    Api,
+};
+use tauri::async_runtime::spawn;
+
+use crate::cluster_metrics::{
+    model::{RawNodeMetrics, RawPodMetrics},
+    ClusterMetrics, NodeUsage, PodUsage,
+};
+
+const METRICS_GROUP: &str = "metrics.k8s.io";
+const POLL_INTERVAL: Duration = Duration::from_secs(15);
+
+impl ClusterMetrics {
+    /// Starts polling PodMetrics and NodeMetrics if the cluster serves the `metrics.k8s.io` group.
+    ///
+    /// The poller only holds a weak reference to the cache and stops as soon as the owning
+    /// `ClusterState` is dropped, e.g. when the cluster is reconnected.
+    pub fn start_polling(self: &Arc<Self>, client: kube::Client, discovery: &kube::Discovery) {
+        let Some(group) = discovery.get(METRICS_GROUP) else {
+            tracing::info!("Cluster does not serve {METRICS_GROUP}, resource usage is unavailable");
+            return;
+        };
+
+        let pod_metrics = group.recommended_kind("PodMetrics").map(|(ar, _)| ar);
+        let node_metrics = group.recommended_kind("NodeMetrics").map(|(ar, _)| ar);
+
+        let metrics = Arc::downgrade(self);
+
+        spawn(async move {
+            let mut interval = tokio::time::interval(POLL_INTERVAL);
+
+            loop {
+                interval.tick().await;
+
+                if !poll_once(
+                    &metrics,
+                    &client,
+                    pod_metrics.as_ref(),
+                    node_metrics.as_ref(),
+                )
+                .await
+                {
+                    tracing::debug!("Metrics cache dropped, stopping poller");
+                    break;
+                }
+            }
+        });
+    }
+}
+
+/// Performs one poll. Returns `false` if the cache is gone and polling should stop.
+async fn poll_once(
+    metrics: &Weak<ClusterMetrics>,
+    client: &kube::Client,
+    pod_metrics: Option<&ApiResource>,
+    node_metrics: Option<&ApiResource>,
+) -> bool {
+    let pods = match pod_metrics {
+        Some(ar) => list_pod_metrics(client.clone(), ar).await,
+        None => Ok(HashMap::new()),
+    };
+
+    let nodes = match node_metrics {
+        Some(ar) => list_node_metrics(client.clone(), ar).await,
+        None => Ok(HashMap::new()),
+    };
+
+    let Some(metrics) = metrics.upgrade() else {
+        return false;
+    };
+
+    match pods {
+        Ok(pods) => metrics.replace_pods(pods),
+        Err(e) => tracing::warn!("Failed to poll PodMetrics: {e}"),
+    }
+
+    match nodes {
+        Ok(nodes) => metrics.replace_nodes(nodes),
+        Err(e) => tracing::warn!("Failed to poll NodeMetrics: {e}"),
+    }
+
+    metrics.notify();
+
+    true
+}
+
+async fn list_pod_metrics(
+    client: kube::Client,
+    ar: &ApiResource,
+) -> anyhow::Result<HashMap<(String, String), PodUsage>> {
+    let api: Api<DynamicObject> = Api::all_with(client, ar);
+
+    let samples = api
+        .list(&ListParams::default())
+        .await?
+        .items
+        .into_iter()
+        .filter_map(|obj| {
+            let namespace = obj.metadata.namespace.clone().unwrap_or_default();
+            let name = obj.metadata.name.clone()?;
+
+            serde_json::from_value::<RawPodMetrics>(obj.data)
+                .map_err(|e| tracing::warn!("Invalid PodMetrics {namespace}/{name}: {e}"))
+                .ok()
+                .map(|raw| ((namespace, name), raw.into()))
+        })
+        .collect();
+
+    Ok(samples)
+}
+
+async fn list_node_metrics(
+    client: kube::Client,
+    ar: &ApiResource,
+) -> anyhow::Result<HashMap<String, NodeUsage>> {
+    let api: Api<DynamicObject> = Api::all_with(client, ar);
+
+    let samples = api
+        .list(&ListParams::default())
+        .await?
+        .items
+        .into_iter()
+        .filter_map(|obj| {
+            let name = obj.metadata.name.clone()?;
+
+            serde_json::from_value::<RawNodeMetrics>(obj.data)
+                .map_err(|e| tracing::warn!("Invalid NodeMetrics {name}: {e}"))
+                .ok()
+                .map(|raw| (name, raw.into()))
+        })
+        .collect();
+
+    Ok(samples)
+}
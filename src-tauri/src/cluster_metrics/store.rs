@@ -0,0 +1,61 @@
+use std::{
+    collections::HashMap,
+    sync::{Arc, RwLock},
+};
+
+use tokio::sync::broadcast;
+
+use crate::cluster_metrics::{NodeUsage, PodUsage};
+
+/// Per-cluster cache of the latest resource usage samples from `metrics.k8s.io`.
+///
+/// The cache is filled by the poller (see [`ClusterMetrics::start_polling`]) and read by scripts
+/// through the Rhai `metrics` module. Every completed poll is announced to subscribers so that
+/// open resource views can re-render their usage columns.
+pub struct ClusterMetrics {
+    pods: RwLock<HashMap<(String, String), PodUsage>>,
+    nodes: RwLock<HashMap<String, NodeUsage>>,
+    updates: broadcast::Sender<()>,
+}
+
+impl ClusterMetrics {
+    pub fn new() -> Arc<Self> {
+        let (updates, _) = broadcast::channel(4);
+
+        Arc::new(Self {
+            pods: RwLock::new(HashMap::new()),
+            nodes: RwLock::new(HashMap::new()),
+            updates,
+        })
+    }
+
+    pub fn pod_usage(&self, namespace: &str, name: &str) -> Option<PodUsage> {
+        self.pods
+            .read()
+            .unwrap()
+            .get(&(namespace.to_owned(), name.to_owned()))
+            .cloned()
+    }
+
+    pub fn node_usage(&self, name: &str) -> Option<NodeUsage> {
+        self.nodes.read().unwrap().get(name).cloned()
+    }
+
+    /// Returns a receiver that yields once after every completed poll.
+    pub fn subscribe(&self) -> broadcast::Receiver<()> {
+        self.updates.subscribe()
+    }
+
+    pub(super) fn replace_pods(&self, pods: HashMap<(String, String), PodUsage>) {
+        *self.pods.write().unwrap() = pods;
+    }
+
+    pub(super) fn replace_nodes(&self, nodes: HashMap<String, NodeUsage>) {
+        *self.nodes.write().unwrap() = nodes;
+    }
+
+    pub(super) fn notify(&self) {
+        // Nobody listening is fine, views subscribe only while they are open.
+        let _ = self.updates.send(());
+    }
+}
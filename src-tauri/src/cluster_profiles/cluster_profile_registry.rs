@@ -21,6 +21,13 @@ impl std::fmt::Display for ClusterProfileId {
     }
 }
 
+impl Default for ClusterProfileId {
+    /// The profile that always exists, see [ClusterProfileRegistry::ensure_default_profile]
+    fn default() -> Self {
+        Self("_default".to_owned())
+    }
+}
+
 pub struct ClusterProfileRegistry {
     app_handle: AppHandle,
     profiles: RwLock<HashMap<ClusterProfileId, (String, PathBuf)>>,
@@ -41,7 +48,7 @@ impl ClusterProfileRegistry {
     pub fn ensure_default_profile(&self) -> Result<(), std::io::Error> {
         let profiles_dir = {
             let mut path = get_cluster_profiles_dir(&self.app_handle).unwrap();
-            path.push("_default");
+            path.push(ClusterProfileId::default().0);
             path
         };
 
@@ -1,7 +1,9 @@
 mod cluster_profile_commands;
 mod cluster_profile_registry;
 mod gvk_commands;
+mod script_package_commands;
 
 pub use cluster_profile_commands::*;
 pub use cluster_profile_registry::*;
 pub use gvk_commands::*;
+pub use script_package_commands::*;
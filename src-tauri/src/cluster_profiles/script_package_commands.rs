@@ -0,0 +1,35 @@
+use crate::{
+    app_state::ManagerExt,
+    persistence::cluster_profile_service::{self, ClusterProfileService},
+    scripting::scripts_provider::{self, PackageId, PackageSource, ScriptPackage, ScriptsProvider},
+};
+
+use super::cluster_profile_registry::ClusterProfileId;
+
+#[tauri::command]
+pub fn cluster_profile_list_script_packages(
+    app: tauri::AppHandle,
+    profile: ClusterProfileId,
+) -> Result<Vec<ScriptPackage>, scripts_provider::Error> {
+    let scripts_provider = app.state::<ScriptsProvider>();
+    scripts_provider.list_packages(&profile)
+}
+
+#[tauri::command]
+pub fn cluster_profile_set_script_package_enabled(
+    app: tauri::AppHandle,
+    profile: ClusterProfileId,
+    source: PackageSource,
+    package: String,
+    enabled: bool,
+) -> Result<(), cluster_profile_service::Error> {
+    let service = app.state::<ClusterProfileService>();
+    service.set_script_package_enabled(
+        &profile,
+        PackageId {
+            source,
+            name: package,
+        },
+        enabled,
+    )
+}
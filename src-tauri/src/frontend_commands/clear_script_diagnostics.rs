@@ -0,0 +1,19 @@
+use crate::{
+    app_state::{ClusterStateRegistry, ManagerExt as _},
+    frontend_commands::KubeContextSource,
+    frontend_types::BackendError,
+};
+
+#[tauri::command]
+#[tracing::instrument(skip_all, fields(request_id = tracing::field::Empty))]
+pub async fn clear_script_diagnostics(
+    app: tauri::AppHandle,
+    context_source: KubeContextSource,
+) -> Result<(), BackendError> {
+    crate::internal::tracing::set_span_request_id();
+
+    let clusters = app.state::<ClusterStateRegistry>();
+    clusters.diagnostics_for(&context_source)?.clear();
+
+    Ok(())
+}
@@ -2,6 +2,7 @@ use kube::api::GroupVersionKind;
 
 use crate::{
     app_state::{ClusterStateRegistry, ManagerExt as _},
+    cluster_discovery::ClusterDiscovery,
     frontend_commands::KubeContextSource,
     frontend_types::BackendError,
 };
@@ -17,9 +18,14 @@ pub async fn list_resource_presentations(
     let clusters = app.state::<ClusterStateRegistry>();
     let gvk = GroupVersionKind::gvk(group, version, kind);
 
+    let is_crd = match &*clusters.discovery_for(&context_source)? {
+        ClusterDiscovery::Inflight(_) => false,
+        ClusterDiscovery::Completed(resources) => resources.crds.contains_key(&gvk),
+    };
+
     let renderers = {
         let views = clusters.presentation_scripting_for(&context_source)?;
-        views.get_renderers(&context_source, &gvk)?
+        views.get_renderers(&gvk, is_crd)?
     };
 
     Ok(renderers)
@@ -0,0 +1,75 @@
+use std::path::PathBuf;
+
+use crate::{
+    app_state::ManagerExt as _,
+    frontend_types::BackendError,
+    scripting::{
+        package_installer::{self, PackageInstaller},
+        package_manifest::PackageManifest,
+        scripts_provider::ScriptsProvider,
+    },
+};
+
+// Clusters that are already connected pick up the changes through their script watcher.
+
+#[tauri::command]
+#[tracing::instrument(skip_all, fields(request_id = tracing::field::Empty))]
+pub async fn install_script_package(
+    app: tauri::AppHandle,
+    source: PathBuf,
+) -> Result<PackageManifest, BackendError> {
+    crate::internal::tracing::set_span_request_id();
+
+    let manifest = run_installer(&app, move |installer| installer.install(&source)).await?;
+    tracing::info!("Installed {} {}", manifest.name, manifest.version);
+
+    Ok(manifest)
+}
+
+#[tauri::command]
+#[tracing::instrument(skip_all, fields(request_id = tracing::field::Empty))]
+pub async fn upgrade_script_package(
+    app: tauri::AppHandle,
+    source: PathBuf,
+) -> Result<PackageManifest, BackendError> {
+    crate::internal::tracing::set_span_request_id();
+
+    let manifest = run_installer(&app, move |installer| installer.upgrade(&source)).await?;
+    tracing::info!("Upgraded {} to {}", manifest.name, manifest.version);
+
+    Ok(manifest)
+}
+
+#[tauri::command]
+#[tracing::instrument(skip_all, fields(request_id = tracing::field::Empty))]
+pub async fn uninstall_script_package(
+    app: tauri::AppHandle,
+    name: String,
+) -> Result<(), BackendError> {
+    crate::internal::tracing::set_span_request_id();
+
+    let name = run_installer(&app, move |installer| {
+        installer.uninstall(&name).map(|()| name)
+    })
+    .await?;
+    tracing::info!("Uninstalled {name}");
+
+    Ok(())
+}
+
+/// Runs an installer operation on the blocking pool, it unpacks archives, copies directories and compiles
+/// all scripts of the package.
+async fn run_installer<T: Send + 'static>(
+    app: &tauri::AppHandle,
+    operation: impl FnOnce(PackageInstaller) -> Result<T, package_installer::Error> + Send + 'static,
+) -> Result<T, BackendError> {
+    let extensions_dir = app
+        .state::<ScriptsProvider>()
+        .get_extension_packages_dir()
+        .map_err(package_installer::Error::from)?;
+    let installer = PackageInstaller::new(extensions_dir);
+
+    Ok(tokio::task::spawn_blocking(move || operation(installer))
+        .await
+        .map_err(|e| BackendError::Generic(format!("package task failed: {e}")))??)
+}
@@ -1,23 +1,33 @@
 mod apply_resource_yaml;
 mod cleanup_channel;
+mod clear_script_diagnostics;
 mod delete_resource;
 mod discover_contexts;
 mod get_resource_yaml;
 mod kube_stream_podlogs;
 mod list_resource_presentations;
 mod logging;
+mod manage_script_packages;
 mod pod_exec;
+mod render_resource_details;
+mod watch_dashboard;
 mod watch_gvk_with_presentation;
 mod watch_namespaces;
+mod watch_script_diagnostics;
 
 pub use apply_resource_yaml::*;
 pub use cleanup_channel::*;
+pub use clear_script_diagnostics::*;
 pub use delete_resource::*;
 pub use discover_contexts::*;
 pub use get_resource_yaml::*;
 pub use kube_stream_podlogs::*;
 pub use list_resource_presentations::*;
 pub use logging::*;
+pub use manage_script_packages::*;
 pub use pod_exec::*;
+pub use render_resource_details::*;
+pub use watch_dashboard::*;
 pub use watch_gvk_with_presentation::*;
 pub use watch_namespaces::*;
+pub use watch_script_diagnostics::*;
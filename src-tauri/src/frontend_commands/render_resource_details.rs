@@ -0,0 +1,117 @@
+use k8s_openapi::api::core::v1::Event;
+use kube::api::{DynamicObject, ListParams};
+
+use crate::{
+    app_state::{ClusterStateRegistry, ManagerExt as _},
+    frontend_commands::KubeContextSource,
+    frontend_types::BackendError,
+    scripting::{
+        resource_details_facade::RenderedDetails,
+        types::resource_details::{EventRow, SectionComponent},
+    },
+};
+
+/// Renders the scripted details panels of a single resource.
+#[tauri::command]
+#[tracing::instrument(skip_all, fields(request_id = tracing::field::Empty))]
+pub async fn render_resource_details(
+    app: tauri::AppHandle,
+    context_source: KubeContextSource,
+    gvk: kube::api::GroupVersionKind,
+    namespace: &str,
+    name: &str,
+) -> Result<Vec<RenderedDetails>, BackendError> {
+    crate::internal::tracing::set_span_request_id();
+
+    let clusters = app.state::<ClusterStateRegistry>();
+    let facade = clusters.details_scripting_for(&context_source)?;
+    let discovery = clusters.discovery_cache_for(&context_source)?;
+    let client = clusters.client_for(&context_source)?;
+
+    let (api_resource, capabilities) = discovery
+        .resolve_gvk(&gvk)
+        .ok_or("GroupVersionKind not found")?;
+
+    let api = match capabilities.scope {
+        kube::discovery::Scope::Cluster => {
+            kube::Api::<DynamicObject>::all_with(client.clone(), &api_resource)
+        }
+        kube::discovery::Scope::Namespaced => match namespace {
+            "" => {
+                return Err(BackendError::Generic(format!(
+                    "{} is namespaced, a namespace is required",
+                    api_resource.kind
+                )))
+            }
+            namespace => kube::Api::namespaced_with(client.clone(), namespace, &api_resource),
+        },
+    };
+
+    let obj = api.get(name).await?;
+    let uid = obj.metadata.uid.clone();
+
+    // Each section may run its script up to the render time limit, keep that off the core runtime workers.
+    let mut rendered = tokio::task::spawn_blocking(move || facade.render(&gvk, &obj))
+        .await
+        .map_err(|e| BackendError::Generic(format!("details render task failed: {e}")))??;
+
+    let event_lists = rendered
+        .iter_mut()
+        .flat_map(|details| &mut details.sections)
+        .filter_map(|section| match &mut section.component {
+            Some(SectionComponent::EventList(events)) => Some(events),
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+
+    if let (false, Some(uid)) = (event_lists.is_empty(), uid) {
+        let events = list_events(client, namespace, &uid).await?;
+
+        for event_list in event_lists {
+            event_list.events = events.iter().take(event_list.max).cloned().collect();
+        }
+    }
+
+    Ok(rendered)
+}
+
+/// The events of the object with the given UID, most recent first.
+async fn list_events(
+    client: kube::Client,
+    namespace: &str,
+    uid: &str,
+) -> Result<Vec<EventRow>, BackendError> {
+    let api = match namespace {
+        "" => kube::Api::<Event>::all(client),
+        namespace => kube::Api::namespaced(client, namespace),
+    };
+
+    let mut events = api
+        .list(&ListParams::default().fields(&format!("involvedObject.uid={uid}")))
+        .await?
+        .items;
+
+    events.sort_by_key(|event| {
+        std::cmp::Reverse(
+            event
+                .last_timestamp
+                .as_ref()
+                .map(|time| time.0)
+                .or(event.event_time.as_ref().map(|time| time.0)),
+        )
+    });
+
+    Ok(events
+        .into_iter()
+        .map(|event| EventRow {
+            kind: event.type_,
+            reason: event.reason,
+            message: event.message,
+            count: event.count,
+            last_seen: event
+                .last_timestamp
+                .map(|time| time.0.to_string())
+                .or(event.event_time.map(|time| time.0.to_string())),
+        })
+        .collect())
+}
@@ -0,0 +1,249 @@
+use std::{
+    collections::{BTreeMap, HashMap, HashSet},
+    sync::Arc,
+    time::Duration,
+};
+
+use futures::StreamExt as _;
+use kube::api::{DynamicObject, GroupVersionKind};
+use serde::Serialize;
+use tokio::sync::broadcast;
+use tracing::{error, info, warn};
+
+use crate::{
+    app_state::{ChannelTasks, ClusterStateRegistry, ManagerExt},
+    frontend_commands::KubeContextSource,
+    frontend_types::BackendError,
+    internal::resources::ResourceWatchStreamEvent,
+    scripting::{
+        dashboard_facade::{DashboardFacade, WidgetDefinition},
+        types::dashboards::WidgetComponent,
+    },
+};
+
+/// Widgets are rendered at most this often, a burst of resource changes results in a single update.
+const RENDER_INTERVAL: Duration = Duration::from_secs(1);
+
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase", tag = "event", content = "data")]
+pub enum DashboardEvent {
+    AnnounceWidgets {
+        widgets: Vec<WidgetDefinition>,
+    },
+    WidgetUpdated {
+        id: String,
+        component: Option<WidgetComponent>,
+        error: Option<String>,
+    },
+}
+
+impl DashboardEvent {
+    fn widget_updated(id: String, result: Result<WidgetComponent, String>) -> Self {
+        match result {
+            Ok(component) => Self::WidgetUpdated {
+                id,
+                component: Some(component),
+                error: None,
+            },
+            Err(error) => Self::WidgetUpdated {
+                id,
+                component: None,
+                error: Some(error),
+            },
+        }
+    }
+}
+
+/// All currently known resources of one kind, by UID.
+type ResourceSet = BTreeMap<String, rhai::Dynamic>;
+
+/// Streams the scripted dashboard of a cluster.
+///
+/// Every resource kind that a widget summarizes is watched cluster-wide once, no matter how many widgets use it.
+/// Widgets are re-rendered when the resources of their kind changed and sent only if their content differs from
+/// what has been sent before. After dashboard scripts have been reloaded, the widgets are announced again.
+#[tauri::command]
+#[tracing::instrument(skip_all, fields(request_id = tracing::field::Empty))]
+pub async fn watch_dashboard(
+    app: tauri::AppHandle,
+    context_source: KubeContextSource,
+    channel: tauri::ipc::Channel<DashboardEvent>,
+) -> Result<(), BackendError> {
+    crate::internal::tracing::set_span_request_id();
+
+    let clusters = app.state::<ClusterStateRegistry>();
+    let channel_tasks = app.state::<ChannelTasks>();
+
+    let channel_id = channel.id();
+    info!("Streaming dashboard to channel {channel_id}");
+
+    let dashboards = clusters.dashboard_scripting_for(&context_source)?;
+    let client = clusters.client_for(&context_source)?;
+
+    let stream = async move {
+        let mut reloads = dashboards.subscribe();
+
+        loop {
+            let widgets = dashboards.widgets();
+
+            if let Err(error) = channel.send(DashboardEvent::AnnounceWidgets {
+                widgets: widgets.clone(),
+            }) {
+                error!("error sending to channel: {error}");
+                return;
+            }
+
+            let mut sources: Vec<GroupVersionKind> = Vec::new();
+            for widget in &widgets {
+                if !sources.contains(&widget.gvk) {
+                    sources.push(widget.gvk.clone());
+                }
+            }
+
+            let mut watches = Vec::new();
+            let mut watched: HashSet<GroupVersionKind> = HashSet::new();
+            for gvk in sources {
+                match kube::discovery::oneshot::pinned_kind(&client, &gvk).await {
+                    Ok((api_resource, _)) => {
+                        let api =
+                            kube::Api::<DynamicObject>::all_with(client.clone(), &api_resource);
+                        let events = crate::internal::resources::watch(api).await.map({
+                            let gvk = gvk.clone();
+                            move |event| (gvk.clone(), event)
+                        });
+
+                        watches.push(events.boxed());
+                        watched.insert(gvk);
+                    }
+                    Err(e) => {
+                        warn!("Cannot watch {gvk:?} for the dashboard: {e}");
+
+                        for widget in widgets.iter().filter(|widget| widget.gvk == gvk) {
+                            let event = DashboardEvent::widget_updated(
+                                widget.id.clone(),
+                                Err(format!("Cannot watch {}: {e}", gvk.kind)),
+                            );
+
+                            if let Err(error) = channel.send(event) {
+                                error!("error sending to channel: {error}");
+                            }
+                        }
+                    }
+                }
+            }
+
+            let mut resource_events = futures::stream::select_all(watches);
+            let mut resource_sets: HashMap<GroupVersionKind, ResourceSet> = HashMap::new();
+            let mut last_sent: HashMap<String, serde_json::Value> = HashMap::new();
+
+            // Resources without any instances still need their widgets rendered once. Widgets of kinds that cannot
+            // be watched keep showing their error instead.
+            let mut dirty = watched;
+
+            let mut renders = tokio::time::interval_at(
+                tokio::time::Instant::now() + RENDER_INTERVAL,
+                RENDER_INTERVAL,
+            );
+
+            loop {
+                tokio::select! {
+                    Some((gvk, event)) = resource_events.next() => {
+                        let resources = resource_sets.entry(gvk.clone()).or_default();
+
+                        match event {
+                            ResourceWatchStreamEvent::Applied { resource } => {
+                                let Some(uid) = resource.metadata.uid.clone() else {
+                                    continue;
+                                };
+
+                                match rhai::serde::to_dynamic(&resource) {
+                                    Ok(resource) => {
+                                        resources.insert(uid, resource);
+                                    }
+                                    Err(e) => warn!("Skipping {uid} on the dashboard: {e}"),
+                                }
+                            }
+                            ResourceWatchStreamEvent::Deleted { resource } => {
+                                if let Some(uid) = &resource.metadata.uid {
+                                    resources.remove(uid);
+                                }
+                            }
+                        }
+
+                        dirty.insert(gvk);
+                    }
+                    _ = renders.tick() => {
+                        if dirty.is_empty() {
+                            continue;
+                        }
+
+                        let pending = widgets
+                            .iter()
+                            .filter(|widget| dirty.contains(&widget.gvk))
+                            .map(|widget| {
+                                let objects = resource_sets
+                                    .get(&widget.gvk)
+                                    .map(|resources| resources.values().cloned().collect())
+                                    .unwrap_or_default();
+
+                                (widget.id.clone(), objects)
+                            })
+                            .collect();
+                        dirty.clear();
+
+                        for event in render_widgets(Arc::clone(&dashboards), pending).await {
+                            let DashboardEvent::WidgetUpdated { id, .. } = &event else {
+                                continue;
+                            };
+
+                            let Ok(content) = serde_json::to_value(&event) else {
+                                continue;
+                            };
+
+                            if last_sent.get(id) == Some(&content) {
+                                continue;
+                            }
+                            last_sent.insert(id.clone(), content);
+
+                            if let Err(error) = channel.send(event) {
+                                error!("error sending to channel: {error}");
+                                return;
+                            }
+                        }
+                    }
+                    Ok(()) | Err(broadcast::error::RecvError::Lagged(_)) = reloads.recv() => {
+                        info!("Dashboard scripts reloaded, restarting dashboard on channel {channel_id}");
+                        break;
+                    }
+                }
+            }
+        }
+    };
+
+    channel_tasks.submit(channel_id, stream)?;
+
+    Ok(())
+}
+
+/// Renders each widget from the given resources, widgets that no longer exist are left out.
+async fn render_widgets(
+    dashboards: Arc<DashboardFacade>,
+    pending: Vec<(String, rhai::Array)>,
+) -> Vec<DashboardEvent> {
+    // Scripts run synchronously up to their time limit, keep them off the core runtime workers.
+    let rendered = tokio::task::spawn_blocking(move || {
+        pending
+            .into_iter()
+            .filter_map(|(id, objects)| {
+                let result = dashboards.render_widget(&id, &objects)?;
+                Some(DashboardEvent::widget_updated(id, result))
+            })
+            .collect()
+    })
+    .await;
+
+    rendered.unwrap_or_else(|e| {
+        error!("Dashboard render task failed: {e}");
+        Vec::new()
+    })
+}
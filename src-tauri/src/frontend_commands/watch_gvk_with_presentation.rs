@@ -4,7 +4,8 @@ use futures::StreamExt as _;
 use k8s_openapi::apiextensions_apiserver::pkg::apis::apiextensions::v1::CustomResourceDefinition;
 use kube::api::{DynamicObject, GroupVersionKind};
 use serde::Serialize;
-use tracing::{error, info};
+use tokio::sync::broadcast;
+use tracing::{error, info, warn};
 
 use crate::{
     app_state::{ChannelTasks, ClusterStateRegistry, ManagerExt},
@@ -12,8 +13,12 @@ use crate::{
     frontend_commands::KubeContextSource,
     frontend_types::BackendError,
     internal::resources::ResourceWatchStreamEvent,
-    resource_rendering::ResourceColumnDefinition,
-    scripting::types::resource_presentations::PresentationComponent,
+    resource_rendering::{FallbackRenderer, ResourceColumnDefinition, ResourceRenderer},
+    scripting::{
+        diagnostics::{DiagnosticKind, ScriptDiagnostic, ScriptDiagnostics},
+        resource_presentation_facade::ResourcePresentationFacade,
+        types::resource_presentations::PresentationComponent,
+    },
 };
 
 #[derive(Clone, Serialize)]
@@ -36,6 +41,13 @@ pub enum ResourceEvent {
     },
 }
 
+enum WatchInput {
+    Resource(Box<ResourceWatchStreamEvent<DynamicObject>>),
+    MetricsRefreshed,
+    RelatedResourcesResolved,
+    PresentationsReloaded,
+}
+
 #[allow(clippy::too_many_arguments)]
 #[tauri::command]
 #[tracing::instrument(skip_all, fields(request_id = tracing::field::Empty))]
@@ -72,53 +84,214 @@ pub async fn watch_gvk_with_presentation(
     };
 
     let views = clusters.presentation_scripting_for(&context_source)?;
+    let metrics = clusters.metrics_for(&context_source)?;
+    let related = clusters.related_for(&context_source)?;
+    let diagnostics = clusters.diagnostics_for(&context_source)?;
+    let shows_metrics = gvk.group.is_empty() && (gvk.kind == "Pod" || gvk.kind == "Node");
 
     let stream = async move {
-        let view = views.get_renderer(&gvk, presentation_name.as_str()).await;
-
         let crds: HashMap<GroupVersionKind, CustomResourceDefinition> = match &*discovery {
-            ClusterDiscovery::Inflight(inflight) => inflight.block_until_done().await.unwrap().crds,
+            ClusterDiscovery::Inflight(inflight) => match inflight.block_until_done().await {
+                Ok(discovery) => discovery.crds,
+                Err(e) => {
+                    warn!("Discovery failed, rendering without CRDs: {e}");
+                    HashMap::new()
+                }
+            },
             ClusterDiscovery::Completed(resources) => resources.crds.clone(),
         };
 
         let crd = crds.get(&gvk);
-        let column_definitions = view.column_definitions(&gvk, crd).unwrap();
 
-        channel
-            .send(ResourceEvent::AnnounceColumns {
-                columns: column_definitions,
-            })
-            .unwrap();
+        let (mut view, column_definitions) =
+            resolve_view(&views, &gvk, &presentation_name, crd, &diagnostics).await;
+        let mut column_count = column_definitions.len();
 
-        crate::internal::resources::watch(api)
+        if let Err(error) = channel.send(ResourceEvent::AnnounceColumns {
+            columns: column_definitions,
+        }) {
+            error!("error sending to channel: {error}");
+            return;
+        }
+
+        let resource_events = crate::internal::resources::watch(api)
             .await
-            .map(|event| match event {
-                ResourceWatchStreamEvent::Applied { resource } => ResourceEvent::Applied {
-                    uid: resource.metadata.uid.clone().expect("no uid"),
-                    namespace: resource.metadata.namespace.clone().unwrap_or_default(),
-                    name: resource.metadata.name.clone().unwrap_or_default(),
-                    columns: view
-                        .render(&gvk, crd, &resource)
-                        .unwrap()
-                        .into_iter()
-                        .map(|value| value.map(|inner| inner.into()))
-                        .collect(),
-                },
-                ResourceWatchStreamEvent::Deleted { resource } => ResourceEvent::Deleted {
-                    uid: resource.metadata.uid.expect("no uid"),
-                    namespace: resource.metadata.namespace.unwrap_or_default(),
-                    name: resource.metadata.name.unwrap_or_default(),
+            .map(|event| WatchInput::Resource(Box::new(event)));
+
+        // Usage columns depend on data that is not part of the resource itself, so resources are
+        // re-rendered whenever a new metrics sample arrives.
+        let metrics_refreshes = async_stream::stream! {
+            if !shows_metrics {
+                return;
+            }
+
+            let mut updates = metrics.subscribe();
+
+            while let Ok(()) | Err(broadcast::error::RecvError::Lagged(_)) = updates.recv().await {
+                yield WatchInput::MetricsRefreshed;
+            }
+        };
+
+        // Related objects are looked up without waiting for them, rows that asked for one are
+        // rendered again once it has been fetched.
+        let mut resolved = related.subscribe();
+        let related_resolutions = async_stream::stream! {
+            while let Ok(()) | Err(broadcast::error::RecvError::Lagged(_)) = resolved.recv().await {
+                yield WatchInput::RelatedResourcesResolved;
+            }
+        };
+
+        let mut reloads = views.subscribe();
+        let presentation_reloads = async_stream::stream! {
+            while let Ok(()) | Err(broadcast::error::RecvError::Lagged(_)) = reloads.recv().await {
+                yield WatchInput::PresentationsReloaded;
+            }
+        };
+
+        let mut known_resources: HashMap<String, DynamicObject> = HashMap::new();
+
+        let mut inputs = std::pin::pin!(futures::stream::select(
+            futures::stream::select(resource_events, metrics_refreshes),
+            futures::stream::select(related_resolutions, presentation_reloads)
+        ));
+
+        while let Some(input) = inputs.next().await {
+            let render = |resource: &DynamicObject| {
+                render_resource(
+                    view.as_ref(),
+                    column_count,
+                    &gvk,
+                    crd,
+                    &diagnostics,
+                    resource,
+                )
+            };
+
+            let frontend_events = match input {
+                WatchInput::Resource(event) => match *event {
+                    ResourceWatchStreamEvent::Applied { resource } => {
+                        let event = render(&resource);
+
+                        if let Some(uid) = &resource.metadata.uid {
+                            known_resources.insert(uid.clone(), resource);
+                        }
+
+                        event.into_iter().collect()
+                    }
+                    ResourceWatchStreamEvent::Deleted { resource } => {
+                        let Some(uid) = resource.metadata.uid else {
+                            continue;
+                        };
+                        known_resources.remove(&uid);
+
+                        vec![ResourceEvent::Deleted {
+                            uid,
+                            namespace: resource.metadata.namespace.unwrap_or_default(),
+                            name: resource.metadata.name.unwrap_or_default(),
+                        }]
+                    }
                 },
-            })
-            .for_each(|frontend_event| async {
+                WatchInput::MetricsRefreshed | WatchInput::RelatedResourcesResolved => {
+                    known_resources.values().filter_map(render).collect()
+                }
+                WatchInput::PresentationsReloaded => {
+                    let columns;
+                    (view, columns) =
+                        resolve_view(&views, &gvk, &presentation_name, crd, &diagnostics).await;
+                    column_count = columns.len();
+
+                    std::iter::once(ResourceEvent::AnnounceColumns { columns })
+                        .chain(known_resources.values().filter_map(|resource| {
+                            render_resource(
+                                view.as_ref(),
+                                column_count,
+                                &gvk,
+                                crd,
+                                &diagnostics,
+                                resource,
+                            )
+                        }))
+                        .collect()
+                }
+            };
+
+            for frontend_event in frontend_events {
                 if let Err(error) = channel.send(frontend_event) {
                     error!("error sending to channel: {error}")
                 }
-            })
-            .await;
+            }
+        }
     };
 
     channel_tasks.submit(channel_id, stream)?;
 
     Ok(())
 }
+
+/// Looks up the renderer of a presentation and its columns.
+///
+/// A presentation that cannot even describe its columns is replaced by the simple list, so that the
+/// resources are still visible.
+async fn resolve_view(
+    views: &ResourcePresentationFacade,
+    gvk: &GroupVersionKind,
+    presentation_name: &str,
+    crd: Option<&CustomResourceDefinition>,
+    diagnostics: &ScriptDiagnostics,
+) -> (Box<dyn ResourceRenderer>, Vec<ResourceColumnDefinition>) {
+    let view = views.get_renderer(gvk, presentation_name).await;
+
+    match view.column_definitions(gvk, crd) {
+        Ok(columns) => (view, columns),
+        Err(e) => {
+            diagnostics.report(
+                ScriptDiagnostic::new(DiagnosticKind::Render, e.to_string())
+                    .with_presentation(view.display_name()),
+            );
+
+            let fallback: Box<dyn ResourceRenderer> = Box::new(FallbackRenderer {});
+            let columns = fallback.column_definitions(gvk, crd).unwrap_or_default();
+
+            (fallback, columns)
+        }
+    }
+}
+
+/// Cell failures are reported by the renderer itself, failures here concern the whole row.
+///
+/// A failed row is still sent, with the error in each of its `column_count` cells, so that the table
+/// does not keep showing its last values as if they were current.
+fn render_resource(
+    view: &dyn ResourceRenderer,
+    column_count: usize,
+    gvk: &GroupVersionKind,
+    crd: Option<&CustomResourceDefinition>,
+    diagnostics: &ScriptDiagnostics,
+    resource: &DynamicObject,
+) -> Option<ResourceEvent> {
+    let uid = resource.metadata.uid.clone()?;
+
+    let columns = match view.render(gvk, crd, resource) {
+        Ok(columns) => columns,
+        Err(e) => {
+            let diagnostic = ScriptDiagnostic::new(DiagnosticKind::Render, e.to_string())
+                .with_presentation(view.display_name())
+                .with_resource_uid(Some(uid.clone()));
+
+            let message = diagnostic.to_string();
+            diagnostics.report(diagnostic);
+
+            vec![Err(message); column_count]
+        }
+    };
+
+    Some(ResourceEvent::Applied {
+        uid,
+        namespace: resource.metadata.namespace.clone().unwrap_or_default(),
+        name: resource.metadata.name.clone().unwrap_or_default(),
+        columns: columns
+            .into_iter()
+            .map(|value| value.map(|inner| inner.into()))
+            .collect(),
+    })
+}
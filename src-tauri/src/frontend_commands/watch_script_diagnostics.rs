@@ -0,0 +1,53 @@
+use tokio::sync::broadcast::error::RecvError;
+use tracing::{error, info, warn};
+
+use crate::{
+    app_state::{ChannelTasks, ClusterStateRegistry, ManagerExt as _},
+    frontend_commands::KubeContextSource,
+    frontend_types::BackendError,
+    scripting::diagnostics::ScriptDiagnostic,
+};
+
+#[tauri::command]
+#[tracing::instrument(skip_all, fields(request_id = tracing::field::Empty))]
+pub async fn watch_script_diagnostics(
+    app: tauri::AppHandle,
+    context_source: KubeContextSource,
+    channel: tauri::ipc::Channel<ScriptDiagnostic>,
+) -> Result<(), BackendError> {
+    crate::internal::tracing::set_span_request_id();
+
+    let clusters = app.state::<ClusterStateRegistry>();
+    let channel_tasks = app.state::<ChannelTasks>();
+
+    let channel_id = channel.id();
+    info!("Streaming script diagnostics to channel {channel_id}");
+
+    let (history, mut diagnostics) = clusters.diagnostics_for(&context_source)?.subscribe();
+
+    let stream = async move {
+        for diagnostic in history {
+            if let Err(error) = channel.send(diagnostic) {
+                error!("error sending to channel: {error}")
+            }
+        }
+
+        loop {
+            match diagnostics.recv().await {
+                Ok(diagnostic) => {
+                    if let Err(error) = channel.send(diagnostic) {
+                        error!("error sending to channel: {error}")
+                    }
+                }
+                Err(RecvError::Lagged(skipped)) => {
+                    warn!("Dropped {skipped} script diagnostics, the frontend is too slow")
+                }
+                Err(RecvError::Closed) => break,
+            }
+        }
+    };
+
+    channel_tasks.submit(channel_id, stream)?;
+
+    Ok(())
+}
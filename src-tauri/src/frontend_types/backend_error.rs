@@ -3,8 +3,7 @@ use crate::{
     frontend_commands::KubeContextSource,
     persistence::discovery_cache_service,
     scripting::{
-        resource_context_menu_facade::ResourceContextMenuError,
-        resource_presentation_facade::ResourcePresentationError,
+        package_installer, resource_context_menu_facade::ResourceContextMenuError, script_registry,
     },
 };
 
@@ -35,7 +34,10 @@ pub enum BackendError {
     ResourceContextMenu(#[from] ResourceContextMenuError),
 
     #[error(transparent)]
-    ResourcePresentationError(#[from] ResourcePresentationError),
+    ScriptError(#[from] script_registry::Error),
+
+    #[error(transparent)]
+    ScriptPackage(#[from] package_installer::Error),
 
     #[error("{0}")]
     Generic(String),
@@ -1,4 +1,5 @@
 pub mod gvk_extraction;
 pub mod mini_id;
+pub mod quantity;
 pub mod resources;
 pub mod tracing;
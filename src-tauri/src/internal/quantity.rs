@@ -0,0 +1,165 @@
+//! Parsing of Kubernetes resource quantities (e.g. `250m`, `1.5`, `128Mi`, `1G`) into plain integers.
+//!
+//! CPU quantities are normalized to millicores and memory quantities to bytes, which is precise enough
+//! for displaying usage and comparing it against requests and limits.
+
+#[derive(thiserror::Error, Debug, PartialEq)]
+pub enum QuantityError {
+    #[error("Empty quantity")]
+    Empty,
+
+    #[error("Invalid quantity: {0}")]
+    Invalid(String),
+}
+
+/// Parses a CPU quantity into millicores, e.g. `"250m"` -> `250`, `"2"` -> `2000`, `"1500000n"` -> `2`.
+pub fn parse_cpu_millis(quantity: &str) -> Result<i64, QuantityError> {
+    let value = parse_quantity(quantity)?;
+
+    Ok((value * 1000.0).ceil() as i64)
+}
+
+/// Parses a memory quantity into bytes, e.g. `"128Mi"` -> `134217728`, `"1G"` -> `1000000000`.
+pub fn parse_memory_bytes(quantity: &str) -> Result<i64, QuantityError> {
+    let value = parse_quantity(quantity)?;
+
+    Ok(value.ceil() as i64)
+}
+
+/// Formats millicores the way `kubectl top` does, e.g. `250` -> `"250m"`, `2000` -> `"2"`.
+pub fn format_cpu_millis(millis: i64) -> String {
+    if millis % 1000 == 0 {
+        format!("{}", millis / 1000)
+    } else {
+        format!("{millis}m")
+    }
+}
+
+/// Formats bytes using the largest binary suffix that keeps the value readable, e.g. `134217728` -> `"128Mi"`.
+pub fn format_memory_bytes(bytes: i64) -> String {
+    const SUFFIXES: [&str; 5] = ["Ki", "Mi", "Gi", "Ti", "Pi"];
+
+    let mut value = bytes as f64;
+    let mut suffix = "";
+
+    for candidate in SUFFIXES {
+        if value.abs() < 1024.0 {
+            break;
+        }
+
+        value /= 1024.0;
+        suffix = candidate;
+    }
+
+    if value.fract() == 0.0 {
+        format!("{value}{suffix}")
+    } else {
+        format!("{value:.1}{suffix}")
+    }
+}
+
+fn parse_quantity(quantity: &str) -> Result<f64, QuantityError> {
+    let quantity = quantity.trim();
+
+    if quantity.is_empty() {
+        return Err(QuantityError::Empty);
+    }
+
+    let invalid = || QuantityError::Invalid(quantity.to_owned());
+
+    // Decimal exponent notation, e.g. "1e3" or "1.5E-3". A trailing "E" or "Ei" is a suffix instead.
+    if let Some(position) = quantity.find(['e', 'E']) {
+        let (mantissa, exponent) = quantity.split_at(position);
+
+        if is_signed_integer(&exponent[1..]) {
+            let mantissa: f64 = mantissa.parse().map_err(|_| invalid())?;
+            let exponent: i32 = exponent[1..].parse().map_err(|_| invalid())?;
+
+            return Ok(mantissa * 10f64.powi(exponent));
+        }
+    }
+
+    let split = quantity
+        .find(|c: char| !(c.is_ascii_digit() || c == '.' || c == '+' || c == '-'))
+        .unwrap_or(quantity.len());
+
+    let (number, suffix) = quantity.split_at(split);
+    let number: f64 = number.parse().map_err(|_| invalid())?;
+
+    let multiplier = match suffix {
+        "" => 1.0,
+        "n" => 1e-9,
+        "u" => 1e-6,
+        "m" => 1e-3,
+        "k" => 1e3,
+        "M" => 1e6,
+        "G" => 1e9,
+        "T" => 1e12,
+        "P" => 1e15,
+        "E" => 1e18,
+        "Ki" => 1024.0,
+        "Mi" => 1024.0 * 1024.0,
+        "Gi" => 1024.0 * 1024.0 * 1024.0,
+        "Ti" => 1024.0 * 1024.0 * 1024.0 * 1024.0,
+        "Pi" => 1024.0 * 1024.0 * 1024.0 * 1024.0 * 1024.0,
+        "Ei" => 1024.0 * 1024.0 * 1024.0 * 1024.0 * 1024.0 * 1024.0,
+        _ => return Err(invalid()),
+    };
+
+    Ok(number * multiplier)
+}
+
+fn is_signed_integer(value: &str) -> bool {
+    let digits = value.strip_prefix(['+', '-']).unwrap_or(value);
+
+    !digits.is_empty() && digits.chars().all(|c| c.is_ascii_digit())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_cpu_millis() {
+        assert_eq!(Ok(250), parse_cpu_millis("250m"));
+        assert_eq!(Ok(2000), parse_cpu_millis("2"));
+        assert_eq!(Ok(500), parse_cpu_millis("0.5"));
+        assert_eq!(Ok(2), parse_cpu_millis("1500000n"));
+        assert_eq!(Ok(1), parse_cpu_millis("250u"));
+    }
+
+    #[test]
+    fn test_parse_memory_bytes() {
+        assert_eq!(Ok(134217728), parse_memory_bytes("128Mi"));
+        assert_eq!(Ok(1000000000), parse_memory_bytes("1G"));
+        assert_eq!(Ok(1024), parse_memory_bytes("1Ki"));
+        assert_eq!(Ok(1000), parse_memory_bytes("1e3"));
+        assert_eq!(Ok(123), parse_memory_bytes("123"));
+    }
+
+    #[test]
+    fn test_parse_exponent_and_exa_suffixes() {
+        assert_eq!(Ok(1000), parse_memory_bytes("1e3"));
+        assert_eq!(Ok(1500), parse_memory_bytes("1.5E+3"));
+        assert_eq!(Ok(1_000_000_000_000_000_000), parse_memory_bytes("1E"));
+        assert_eq!(Ok(2 * 1024_i64.pow(6)), parse_memory_bytes("2Ei"));
+        assert!(parse_memory_bytes("1e").is_err());
+        assert!(parse_memory_bytes("1e3Ki").is_err());
+    }
+
+    #[test]
+    fn test_parse_err_on_invalid_input() {
+        assert_eq!(Err(QuantityError::Empty), parse_cpu_millis(""));
+        assert!(parse_cpu_millis("abc").is_err());
+        assert!(parse_memory_bytes("12Xi").is_err());
+    }
+
+    #[test]
+    fn test_format() {
+        assert_eq!("250m", format_cpu_millis(250));
+        assert_eq!("2", format_cpu_millis(2000));
+        assert_eq!("128Mi", format_memory_bytes(134217728));
+        assert_eq!("1.5Gi", format_memory_bytes(1610612736));
+        assert_eq!("512", format_memory_bytes(512));
+    }
+}
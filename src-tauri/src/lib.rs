@@ -3,6 +3,7 @@
 
 mod app_state;
 mod cluster_discovery;
+mod cluster_metrics;
 mod cluster_profiles;
 mod frontend_commands;
 mod frontend_types;
@@ -10,11 +11,15 @@ mod internal;
 mod logging;
 mod panic_handler;
 mod persistence;
+mod related_resources;
 mod resource_menu;
 mod resource_rendering;
 mod scripting;
 
-use app_state::{ChannelTasks, ExecSessions};
+#[cfg(any(test, feature = "script-test"))]
+pub use scripting::harness;
+
+use app_state::{ChannelTasks, ExecSessions, MenuPrompts};
 use persistence::cluster_profile_service::ClusterProfileService;
 use tauri::{async_runtime::spawn, Listener, Manager as _};
 use tracing::info;
@@ -44,6 +49,7 @@ pub fn run() {
 
             app.manage(ChannelTasks::build(app_handle.clone()));
             app.manage(ExecSessions::build(app_handle.clone()));
+            app.manage(MenuPrompts::build(app_handle.clone()));
             app.manage(ClusterStateRegistry::build(app_handle.clone()));
             app.manage(ScriptsProvider::build(app_handle.clone()));
             app.manage(Repository::build(app_handle.clone()));
@@ -68,10 +74,17 @@ pub fn run() {
             frontend_commands::kube_stream_podlogs,
             frontend_commands::watch_gvk_with_presentation,
             frontend_commands::watch_namespaces,
+            frontend_commands::watch_dashboard,
+            frontend_commands::watch_script_diagnostics,
+            frontend_commands::clear_script_diagnostics,
+            frontend_commands::install_script_package,
+            frontend_commands::upgrade_script_package,
+            frontend_commands::uninstall_script_package,
             frontend_commands::cleanup_channel,
             frontend_commands::discover_contexts,
             frontend_commands::delete_resource,
             frontend_commands::list_resource_presentations,
+            frontend_commands::render_resource_details,
             frontend_commands::pod_exec_start_session,
             frontend_commands::pod_exec_write_stdin,
             frontend_commands::pod_exec_abort_session,
@@ -87,12 +100,16 @@ pub fn run() {
             cluster_profiles::cluster_profile_list_hidden_gvks,
             cluster_profiles::get_default_namespace,
             cluster_profiles::set_default_namespace,
+            cluster_profiles::cluster_profile_list_script_packages,
+            cluster_profiles::cluster_profile_set_script_package_enabled,
             frontend_commands::log_stdout,
             crate::cluster_discovery::connect_cluster,
             crate::cluster_discovery::get_apiserver_gitversion,
             crate::resource_menu::create_resource_menustack,
+            crate::resource_menu::create_multi_resource_menustack,
             crate::resource_menu::drop_resource_menustack,
-            crate::resource_menu::call_menustack_action
+            crate::resource_menu::call_menustack_action,
+            crate::resource_menu::answer_menu_prompt
         ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
@@ -8,6 +8,7 @@ use crate::{
     app_state::{ManagedState, ManagerExt},
     cluster_profiles::ClusterProfileId,
     persistence::repository::{self, Repository},
+    scripting::scripts_provider::PackageId,
 };
 
 use super::ToFlatString as _;
@@ -27,6 +28,7 @@ impl ManagedState for ClusterProfileService {
 const PERISTENCE_KEY_PINNED_GVKS: &str = "pinned_gvks";
 const PERISTENCE_KEY_HIDDEN_GVKS: &str = "hidden_gvks";
 const PERISTENCE_KEY_DEFAULT_NAMESPACES: &str = "default_namespaces";
+const PERISTENCE_KEY_DISABLED_SCRIPT_PACKAGES: &str = "disabled_script_packages";
 
 impl ClusterProfileService {
     pub fn list_pinned_gvks(
@@ -168,6 +170,54 @@ impl ClusterProfileService {
             .map_err(self::Error::Repository)
     }
 
+    /// The script packages that must not be loaded for clusters of this profile.
+    pub fn list_disabled_script_packages(
+        &self,
+        profile: &ClusterProfileId,
+    ) -> Result<Vec<PackageId>, self::Error> {
+        let repository = self.app.state::<Repository>();
+
+        Ok(repository
+            .read_key(
+                &repository::Context::PerClusterProfile(profile.clone()),
+                PERISTENCE_KEY_DISABLED_SCRIPT_PACKAGES,
+            )?
+            .map(serde_json::from_value)
+            .transpose()?
+            .unwrap_or_default())
+    }
+
+    pub fn set_script_package_enabled(
+        &self,
+        profile: &ClusterProfileId,
+        package: PackageId,
+        enabled: bool,
+    ) -> Result<(), self::Error> {
+        let repository = self.app.state::<Repository>();
+        let mut disabled = self.list_disabled_script_packages(profile)?;
+
+        disabled.retain(|p| *p != package);
+
+        if !enabled {
+            disabled.push(package);
+        }
+
+        repository.set_key(
+            &repository::Context::PerClusterProfile(profile.clone()),
+            PERISTENCE_KEY_DISABLED_SCRIPT_PACKAGES,
+            serde_json::to_value(disabled)?,
+        )?;
+
+        self.app.emit(
+            "hyprkube://script-packages-changed",
+            ScriptPackagesChanged {
+                cluster_profile: profile.to_owned(),
+            },
+        )?;
+
+        Ok(())
+    }
+
     fn list_gvks(
         &self,
         profile: &ClusterProfileId,
@@ -244,6 +294,12 @@ struct PinnedGvksChanged {
     gvks: Vec<GroupVersionKind>,
 }
 
+#[derive(Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+struct ScriptPackagesChanged {
+    cluster_profile: ClusterProfileId,
+}
+
 #[derive(thiserror::Error, Debug)]
 pub enum Error {
     #[error(transparent)]
@@ -1,4 +1,4 @@
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
 
 use serde::Serialize;
 use tauri::{AppHandle, Wry};
@@ -15,13 +15,18 @@ pub enum Context {
 
 pub struct Repository {
     app: AppHandle,
+    /// Serializes the read-modify-write updates of script storage
+    pub(crate) script_storage_updates: Mutex<()>,
 }
 
 impl ManagedState for Repository {
     type WrappedState = Arc<Repository>;
 
     fn build(app: tauri::AppHandle) -> Self::WrappedState {
-        Arc::new(Self { app })
+        Arc::new(Self {
+            app,
+            script_storage_updates: Mutex::new(()),
+        })
     }
 }
 
@@ -0,0 +1,48 @@
+use std::sync::Arc;
+
+use crate::scripting::types::ResourceRef;
+
+/// Identifies a related object, e.g. the owner of a resource.
+///
+/// Cluster-scoped objects have no namespace.
+#[derive(Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct RelatedKey {
+    pub api_version: String,
+    pub kind: String,
+    pub namespace: Option<String>,
+    pub name: String,
+}
+
+impl RelatedKey {
+    pub fn new(api_version: &str, kind: &str, namespace: &str, name: &str) -> Self {
+        Self {
+            api_version: api_version.to_owned(),
+            kind: kind.to_owned(),
+            namespace: Some(namespace.to_owned()).filter(|namespace| !namespace.is_empty()),
+            name: name.to_owned(),
+        }
+    }
+}
+
+impl From<ResourceRef> for RelatedKey {
+    fn from(value: ResourceRef) -> Self {
+        Self {
+            api_version: value.api_version,
+            kind: value.kind,
+            namespace: value.namespace.filter(|namespace| !namespace.is_empty()),
+            name: value.name,
+        }
+    }
+}
+
+/// The state of a related object in the cache.
+#[derive(Clone, Debug)]
+pub enum Lookup {
+    /// The object has been requested, but not fetched yet
+    Pending,
+    Found(Arc<serde_json::Value>),
+    /// The object does not exist or could not be fetched
+    Missing,
+    /// The lookup itself is wrong, e.g. a namespaced kind without a namespace
+    Failed(String),
+}
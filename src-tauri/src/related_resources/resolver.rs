@@ -0,0 +1,282 @@
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    sync::{Arc, Weak},
+    time::Duration,
+};
+
+use futures::StreamExt as _;
+use kube::{
+    api::{DynamicObject, ListParams},
+    core::GroupVersion,
+    discovery::Scope,
+    Api,
+};
+use tauri::async_runtime::spawn;
+
+use crate::related_resources::{Lookup, RelatedKey, RelatedResources};
+
+/// Lookups arriving within this window end up in the same batch, e.g. all rows of a table
+const BATCH_WINDOW: Duration = Duration::from_millis(50);
+/// Interval in which an idle resolver checks whether its cache still exists
+const IDLE_CHECK_INTERVAL: Duration = Duration::from_secs(30);
+/// Objects of the same kind and namespace are listed instead of fetched one by one above this count
+const LIST_THRESHOLD: usize = 4;
+const MAX_CONCURRENT_REQUESTS: usize = 8;
+
+/// A single request to the cluster, resolving one or more related objects.
+#[derive(Debug, PartialEq)]
+enum Batch {
+    Get(RelatedKey),
+    List {
+        api_version: String,
+        kind: String,
+        namespace: Option<String>,
+        names: BTreeSet<String>,
+    },
+}
+
+impl RelatedResources {
+    /// Starts resolving queued lookups, including those queued before the cluster was discovered.
+    ///
+    /// Like the metrics poller, the resolver only holds a weak reference to the cache and stops
+    /// once the owning `ClusterState` is dropped.
+    pub fn start_resolving(
+        self: &Arc<Self>,
+        client: kube::Client,
+        discovery: Arc<kube::Discovery>,
+    ) {
+        let related = Arc::downgrade(self);
+        let wakeup = Arc::clone(&self.wakeup);
+
+        // Anything that was queued while waiting for discovery
+        wakeup.notify_one();
+
+        spawn(async move {
+            loop {
+                if tokio::time::timeout(IDLE_CHECK_INTERVAL, wakeup.notified())
+                    .await
+                    .is_ok()
+                {
+                    tokio::time::sleep(BATCH_WINDOW).await;
+                }
+
+                if !resolve_queued(&related, &client, &discovery).await {
+                    tracing::debug!("Related resource cache dropped, stopping resolver");
+                    break;
+                }
+            }
+        });
+    }
+}
+
+/// Resolves everything that is queued. Returns `false` if the cache is gone and resolving should stop.
+async fn resolve_queued(
+    related: &Weak<RelatedResources>,
+    client: &kube::Client,
+    discovery: &kube::Discovery,
+) -> bool {
+    let Some(queued) = related.upgrade().map(|related| related.take_queued()) else {
+        return false;
+    };
+
+    if queued.is_empty() {
+        return true;
+    }
+
+    let resolved: Vec<(RelatedKey, Lookup)> = futures::stream::iter(plan_batches(queued))
+        .map(|batch| resolve_batch(client, discovery, batch))
+        .buffer_unordered(MAX_CONCURRENT_REQUESTS)
+        .flat_map(futures::stream::iter)
+        .collect()
+        .await;
+
+    let Some(related) = related.upgrade() else {
+        return false;
+    };
+
+    related.complete(resolved);
+
+    true
+}
+
+/// Groups lookups by kind and namespace, so that e.g. the owners of all Pods in a namespace are
+/// listed once instead of fetched one by one.
+fn plan_batches(queued: BTreeSet<RelatedKey>) -> Vec<Batch> {
+    let mut groups: BTreeMap<(String, String, Option<String>), BTreeSet<String>> = BTreeMap::new();
+
+    for key in queued {
+        groups
+            .entry((key.api_version, key.kind, key.namespace))
+            .or_default()
+            .insert(key.name);
+    }
+
+    groups
+        .into_iter()
+        .flat_map(|((api_version, kind, namespace), names)| {
+            if names.len() > LIST_THRESHOLD {
+                return vec![Batch::List {
+                    api_version,
+                    kind,
+                    namespace,
+                    names,
+                }];
+            }
+
+            names
+                .into_iter()
+                .map(|name| {
+                    Batch::Get(RelatedKey {
+                        api_version: api_version.clone(),
+                        kind: kind.clone(),
+                        namespace: namespace.clone(),
+                        name,
+                    })
+                })
+                .collect()
+        })
+        .collect()
+}
+
+async fn resolve_batch(
+    client: &kube::Client,
+    discovery: &kube::Discovery,
+    batch: Batch,
+) -> Vec<(RelatedKey, Lookup)> {
+    match batch {
+        Batch::Get(key) => {
+            let lookup = match api_for(
+                client,
+                discovery,
+                &key.api_version,
+                &key.kind,
+                &key.namespace,
+            ) {
+                Ok(api) => match api.get_opt(&key.name).await {
+                    Ok(object) => found_or_missing(object),
+                    Err(e) => {
+                        tracing::warn!("Failed to fetch related {key:?}: {e}");
+                        Lookup::Missing
+                    }
+                },
+                Err(lookup) => lookup,
+            };
+
+            vec![(key, lookup)]
+        }
+        Batch::List {
+            api_version,
+            kind,
+            namespace,
+            names,
+        } => {
+            let (mut objects, failed) =
+                match api_for(client, discovery, &api_version, &kind, &namespace) {
+                    Ok(api) => match api.list(&ListParams::default()).await {
+                        Ok(list) => (
+                            list.items
+                                .into_iter()
+                                .filter_map(|object| Some((object.metadata.name.clone()?, object)))
+                                .collect(),
+                            None,
+                        ),
+                        Err(e) => {
+                            tracing::warn!("Failed to list related {kind} in {namespace:?}: {e}");
+                            (BTreeMap::new(), None)
+                        }
+                    },
+                    Err(lookup) => (BTreeMap::new(), Some(lookup)),
+                };
+
+            names
+                .into_iter()
+                .map(|name| {
+                    let lookup = match &failed {
+                        Some(failed) => failed.clone(),
+                        None => found_or_missing(objects.remove(&name)),
+                    };
+
+                    let key = RelatedKey {
+                        api_version: api_version.clone(),
+                        kind: kind.clone(),
+                        namespace: namespace.clone(),
+                        name,
+                    };
+
+                    (key, lookup)
+                })
+                .collect()
+        }
+    }
+}
+
+fn found_or_missing(object: Option<DynamicObject>) -> Lookup {
+    object
+        .and_then(|object| serde_json::to_value(object).ok())
+        .map(|object| Lookup::Found(Arc::new(object)))
+        .unwrap_or(Lookup::Missing)
+}
+
+/// Builds the API for a lookup. Unknown kinds are treated as missing objects, while a namespaced
+/// kind without a namespace is an error of the script, like in `kube::get`.
+fn api_for(
+    client: &kube::Client,
+    discovery: &kube::Discovery,
+    api_version: &str,
+    kind: &str,
+    namespace: &Option<String>,
+) -> Result<Api<DynamicObject>, Lookup> {
+    let missing = |e: String| {
+        tracing::warn!("Cannot look up related {kind}: {e}");
+        Lookup::Missing
+    };
+
+    let gv: GroupVersion = api_version.parse().map_err(|e| missing(format!("{e}")))?;
+
+    let (ar, capabilities) = discovery
+        .get(&gv.group)
+        .ok_or_else(|| missing(format!("ApiVersion not found: {api_version}")))?
+        .recommended_kind(kind)
+        .ok_or_else(|| missing(format!("Kind not found: {kind}")))?;
+
+    match (capabilities.scope, namespace) {
+        (Scope::Cluster, _) => Ok(Api::all_with(client.clone(), &ar)),
+        (Scope::Namespaced, Some(namespace)) => {
+            Ok(Api::namespaced_with(client.clone(), namespace, &ar))
+        }
+        (Scope::Namespaced, None) => Err(Lookup::Failed(format!(
+            "{} is namespaced, a namespace is required",
+            ar.kind
+        ))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    pub fn test_plan_batches() {
+        let mut queued: BTreeSet<RelatedKey> = (0..5)
+            .map(|i| RelatedKey::new("apps/v1", "ReplicaSet", "default", &format!("web-{i}")))
+            .collect();
+        queued.insert(RelatedKey::new("apps/v1", "ReplicaSet", "other", "api"));
+        queued.insert(RelatedKey::new("v1", "Node", "", "node-1"));
+
+        let batches = plan_batches(queued);
+
+        assert_eq!(
+            vec![
+                Batch::List {
+                    api_version: "apps/v1".into(),
+                    kind: "ReplicaSet".into(),
+                    namespace: Some("default".into()),
+                    names: (0..5).map(|i| format!("web-{i}")).collect(),
+                },
+                Batch::Get(RelatedKey::new("apps/v1", "ReplicaSet", "other", "api")),
+                Batch::Get(RelatedKey::new("v1", "Node", "", "node-1")),
+            ],
+            batches
+        );
+    }
+}
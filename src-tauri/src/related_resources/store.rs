@@ -0,0 +1,187 @@
+use std::{
+    collections::{hash_map, BTreeSet, HashMap},
+    sync::{Arc, Mutex, RwLock},
+    time::{Duration, Instant},
+};
+
+use tokio::sync::{broadcast, Notify};
+
+use crate::related_resources::{Lookup, RelatedKey};
+
+/// Resolved objects are fetched again on the next lookup after this long
+const MAX_AGE: Duration = Duration::from_secs(30);
+/// Upper bound for cached objects, the cache starts over once it is reached
+const MAX_ENTRIES: usize = 10_000;
+
+struct Entry {
+    lookup: Lookup,
+    resolved_at: Option<Instant>,
+}
+
+/// Per-cluster cache of objects that scripts look up in addition to the resource they render.
+///
+/// Renderers must never wait for the cluster, so lookups only ever read the cache. An object that
+/// is not cached yet is queued and reported as [Lookup::Pending]; the resolver (see
+/// [`RelatedResources::start_resolving`]) fetches queued objects in batches and announces every
+/// completed batch to subscribers, so that open resource views can render again.
+///
+/// The cache does not read from the watches of resource views: each view only watches its own kind
+/// for as long as it is open, so related objects of other kinds are rarely watched at all.
+pub struct RelatedResources {
+    entries: RwLock<HashMap<RelatedKey, Entry>>,
+    queue: Mutex<BTreeSet<RelatedKey>>,
+    pub(super) wakeup: Arc<Notify>,
+    updates: broadcast::Sender<()>,
+}
+
+impl RelatedResources {
+    pub fn new() -> Arc<Self> {
+        let (updates, _) = broadcast::channel(4);
+
+        Arc::new(Self {
+            entries: RwLock::new(HashMap::new()),
+            queue: Mutex::new(BTreeSet::new()),
+            wakeup: Arc::new(Notify::new()),
+            updates,
+        })
+    }
+
+    /// Returns the cached state of `key`, queueing it for the resolver if it is unknown or outdated.
+    ///
+    /// Outdated objects are still returned while they are fetched again.
+    pub fn lookup(&self, key: &RelatedKey) -> Lookup {
+        if let Some(entry) = self.entries.read().unwrap().get(key) {
+            if entry
+                .resolved_at
+                .is_none_or(|resolved_at| resolved_at.elapsed() < MAX_AGE)
+            {
+                return entry.lookup.clone();
+            }
+        }
+
+        let mut entries = self.entries.write().unwrap();
+
+        if entries.len() >= MAX_ENTRIES {
+            entries.retain(|_, entry| entry.resolved_at.is_none());
+        }
+
+        let entry = match entries.entry(key.clone()) {
+            // Queued by another thread in the meantime
+            hash_map::Entry::Occupied(entry)
+                if entry
+                    .get()
+                    .resolved_at
+                    .is_none_or(|resolved_at| resolved_at.elapsed() < MAX_AGE) =>
+            {
+                return entry.get().lookup.clone();
+            }
+            hash_map::Entry::Occupied(entry) => {
+                let entry = entry.into_mut();
+                entry.resolved_at = None;
+                entry
+            }
+            hash_map::Entry::Vacant(entry) => entry.insert(Entry {
+                lookup: Lookup::Pending,
+                resolved_at: None,
+            }),
+        };
+
+        self.queue.lock().unwrap().insert(key.clone());
+        self.wakeup.notify_one();
+
+        entry.lookup.clone()
+    }
+
+    /// Returns a receiver that yields once after every resolved batch.
+    pub fn subscribe(&self) -> broadcast::Receiver<()> {
+        self.updates.subscribe()
+    }
+
+    pub(crate) fn take_queued(&self) -> BTreeSet<RelatedKey> {
+        std::mem::take(&mut *self.queue.lock().unwrap())
+    }
+
+    pub(crate) fn complete(&self, resolved: impl IntoIterator<Item = (RelatedKey, Lookup)>) {
+        let now = Instant::now();
+        let mut entries = self.entries.write().unwrap();
+
+        for (key, lookup) in resolved {
+            entries.insert(
+                key,
+                Entry {
+                    lookup,
+                    resolved_at: Some(now),
+                },
+            );
+        }
+
+        drop(entries);
+
+        // Nobody listening is fine, views subscribe only while they are open.
+        let _ = self.updates.send(());
+    }
+
+    #[cfg(test)]
+    fn age(&self, key: &RelatedKey, by: Duration) {
+        let mut entries = self.entries.write().unwrap();
+        let entry = entries.get_mut(key).unwrap();
+
+        entry.resolved_at = entry.resolved_at.map(|at| at - by);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn deployment() -> RelatedKey {
+        RelatedKey::new("apps/v1", "Deployment", "default", "web")
+    }
+
+    #[test]
+    pub fn test_unknown_objects_are_queued_once() {
+        let related = RelatedResources::new();
+
+        assert!(matches!(related.lookup(&deployment()), Lookup::Pending));
+        assert!(matches!(related.lookup(&deployment()), Lookup::Pending));
+
+        assert_eq!(
+            BTreeSet::from([deployment()]),
+            related.take_queued(),
+            "queued exactly once"
+        );
+        assert!(matches!(related.lookup(&deployment()), Lookup::Pending));
+        assert!(related.take_queued().is_empty(), "still in flight");
+    }
+
+    #[test]
+    pub fn test_resolved_objects_are_served_from_cache() {
+        let related = RelatedResources::new();
+        let mut updates = related.subscribe();
+
+        related.lookup(&deployment());
+        related.take_queued();
+        related.complete([(
+            deployment(),
+            Lookup::Found(Arc::new(json!({"kind": "Deployment"}))),
+        )]);
+
+        assert!(updates.try_recv().is_ok());
+        assert!(matches!(related.lookup(&deployment()), Lookup::Found(_)));
+        assert!(related.take_queued().is_empty());
+    }
+
+    #[test]
+    pub fn test_outdated_objects_are_fetched_again() {
+        let related = RelatedResources::new();
+
+        related.complete([(deployment(), Lookup::Missing)]);
+        related.age(&deployment(), MAX_AGE);
+
+        assert!(matches!(related.lookup(&deployment()), Lookup::Missing));
+        assert!(matches!(related.lookup(&deployment()), Lookup::Missing));
+        assert_eq!(BTreeSet::from([deployment()]), related.take_queued());
+    }
+}
@@ -1,10 +1,13 @@
 use kube::api::DynamicObject;
+use serde::Deserialize;
 use tracing::debug;
 
-use crate::app_state::{ClusterStateRegistry, ManagerExt as _};
+use crate::app_state::{
+    ClusterStateRegistry, ManagerExt as _, MenuPromptError, MenuPrompts, PromptAnswer, PromptId,
+};
 use crate::frontend_commands::KubeContextSource;
 use crate::frontend_types::BackendError;
-use crate::scripting::resource_context_menu::MenuBlueprint;
+use crate::scripting::resource_context_menu::{ActionOutcome, MenuBlueprint};
 
 #[tauri::command]
 #[tracing::instrument(skip_all, fields(request_id = tracing::field::Empty))]
@@ -13,7 +16,7 @@ pub async fn call_menustack_action(
     context_source: KubeContextSource,
     menustack_id: &str,
     action_ref: &str,
-) -> Result<(), BackendError> {
+) -> Result<ActionOutcome, BackendError> {
     let clusters = app.state::<ClusterStateRegistry>();
     let facade = clusters.contextmenu_scripting_for(&context_source)?;
 
@@ -22,15 +25,27 @@ pub async fn call_menustack_action(
 
     // Rhai actions may call `kube.*`, which blocks the thread for the round-trip. Run on the
     // blocking pool so core runtime workers (e.g. UI watchers) aren't parked.
-    tokio::task::spawn_blocking(move || {
-        facade
-            .call_menustack_action(&menustack_id, &action_ref)
-            .unwrap(); // todo: error should appear in some kind of "scripting console"
+    // Script failures are reported to the scripting console by the facade and returned as outcome.
+    let outcome = tokio::task::spawn_blocking(move || {
+        facade.call_menustack_action(&menustack_id, &action_ref)
     })
     .await
-    .map_err(|e| BackendError::Generic(format!("menu action task failed: {e}")))?;
+    .map_err(|e| BackendError::Generic(format!("menu action task failed: {e}")))??;
 
-    Ok(())
+    Ok(outcome)
+}
+
+/// Hands the answer to a prompt of `frontend::prompt` to the menu action waiting for it.
+#[tauri::command]
+#[tracing::instrument(skip_all, fields(request_id = tracing::field::Empty))]
+pub fn answer_menu_prompt(
+    app: tauri::AppHandle,
+    prompt_id: PromptId,
+    answer: PromptAnswer,
+) -> Result<(), MenuPromptError> {
+    crate::internal::tracing::set_span_request_id();
+
+    app.state::<MenuPrompts>().answer(&prompt_id, answer)
 }
 
 #[tauri::command]
@@ -81,6 +96,60 @@ pub async fn create_resource_menustack(
     Ok(blueprint)
 }
 
+/// One resource of a multi-selection.
+#[derive(Debug, Deserialize)]
+pub struct SelectedResource {
+    pub namespace: String,
+    pub name: String,
+}
+
+/// Builds the menu of batch actions for a selection of resources of the same kind.
+#[tauri::command]
+#[tracing::instrument(skip_all, fields(request_id = tracing::field::Empty))]
+pub async fn create_multi_resource_menustack(
+    app: tauri::AppHandle,
+    context_source: KubeContextSource,
+    gvk: kube::api::GroupVersionKind,
+    resources: Vec<SelectedResource>,
+    tab_id: &str,
+) -> Result<MenuBlueprint, BackendError> {
+    crate::internal::tracing::set_span_request_id();
+
+    let clusters = app.state::<ClusterStateRegistry>();
+    let facade = clusters.contextmenu_scripting_for(&context_source)?;
+    let discovery = clusters.discovery_cache_for(&context_source)?;
+    let client = clusters.client_for(&context_source)?;
+
+    let (api_resource, capabilities) = discovery
+        .resolve_gvk(&gvk)
+        .ok_or("GroupVersionKind not found")?;
+
+    let objs = futures::future::try_join_all(resources.iter().map(|resource| {
+        let api = match capabilities.scope {
+            kube::discovery::Scope::Cluster => {
+                kube::Api::<DynamicObject>::all_with(client.clone(), &api_resource)
+            }
+            kube::discovery::Scope::Namespaced => match resource.namespace.as_str() {
+                "" => kube::Api::all_with(client.clone(), &api_resource),
+                namespace => kube::Api::namespaced_with(client.clone(), namespace, &api_resource),
+            },
+        };
+
+        async move { api.get(&resource.name).await }
+    }))
+    .await?;
+
+    let tab_id = tab_id.to_owned();
+
+    // Same as above, `multi_items` may call `kube.*`.
+    let blueprint =
+        tokio::task::spawn_blocking(move || facade.create_multi_resource_menustack(objs, &tab_id))
+            .await
+            .map_err(|e| BackendError::Generic(format!("menu build task failed: {e}")))??;
+
+    Ok(blueprint)
+}
+
 #[tauri::command]
 #[tracing::instrument(skip_all, fields(request_id = tracing::field::Empty))]
 pub async fn drop_resource_menustack(
@@ -24,7 +24,7 @@ impl ResourceRenderer for CrdRenderer {
         _gvk: &GroupVersionKind,
         crd: Option<&CustomResourceDefinition>,
     ) -> Result<Vec<super::ResourceColumnDefinition>, BackendError> {
-        let crd = crd.expect("must pass a CustomResourceDefinition");
+        let crd = crd.ok_or("No CustomResourceDefinition found for this resource")?;
 
         let crd_version = crd.spec.versions.first().ok_or("CRD version not found")?;
 
@@ -65,7 +65,7 @@ impl ResourceRenderer for CrdRenderer {
         crd: Option<&CustomResourceDefinition>,
         obj: &kube::api::DynamicObject,
     ) -> Result<Vec<Result<ResourcePresentationField, String>>, BackendError> {
-        let crd = crd.expect("must pass a CustomResourceDefinition");
+        let crd = crd.ok_or("No CustomResourceDefinition found for this resource")?;
 
         let crd_version = crd
             .spec
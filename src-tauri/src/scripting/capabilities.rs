@@ -0,0 +1,70 @@
+use std::collections::BTreeSet;
+
+pub use crate::scripting::package_manifest::Capability;
+
+/// The capabilities granted to the scripts of one package. Scripts with equal grants share an engine.
+pub type Capabilities = BTreeSet<Capability>;
+
+impl Capability {
+    pub const ALL: [Capability; 5] = [
+        Capability::KubeRead,
+        Capability::KubeWrite,
+        Capability::KubeDelete,
+        Capability::Clipboard,
+        Capability::Frontend,
+    ];
+
+    /// The name used in `package.toml`
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Capability::KubeRead => "kube-read",
+            Capability::KubeWrite => "kube-write",
+            Capability::KubeDelete => "kube-delete",
+            Capability::Clipboard => "clipboard",
+            Capability::Frontend => "frontend",
+        }
+    }
+}
+
+/// Fails a call to `function` unless `capability` has been granted.
+///
+/// Denied functions are still registered, so that scripts get this error instead of a confusing "function not found".
+pub fn require(
+    granted: bool,
+    function: &str,
+    capability: Capability,
+) -> Result<(), Box<rhai::EvalAltResult>> {
+    if granted {
+        return Ok(());
+    }
+
+    Err(format!(
+        "{function} is not permitted: the package does not declare the `{}` capability in its package.toml",
+        capability.as_str()
+    )
+    .into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    pub fn test_names_match_manifest() {
+        for capability in Capability::ALL {
+            assert_eq!(
+                format!("\"{}\"", capability.as_str()),
+                serde_json::to_string(&capability).unwrap()
+            );
+        }
+    }
+
+    #[test]
+    pub fn test_denied_error_names_capability() {
+        let error = require(false, "kube::delete", Capability::KubeDelete).unwrap_err();
+
+        assert!(require(true, "kube::delete", Capability::KubeDelete).is_ok());
+        assert!(error.to_string().contains("kube::delete"));
+        assert!(error.to_string().contains("`kube-delete`"));
+    }
+}
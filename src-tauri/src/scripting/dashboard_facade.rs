@@ -0,0 +1,270 @@
+use std::{
+    path::Path,
+    sync::{Arc, Weak},
+};
+
+use kube::api::GroupVersionKind;
+use serde::Serialize;
+use tokio::sync::broadcast;
+
+use crate::{
+    cluster_profiles::ClusterProfileId,
+    internal::mini_id::random_id,
+    scripting::{
+        diagnostics::{DiagnosticKind, ScriptDiagnostic, ScriptDiagnostics},
+        environment::ScriptEnvironment,
+        limits,
+        script_registry::{self, ScriptRegistry},
+        scripts_provider::{ScriptType, ScriptsProvider},
+        types::{
+            dashboards::{self, DashboardWidget, WidgetComponent},
+            resource_presentations, ResourceKind, ResourceRef,
+        },
+    },
+};
+
+struct DashboardWidgetDefinition {
+    id: String,
+    title: String,
+    source: GroupVersionKind,
+    render: rhai::FnPtr,
+}
+
+/// A registered widget as announced to the frontend, before anything has been rendered.
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WidgetDefinition {
+    pub id: String,
+    pub title: String,
+    pub gvk: GroupVersionKind,
+}
+
+pub struct DashboardFacade {
+    registry: ScriptRegistry<DashboardWidgetDefinition>,
+    diagnostics: Arc<ScriptDiagnostics>,
+    reloads: broadcast::Sender<()>,
+}
+
+impl DashboardFacade {
+    pub fn new(diagnostics: Arc<ScriptDiagnostics>, environment: ScriptEnvironment) -> Arc<Self> {
+        Arc::new_cyclic(|weak| Self {
+            registry: ScriptRegistry::new(
+                ScriptType::Dashboard,
+                environment.clone(),
+                Arc::clone(&diagnostics),
+                {
+                    let facade = weak.clone();
+                    let diagnostics = Arc::clone(&diagnostics);
+
+                    // Widgets only summarize what the dashboard watches, they need no capabilities.
+                    move |_| Self::make_engine(facade.clone(), &diagnostics, &environment)
+                },
+            ),
+            diagnostics,
+            reloads: broadcast::channel(4).0,
+        })
+    }
+
+    fn make_engine(
+        facade: Weak<Self>,
+        diagnostics: &Arc<ScriptDiagnostics>,
+        environment: &ScriptEnvironment,
+    ) -> rhai::Engine {
+        let mut engine = rhai::Engine::new();
+        diagnostics.capture_output(&mut engine);
+
+        engine.build_type::<ResourceRef>();
+        engine.build_type::<ResourceKind>();
+        resource_presentations::register_components(&mut engine);
+        dashboards::register_types(&mut engine);
+
+        environment.apply(&mut engine);
+
+        {
+            engine.register_fn(
+                "register_dashboard_widget",
+                move |widget: DashboardWidget| {
+                    let facade = facade.upgrade().expect("facade dropped");
+
+                    facade.registry.stage(DashboardWidgetDefinition {
+                        id: random_id(8),
+                        title: widget.title,
+                        source: widget.source,
+                        render: widget.render,
+                    });
+                },
+            );
+        }
+
+        limits::apply(&mut engine);
+
+        engine
+    }
+
+    /// All registered widgets, in the order they were registered.
+    pub fn widgets(&self) -> Vec<WidgetDefinition> {
+        self.registry
+            .registered()
+            .iter()
+            .map(|widget| WidgetDefinition {
+                id: widget.definition.id.clone(),
+                title: widget.definition.title.clone(),
+                gvk: widget.definition.source.clone(),
+            })
+            .collect()
+    }
+
+    /// Renders the widget with the given ID from all currently known resources of its source kind.
+    ///
+    /// Returns `None` if the widget no longer exists, e.g. because its script has been reloaded in the meantime.
+    /// Failures are reported to the scripting console and returned as a message.
+    pub fn render_widget(
+        &self,
+        id: &str,
+        objects: &rhai::Array,
+    ) -> Option<Result<WidgetComponent, String>> {
+        let registered_widgets = self.registry.registered();
+        let widget = registered_widgets
+            .iter()
+            .find(|widget| widget.definition.id == id)?;
+
+        let result = limits::with_time_limit(limits::RENDER_TIME_LIMIT, || {
+            widget.definition.render.call::<rhai::Dynamic>(
+                &widget.engine,
+                &widget.ast,
+                (objects.clone(),),
+            )
+        });
+
+        let diagnostic = match result.map(WidgetComponent::from_dynamic) {
+            Ok(Ok(component)) => return Some(Ok(component)),
+            Ok(Err(message)) => ScriptDiagnostic {
+                script: widget.ast.source().map(ToOwned::to_owned),
+                ..ScriptDiagnostic::new(DiagnosticKind::Render, message)
+            },
+            Err(e) => {
+                ScriptDiagnostic::from_eval_error(DiagnosticKind::Render, &e, widget.ast.source())
+            }
+        }
+        .with_presentation(&widget.definition.title);
+
+        let message = diagnostic.to_string();
+        self.diagnostics.report(diagnostic);
+
+        Some(Err(message))
+    }
+
+    pub fn evaluate(
+        &self,
+        scripts_provider: &ScriptsProvider,
+        profile: &ClusterProfileId,
+    ) -> Result<(), script_registry::Error> {
+        self.registry.evaluate(scripts_provider, profile)
+    }
+
+    /// (Re-)compiles and evaluates a single script and replaces all widgets it had registered before.
+    ///
+    /// If the script fails to compile or to evaluate, its previous widgets stay in place.
+    pub fn load_script(&self, entrypoint: &Path) -> Result<(), script_registry::Error> {
+        self.registry.load_script(entrypoint)
+    }
+
+    /// Removes all widgets of a script that no longer exists.
+    pub fn unload_script(&self, entrypoint: &Path) {
+        self.registry.unload_script(entrypoint)
+    }
+
+    /// Returns a receiver that yields once after every batch of reloaded scripts.
+    pub fn subscribe(&self) -> broadcast::Receiver<()> {
+        self.reloads.subscribe()
+    }
+
+    pub fn notify_reloaded(&self) {
+        // Nobody listening is fine, dashboards subscribe only while they are open.
+        let _ = self.reloads.send(());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::scripting::test_fixtures::TempPackages;
+
+    fn facade_with_script(script: &str) -> Arc<DashboardFacade> {
+        let packages = TempPackages::new();
+        let entrypoint = packages.write("core/dashboards/pods.rhai", script);
+
+        let facade = DashboardFacade::new(ScriptDiagnostics::new(), ScriptEnvironment::default());
+        facade.load_script(&entrypoint).unwrap();
+
+        facade
+    }
+
+    fn pods() -> rhai::Array {
+        [("web", "Running"), ("job", "Pending"), ("db", "Failed")]
+            .into_iter()
+            .map(|(name, phase)| {
+                rhai::serde::to_dynamic(json!({
+                    "apiVersion": "v1",
+                    "kind": "Pod",
+                    "metadata": { "name": name, "namespace": "default" },
+                    "status": { "phase": phase }
+                }))
+                .unwrap()
+            })
+            .collect()
+    }
+
+    #[test]
+    pub fn test_render_widgets() {
+        let facade = facade_with_script(
+            r#"
+            register_dashboard_widget(DashboardWidget(#{
+                title: "Pods not Running",
+                source: ResourceKind("v1", "Pod"),
+                render: |pods| pods.filter(|pod| pod.status?.phase != "Running").len()
+            }));
+            register_dashboard_widget(DashboardWidget(#{
+                title: "Pods by phase",
+                source: ResourceKind("v1", "Pod"),
+                render: |pods| #{ Running: pods.filter(|pod| pod.status?.phase == "Running").len() }
+            }));
+            "#,
+        );
+
+        let widgets = facade.widgets();
+
+        assert_eq!(2, widgets.len());
+        assert_eq!(GroupVersionKind::gvk("", "v1", "Pod"), widgets[0].gvk);
+
+        let count = facade.render_widget(&widgets[0].id, &pods()).unwrap();
+        let grid = facade.render_widget(&widgets[1].id, &pods()).unwrap();
+
+        assert_eq!(
+            json!({ "kind": "Stat", "args": { "value": "2", "items": [], "properties": null } }),
+            serde_json::to_value(count.ok()).unwrap()
+        );
+        assert!(matches!(grid, Ok(WidgetComponent::KeyValueGrid(_))));
+        assert!(facade.render_widget("unknown", &pods()).is_none());
+    }
+
+    #[test]
+    pub fn test_failing_widget_returns_error() {
+        let facade = facade_with_script(
+            r#"
+            register_dashboard_widget(DashboardWidget(#{
+                title: "Broken",
+                source: ResourceKind("v1", "Pod"),
+                render: |pods| pods[0].missing()
+            }));
+            "#,
+        );
+
+        let id = &facade.widgets()[0].id;
+        let result = facade.render_widget(id, &pods()).unwrap();
+
+        assert!(result.err().unwrap().contains("missing"));
+    }
+}
@@ -0,0 +1,320 @@
+use std::{
+    collections::VecDeque,
+    fmt,
+    sync::{Arc, Mutex},
+};
+
+use rhai::EvalAltResult;
+use serde::Serialize;
+use tokio::sync::broadcast;
+
+#[derive(Clone, Copy, Debug, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum DiagnosticKind {
+    /// A script could not be parsed
+    Compile,
+    /// A script failed while it was evaluated, e.g. when registering its sections or presentations
+    Evaluation,
+    /// A `matcher` function failed
+    Matcher,
+    /// A resource presentation failed to render a resource or to describe its columns
+    Render,
+    /// A menu action failed
+    Action,
+    /// Output of `print`
+    Print,
+    /// Output of `debug`
+    Debug,
+}
+
+impl DiagnosticKind {
+    /// Whether this is regular script output rather than a problem
+    pub fn is_output(self) -> bool {
+        matches!(self, Self::Print | Self::Debug)
+    }
+}
+
+/// A problem that occurred while running a script, with enough context to locate it.
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScriptDiagnostic {
+    pub kind: DiagnosticKind,
+    pub timestamp: String,
+    pub message: String,
+    pub script: Option<String>,
+    pub line: Option<usize>,
+    pub column: Option<usize>,
+    pub presentation: Option<String>,
+    pub column_title: Option<String>,
+    pub resource_uid: Option<String>,
+}
+
+impl ScriptDiagnostic {
+    pub fn new(kind: DiagnosticKind, message: impl Into<String>) -> Self {
+        Self {
+            kind,
+            timestamp: chrono::Utc::now().to_rfc3339(),
+            message: message.into(),
+            script: None,
+            line: None,
+            column: None,
+            presentation: None,
+            column_title: None,
+            resource_uid: None,
+        }
+    }
+
+    /// Builds a diagnostic from a Rhai error, pointing at the innermost location of nested function calls.
+    ///
+    /// `source` is used as script path if the error itself does not carry one, e.g. for errors in closures.
+    pub fn from_eval_error(
+        kind: DiagnosticKind,
+        error: &EvalAltResult,
+        source: Option<&str>,
+    ) -> Self {
+        let mut script = source.filter(|s| !s.is_empty()).map(ToOwned::to_owned);
+        let mut inner = error;
+
+        while let EvalAltResult::ErrorInFunctionCall(_, fn_source, next, _) = inner {
+            if !fn_source.is_empty() {
+                script = Some(fn_source.clone());
+            }
+            inner = next;
+        }
+
+        let inner = inner.unwrap_inner();
+        let position = inner.position();
+
+        // The position is part of the structured fields, not of the message
+        let message = inner.to_string();
+        let message = message
+            .strip_suffix(&format!(" ({position})"))
+            .unwrap_or(&message);
+
+        Self {
+            script,
+            line: position.line(),
+            column: position.position(),
+            ..Self::new(kind, message)
+        }
+    }
+
+    /// Builds a diagnostic for script output at the given position, e.g. from a `debug` statement.
+    pub fn from_output(
+        kind: DiagnosticKind,
+        message: &str,
+        source: Option<&str>,
+        position: rhai::Position,
+    ) -> Self {
+        Self {
+            script: source.filter(|s| !s.is_empty()).map(ToOwned::to_owned),
+            line: position.line(),
+            column: position.position(),
+            ..Self::new(kind, message)
+        }
+    }
+
+    pub fn with_presentation(mut self, title: impl Into<String>) -> Self {
+        self.presentation = Some(title.into());
+        self
+    }
+
+    pub fn with_column_title(mut self, title: impl Into<String>) -> Self {
+        self.column_title = Some(title.into());
+        self
+    }
+
+    pub fn with_resource_uid(mut self, uid: Option<String>) -> Self {
+        self.resource_uid = uid;
+        self
+    }
+}
+
+impl fmt::Display for ScriptDiagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match (&self.script, self.line, self.column) {
+            (Some(script), Some(line), Some(column)) => write!(f, "{script}:{line}:{column}: ")?,
+            (Some(script), Some(line), None) => write!(f, "{script}:{line}: ")?,
+            (Some(script), None, _) => write!(f, "{script}: ")?,
+            (None, _, _) => {}
+        }
+
+        write!(f, "{}", self.message)
+    }
+}
+
+/// Number of diagnostics that are kept for consoles that are opened later on
+const HISTORY_SIZE: usize = 500;
+
+/// Per-cluster store and channel for script diagnostics and script output.
+///
+/// Failing scripts must never take down the task that runs them, so callers report what went wrong
+/// here and carry on. Reports are logged, kept in a bounded history and announced to all current subscribers.
+pub struct ScriptDiagnostics {
+    history: Mutex<VecDeque<ScriptDiagnostic>>,
+    updates: broadcast::Sender<ScriptDiagnostic>,
+}
+
+impl ScriptDiagnostics {
+    pub fn new() -> Arc<Self> {
+        let (updates, _) = broadcast::channel(256);
+
+        Arc::new(Self {
+            history: Mutex::new(VecDeque::with_capacity(HISTORY_SIZE)),
+            updates,
+        })
+    }
+
+    pub fn report(&self, diagnostic: ScriptDiagnostic) {
+        if diagnostic.kind.is_output() {
+            tracing::info!("{diagnostic}");
+        } else {
+            tracing::warn!("{diagnostic}");
+        }
+
+        // Send while holding the lock, so that `subscribe` never misses or duplicates a diagnostic
+        let mut history = self.history.lock().unwrap();
+
+        if history.len() == HISTORY_SIZE {
+            history.pop_front();
+        }
+        history.push_back(diagnostic.clone());
+
+        // Nobody listening is fine, the diagnostic has been logged and stored anyway.
+        let _ = self.updates.send(diagnostic);
+    }
+
+    /// Returns all stored diagnostics along with a receiver for everything reported afterwards.
+    pub fn subscribe(&self) -> (Vec<ScriptDiagnostic>, broadcast::Receiver<ScriptDiagnostic>) {
+        let history = self.history.lock().unwrap();
+
+        (history.iter().cloned().collect(), self.updates.subscribe())
+    }
+
+    pub fn clear(&self) {
+        self.history.lock().unwrap().clear();
+    }
+
+    /// Routes `print` and `debug` of all scripts run by `engine` into this store.
+    pub fn capture_output(self: &Arc<Self>, engine: &mut rhai::Engine) {
+        let diagnostics = Arc::clone(self);
+        engine.on_print(move |text| {
+            diagnostics.report(ScriptDiagnostic::new(DiagnosticKind::Print, text));
+        });
+
+        let diagnostics = Arc::clone(self);
+        engine.on_debug(move |text, source, position| {
+            diagnostics.report(ScriptDiagnostic::from_output(
+                DiagnosticKind::Debug,
+                text,
+                source,
+                position,
+            ));
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SCRIPT: &str = r#"
+fn broken(obj) {
+    obj.missing.field
+}
+
+|obj| broken(obj)
+"#;
+
+    #[test]
+    pub fn test_points_at_innermost_position() {
+        let engine = rhai::Engine::new();
+        let mut ast = engine.compile(SCRIPT).unwrap();
+        ast.set_source("presentations/broken.rhai");
+
+        let render = engine.eval_ast::<rhai::FnPtr>(&ast).unwrap();
+        let error = render
+            .call::<rhai::Dynamic>(&engine, &ast, (rhai::Map::new(),))
+            .unwrap_err();
+
+        let diagnostic =
+            ScriptDiagnostic::from_eval_error(DiagnosticKind::Render, &error, ast.source())
+                .with_column_title("Name");
+
+        assert_eq!(
+            Some("presentations/broken.rhai"),
+            diagnostic.script.as_deref()
+        );
+        assert_eq!(Some(3), diagnostic.line);
+        assert_eq!(Some("Name"), diagnostic.column_title.as_deref());
+        assert!(diagnostic
+            .to_string()
+            .starts_with("presentations/broken.rhai:3:"));
+    }
+
+    #[test]
+    pub fn test_display_without_location() {
+        let diagnostic = ScriptDiagnostic::new(DiagnosticKind::Render, "boom");
+
+        assert_eq!("boom", diagnostic.to_string());
+    }
+
+    #[test]
+    pub fn test_report_reaches_subscribers() {
+        let diagnostics = ScriptDiagnostics::new();
+        diagnostics.report(ScriptDiagnostic::new(DiagnosticKind::Render, "before"));
+
+        let (history, mut receiver) = diagnostics.subscribe();
+        diagnostics.report(ScriptDiagnostic::new(DiagnosticKind::Render, "after"));
+
+        assert_eq!(1, history.len());
+        assert_eq!("before", history[0].message);
+        assert_eq!("after", receiver.try_recv().unwrap().message);
+    }
+
+    #[test]
+    pub fn test_history_is_bounded() {
+        let diagnostics = ScriptDiagnostics::new();
+
+        for i in 0..HISTORY_SIZE + 10 {
+            diagnostics.report(ScriptDiagnostic::new(DiagnosticKind::Print, i.to_string()));
+        }
+
+        let (history, _) = diagnostics.subscribe();
+
+        assert_eq!(HISTORY_SIZE, history.len());
+        assert_eq!("10", history[0].message);
+    }
+
+    #[test]
+    pub fn test_captures_print_and_debug() {
+        let diagnostics = ScriptDiagnostics::new();
+        let mut engine = rhai::Engine::new();
+        diagnostics.capture_output(&mut engine);
+
+        let mut ast = engine.compile("print(\"hello\");\ndebug(42);").unwrap();
+        ast.set_source("menus/hello.rhai");
+        engine.run_ast(&ast).unwrap();
+
+        let (history, _) = diagnostics.subscribe();
+
+        assert_eq!(DiagnosticKind::Print, history[0].kind);
+        assert_eq!("hello", history[0].message);
+        assert_eq!(DiagnosticKind::Debug, history[1].kind);
+        assert_eq!(Some("menus/hello.rhai"), history[1].script.as_deref());
+        assert_eq!(Some(2), history[1].line);
+    }
+
+    #[test]
+    pub fn test_compile_error_position() {
+        let engine = rhai::Engine::new();
+        let error: Box<EvalAltResult> = engine.compile("let x = ;").unwrap_err().into();
+
+        let diagnostic =
+            ScriptDiagnostic::from_eval_error(DiagnosticKind::Compile, &error, Some("broken.rhai"));
+
+        assert_eq!(Some(1), diagnostic.line);
+        assert!(diagnostic.column.is_some());
+        assert!(!diagnostic.message.contains("line 1"));
+    }
+}
@@ -0,0 +1,202 @@
+use std::{
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+use rhai::{exported_module, OptimizationLevel};
+
+use crate::scripting::{
+    capabilities::Capabilities,
+    module_resolver::LibraryResolver,
+    modules::{
+        self,
+        storage::{MemoryStorage, ScriptStorage},
+        time::{Clock, SystemClock},
+    },
+    package_manifest::PackageManifest,
+    scripts_provider::{self, PackageSource},
+};
+
+/// What all scripting engines share, regardless of the kind of scripts they evaluate.
+#[derive(Clone)]
+pub struct ScriptEnvironment {
+    /// The `lib` directory of the builtin core package, importable by all scripts
+    pub core_lib: Option<PathBuf>,
+    /// The directory of the builtin packages, all other packages are extensions
+    pub builtin_packages: Option<PathBuf>,
+    /// The current time as seen by the `time` module and `k8s::age`
+    pub clock: Arc<dyn Clock>,
+    /// Where the `storage` module keeps the values of scripts
+    pub storage: Arc<dyn ScriptStorage>,
+    /// The kube context of the cluster that the scripts run for, scopes `storage::cluster()`
+    pub cluster: Option<String>,
+}
+
+impl ScriptEnvironment {
+    pub fn new(core_lib: Option<PathBuf>) -> Self {
+        Self {
+            core_lib,
+            builtin_packages: None,
+            clock: Arc::new(SystemClock),
+            storage: Arc::new(MemoryStorage::default()),
+            cluster: None,
+        }
+    }
+
+    /// Whether the package in `package_dir` is shipped with Hyprkube or has been installed by the user.
+    pub fn package_source(&self, package_dir: &Path) -> PackageSource {
+        match &self.builtin_packages {
+            Some(builtin_packages) if package_dir.starts_with(builtin_packages) => {
+                PackageSource::Builtin
+            }
+            _ => PackageSource::Extension,
+        }
+    }
+
+    /// The capabilities that the manifest of the script's package declares. Nothing is granted if it can't be read.
+    pub fn capabilities_of(&self, entrypoint: &Path) -> Capabilities {
+        let manifest = scripts_provider::package_dir_of(entrypoint)
+            .ok_or_else(|| "script is not part of a package".to_owned())
+            .and_then(|package| {
+                PackageManifest::read(package, self.package_source(package))
+                    .map_err(|e| e.to_string())
+            });
+
+        match manifest {
+            Ok(manifest) => manifest.capabilities.into_iter().collect(),
+            Err(e) => {
+                tracing::warn!("Denying all capabilities to {}: {e}", entrypoint.display());
+                Capabilities::new()
+            }
+        }
+    }
+
+    /// Installs the library resolver and the general purpose modules `k8s`, `time`, `json`, `yaml` and `storage`.
+    pub fn apply(&self, engine: &mut rhai::Engine) {
+        // Scripts are optimized by `compile_file`, which leaves out those that import modules.
+        engine.set_optimization_level(OptimizationLevel::None);
+        engine.set_module_resolver(LibraryResolver::new(self.core_lib.clone()));
+
+        engine.register_static_module(
+            "k8s",
+            modules::k8s::build_module(Arc::clone(&self.clock)).into(),
+        );
+        engine.register_static_module(
+            "time",
+            modules::time::build_module(Arc::clone(&self.clock)).into(),
+        );
+        engine.register_static_module("json", exported_module!(modules::json_rhai).into());
+        engine.register_static_module("yaml", exported_module!(modules::yaml_rhai).into());
+        engine.register_static_module(
+            "storage",
+            modules::storage::build_module(Arc::clone(&self.storage), self.cluster.clone()).into(),
+        );
+    }
+}
+
+/// Compiles a script for an engine of [ScriptEnvironment::apply], optimizing it unless it imports modules.
+///
+/// The optimizer folds literal maps into constants, and closures in them, e.g. the `items` of a menu section,
+/// would then lose the modules imported by their script when they are called later on.
+pub fn compile_file(
+    engine: &rhai::Engine,
+    path: &Path,
+) -> Result<rhai::AST, Box<rhai::EvalAltResult>> {
+    let ast = engine.compile_file(path.to_owned())?;
+
+    if std::fs::read_to_string(path).map_or(true, |source| may_import(&source)) {
+        return Ok(ast);
+    }
+
+    let mut ast = engine.optimize_ast(&rhai::Scope::new(), ast, OptimizationLevel::Simple);
+    ast.set_source(path.to_string_lossy().as_ref());
+
+    Ok(ast)
+}
+
+/// Whether `source` contains the `import` keyword. Mentions in comments or strings only cost the optimization.
+fn may_import(source: &str) -> bool {
+    let is_identifier = |c: char| c.is_alphanumeric() || c == '_';
+
+    source.match_indices("import").any(|(at, keyword)| {
+        !source[..at].chars().next_back().is_some_and(is_identifier)
+            && !source[at + keyword.len()..]
+                .chars()
+                .next()
+                .is_some_and(is_identifier)
+    })
+}
+
+impl Default for ScriptEnvironment {
+    fn default() -> Self {
+        Self::new(None)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rhai::FnPtr;
+
+    use super::*;
+    use crate::scripting::test_fixtures::TempPackages;
+
+    #[test]
+    pub fn test_closures_in_maps_keep_imports() {
+        let packages = TempPackages::new();
+        packages.write("core/lib/helpers.rhai", r#"fn greet() { "hello" }"#);
+        let script = packages.write(
+            "core/menus/a.rhai",
+            r#"import "helpers" as h; #{ items: || h::greet() }"#,
+        );
+
+        let mut engine = rhai::Engine::new();
+        ScriptEnvironment::new(Some(packages.root().join("core/lib"))).apply(&mut engine);
+
+        assert_eq!(Ok("hello".to_owned()), greet(&engine, &script));
+
+        // What the optimizer would do to the script
+        engine.set_optimization_level(OptimizationLevel::Simple);
+
+        assert_eq!(
+            Err("Module not found: h (line 1, position 37)\nin closure call".to_owned()),
+            greet(&engine, &script)
+        );
+    }
+
+    /// Calls the `items` closure of the map that `script` evaluates to.
+    fn greet(engine: &rhai::Engine, script: &Path) -> Result<String, String> {
+        let ast = compile_file(engine, script).map_err(|e| e.to_string())?;
+        let section = engine
+            .eval_ast::<rhai::Map>(&ast)
+            .map_err(|e| e.to_string())?;
+
+        section["items"]
+            .clone_cast::<FnPtr>()
+            .call::<String>(engine, &ast, ())
+            .map_err(|e| e.to_string())
+    }
+
+    #[test]
+    pub fn test_only_scripts_without_imports_are_optimized() {
+        assert!(may_import(r#"import "helpers" as h;"#));
+        assert!(may_import("fn f() {\n    import \"helpers\" as h;\n}"));
+        assert!(!may_import("let important = 1; // reimports"));
+
+        let packages = TempPackages::new();
+        let script = packages.write("core/menus/a.rhai", "const ANSWER = 40 + 2;");
+
+        let mut engine = rhai::Engine::new();
+        ScriptEnvironment::default().apply(&mut engine);
+
+        let ast = compile_file(&engine, &script).unwrap();
+
+        // Only a folded constant is a literal
+        assert_eq!(
+            vec![("ANSWER".to_owned(), 42)],
+            ast.iter_literal_variables(true, false)
+                .map(|(name, _, value)| (name.to_owned(), value.cast::<i64>()))
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(Some(script.to_string_lossy().as_ref()), ast.source());
+    }
+}
@@ -0,0 +1,792 @@
+use std::{
+    convert::Infallible,
+    sync::{Arc, Mutex},
+};
+
+use http::{Method, Request, Response, StatusCode};
+use kube::client::Body;
+use kube::core::SelectorExt as _;
+use serde::Serialize;
+use serde_json::{json, Value};
+
+use crate::scripting::modules::k8s;
+
+#[derive(Clone, Debug)]
+struct FakeKind {
+    group: String,
+    version: String,
+    kind: String,
+    plural: String,
+    namespaced: bool,
+    subresources: Vec<String>,
+}
+
+impl FakeKind {
+    fn new(
+        group: &str,
+        version: &str,
+        kind: &str,
+        plural: &str,
+        namespaced: bool,
+        subresources: &[&str],
+    ) -> Self {
+        Self {
+            group: group.to_owned(),
+            version: version.to_owned(),
+            kind: kind.to_owned(),
+            plural: plural.to_owned(),
+            namespaced,
+            subresources: subresources.iter().map(|s| s.to_string()).collect(),
+        }
+    }
+
+    /// The kinds that every fake cluster serves, in addition to the kinds of its objects
+    fn builtins() -> Vec<Self> {
+        vec![
+            Self::new("", "v1", "Pod", "pods", true, &["status"]),
+            Self::new("", "v1", "Service", "services", true, &["status"]),
+            Self::new("", "v1", "ConfigMap", "configmaps", true, &[]),
+            Self::new("", "v1", "Secret", "secrets", true, &[]),
+            Self::new("", "v1", "ServiceAccount", "serviceaccounts", true, &[]),
+            Self::new(
+                "",
+                "v1",
+                "PersistentVolumeClaim",
+                "persistentvolumeclaims",
+                true,
+                &["status"],
+            ),
+            Self::new("", "v1", "Event", "events", true, &[]),
+            Self::new("", "v1", "Namespace", "namespaces", false, &["status"]),
+            Self::new("", "v1", "Node", "nodes", false, &["status"]),
+            Self::new(
+                "",
+                "v1",
+                "PersistentVolume",
+                "persistentvolumes",
+                false,
+                &["status"],
+            ),
+            Self::new(
+                "apps",
+                "v1",
+                "Deployment",
+                "deployments",
+                true,
+                &["status", "scale"],
+            ),
+            Self::new(
+                "apps",
+                "v1",
+                "StatefulSet",
+                "statefulsets",
+                true,
+                &["status", "scale"],
+            ),
+            Self::new(
+                "apps",
+                "v1",
+                "ReplicaSet",
+                "replicasets",
+                true,
+                &["status", "scale"],
+            ),
+            Self::new("apps", "v1", "DaemonSet", "daemonsets", true, &["status"]),
+            Self::new("batch", "v1", "Job", "jobs", true, &["status"]),
+            Self::new("batch", "v1", "CronJob", "cronjobs", true, &["status"]),
+        ]
+    }
+
+    fn api_version(&self) -> String {
+        match self.group.as_str() {
+            "" => self.version.clone(),
+            group => format!("{group}/{}", self.version),
+        }
+    }
+
+    /// Kinds that only appear in fixtures get a naive plural and are namespaced if the object has a namespace.
+    fn of_object(object: &Value) -> Option<Self> {
+        let api_version = object["apiVersion"].as_str()?;
+        let kind = object["kind"].as_str()?;
+
+        let (group, version) = api_version.split_once('/').unwrap_or(("", api_version));
+
+        Some(Self::new(
+            group,
+            version,
+            kind,
+            &format!("{}s", kind.to_lowercase()),
+            object["metadata"]["namespace"].is_string(),
+            &["status"],
+        ))
+    }
+}
+
+/// A request that reached the fake API server. Discovery requests are not recorded.
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RecordedRequest {
+    pub method: String,
+    pub path: String,
+    pub query: Option<String>,
+    pub content_type: Option<String>,
+    pub body: Value,
+}
+
+/// What a request path refers to
+struct Target {
+    kind: FakeKind,
+    namespace: Option<String>,
+    name: Option<String>,
+    subresource: Option<String>,
+}
+
+struct State {
+    kinds: Vec<FakeKind>,
+    objects: Vec<Value>,
+    requests: Vec<RecordedRequest>,
+    /// Source of resource versions, uids and generated names
+    revision: u64,
+}
+
+/// An in-memory Kubernetes API server for the `kube` module of scripts under test.
+///
+/// It serves discovery, and get, list, create, patch, replace and delete of objects including the `scale` and
+/// `status` subresources. Server-side apply and strategic merge patches are treated as JSON merge patches, which
+/// is close enough for what scripts send. There is no validation, no admission and no watches.
+pub struct FakeCluster {
+    state: Arc<Mutex<State>>,
+}
+
+impl FakeCluster {
+    pub fn new(objects: Vec<Value>) -> Self {
+        let mut kinds = FakeKind::builtins();
+
+        for kind in objects.iter().filter_map(FakeKind::of_object) {
+            let known = kinds
+                .iter()
+                .any(|k| k.api_version() == kind.api_version() && k.kind == kind.kind);
+
+            if !known {
+                kinds.push(kind);
+            }
+        }
+
+        Self {
+            state: Arc::new(Mutex::new(State {
+                kinds,
+                objects,
+                requests: Vec::new(),
+                revision: 0,
+            })),
+        }
+    }
+
+    /// A client that talks to this cluster. Must be called within a Tokio runtime.
+    pub fn client(&self) -> kube::Client {
+        let state = Arc::clone(&self.state);
+
+        let service = tower::service_fn(move |request: Request<Body>| {
+            let state = Arc::clone(&state);
+
+            async move {
+                let (parts, body) = request.into_parts();
+                let body = body.collect_bytes().await.unwrap_or_default();
+                let body = serde_json::from_slice(&body).unwrap_or(Value::Null);
+
+                let content_type = parts
+                    .headers
+                    .get(http::header::CONTENT_TYPE)
+                    .and_then(|value| value.to_str().ok())
+                    .map(str::to_owned);
+
+                let (status, response) = state.lock().unwrap().handle(
+                    &parts.method,
+                    parts.uri.path(),
+                    parts.uri.query(),
+                    content_type.as_deref(),
+                    body,
+                );
+
+                Ok::<_, Infallible>(
+                    Response::builder()
+                        .status(status)
+                        .header(http::header::CONTENT_TYPE, "application/json")
+                        .body(Body::from(serde_json::to_vec(&response).unwrap()))
+                        .unwrap(),
+                )
+            }
+        });
+
+        kube::Client::new(service, "default")
+    }
+
+    /// The current state of an object, `namespace` is ignored for cluster-scoped kinds.
+    pub fn get(&self, api_version: &str, kind: &str, namespace: &str, name: &str) -> Option<Value> {
+        self.state
+            .lock()
+            .unwrap()
+            .objects
+            .iter()
+            .find(|o| {
+                o["apiVersion"] == api_version
+                    && o["kind"] == kind
+                    && o["metadata"]["name"] == name
+                    && o["metadata"]["namespace"]
+                        .as_str()
+                        .is_none_or(|ns| ns == namespace)
+            })
+            .cloned()
+    }
+
+    pub fn take_requests(&self) -> Vec<RecordedRequest> {
+        std::mem::take(&mut self.state.lock().unwrap().requests)
+    }
+}
+
+impl State {
+    fn handle(
+        &mut self,
+        method: &Method,
+        path: &str,
+        query: Option<&str>,
+        content_type: Option<&str>,
+        body: Value,
+    ) -> (StatusCode, Value) {
+        if let Some(discovery) = self.discovery(path) {
+            return (StatusCode::OK, discovery);
+        }
+
+        self.requests.push(RecordedRequest {
+            method: method.to_string(),
+            path: path.to_owned(),
+            query: query.map(str::to_owned),
+            content_type: content_type.map(str::to_owned),
+            body: body.clone(),
+        });
+
+        let Some(target) = self.route(path) else {
+            return status(
+                StatusCode::NOT_FOUND,
+                "NotFound",
+                format!("no route for {path}"),
+            );
+        };
+
+        if let Some(subresource) = &target.subresource {
+            if !target.kind.subresources.contains(subresource) {
+                return status(
+                    StatusCode::NOT_FOUND,
+                    "NotFound",
+                    format!("the fake cluster does not serve {path}"),
+                );
+            }
+        }
+
+        match (method, &target.name) {
+            (&Method::GET, None) => self.list(&target, query),
+            (&Method::POST, None) => self.create(&target, body),
+            (&Method::GET, Some(name)) => match self.position(&target, name) {
+                Some(idx) => (StatusCode::OK, self.view(&target, idx)),
+                None => not_found(&target, name),
+            },
+            (&Method::PATCH, Some(name)) => self.patch(&target, name, content_type, body),
+            (&Method::PUT, Some(name)) => self.replace(&target, name, body),
+            (&Method::DELETE, Some(name)) => match self.position(&target, name) {
+                Some(idx) => (StatusCode::OK, self.objects.remove(idx)),
+                None => not_found(&target, name),
+            },
+            _ => status(
+                StatusCode::METHOD_NOT_ALLOWED,
+                "MethodNotAllowed",
+                format!("{method} {path} is not supported by the fake cluster"),
+            ),
+        }
+    }
+
+    fn discovery(&self, path: &str) -> Option<Value> {
+        let segments: Vec<&str> = path.trim_matches('/').split('/').collect();
+
+        match segments.as_slice() {
+            ["api"] => Some(json!({
+                "kind": "APIVersions",
+                "versions": ["v1"],
+                "serverAddressByClientCIDRs": []
+            })),
+            ["apis"] => {
+                let mut groups: Vec<(&str, Vec<&str>)> = Vec::new();
+
+                for kind in self.kinds.iter().filter(|k| !k.group.is_empty()) {
+                    match groups.iter_mut().find(|(group, _)| *group == kind.group) {
+                        Some((_, versions)) if !versions.contains(&kind.version.as_str()) => {
+                            versions.push(&kind.version)
+                        }
+                        Some(_) => {}
+                        None => groups.push((&kind.group, vec![&kind.version])),
+                    }
+                }
+
+                Some(json!({
+                    "kind": "APIGroupList",
+                    "apiVersion": "v1",
+                    "groups": groups.iter().map(|(group, versions)| json!({
+                        "name": group,
+                        "versions": versions.iter().map(|version| json!({
+                            "groupVersion": format!("{group}/{version}"),
+                            "version": version
+                        })).collect::<Vec<_>>(),
+                        "preferredVersion": {
+                            "groupVersion": format!("{group}/{}", versions[0]),
+                            "version": versions[0]
+                        }
+                    })).collect::<Vec<_>>()
+                }))
+            }
+            ["api", version] => Some(self.resource_list("", version)),
+            ["apis", group, version] => Some(self.resource_list(group, version)),
+            _ => None,
+        }
+    }
+
+    fn resource_list(&self, group: &str, version: &str) -> Value {
+        let kinds = self
+            .kinds
+            .iter()
+            .filter(|k| k.group == group && k.version == version);
+
+        let resources: Vec<Value> = kinds
+            .flat_map(|k| {
+                let verbs = ["create", "delete", "get", "list", "patch", "update"];
+
+                std::iter::once(json!({
+                    "name": k.plural,
+                    "singularName": k.kind.to_lowercase(),
+                    "namespaced": k.namespaced,
+                    "kind": k.kind,
+                    "verbs": verbs
+                }))
+                .chain(k.subresources.iter().map(|subresource| {
+                    let mut resource = json!({
+                        "name": format!("{}/{subresource}", k.plural),
+                        "singularName": "",
+                        "namespaced": k.namespaced,
+                        "kind": k.kind,
+                        "verbs": ["get", "patch", "update"]
+                    });
+
+                    if subresource == "scale" {
+                        resource["group"] = json!("autoscaling");
+                        resource["version"] = json!("v1");
+                        resource["kind"] = json!("Scale");
+                    }
+
+                    resource
+                }))
+            })
+            .collect();
+
+        json!({
+            "kind": "APIResourceList",
+            "apiVersion": "v1",
+            "groupVersion": match group {
+                "" => version.to_owned(),
+                group => format!("{group}/{version}"),
+            },
+            "resources": resources
+        })
+    }
+
+    fn route(&self, path: &str) -> Option<Target> {
+        let segments: Vec<&str> = path.trim_matches('/').split('/').collect();
+
+        let (group, version, rest) = match segments.as_slice() {
+            ["api", version, rest @ ..] => ("", *version, rest),
+            ["apis", group, version, rest @ ..] => (*group, *version, rest),
+            _ => return None,
+        };
+
+        let kind_of = |plural: &str| {
+            self.kinds
+                .iter()
+                .find(|k| k.group == group && k.version == version && k.plural == plural)
+        };
+
+        // `namespaces/x/status` is the status of a namespace, `namespaces/x/pods` are the pods in it
+        let (namespace, rest): (Option<String>, Vec<&str>) = match rest {
+            ["namespaces", namespace, plural, tail @ ..]
+                if kind_of(plural).is_some_and(|k| k.namespaced) =>
+            {
+                (Some(namespace.to_string()), [&[*plural], tail].concat())
+            }
+            _ => (None, rest.to_vec()),
+        };
+
+        let (plural, name, subresource) = match rest.as_slice() {
+            [plural] => (*plural, None, None),
+            [plural, name] => (*plural, Some(name.to_string()), None),
+            [plural, name, subresource] => (
+                *plural,
+                Some(name.to_string()),
+                Some(subresource.to_string()),
+            ),
+            _ => return None,
+        };
+
+        Some(Target {
+            kind: kind_of(plural)?.clone(),
+            namespace,
+            name,
+            subresource,
+        })
+    }
+
+    fn position(&self, target: &Target, name: &str) -> Option<usize> {
+        self.objects.iter().position(|o| {
+            o["apiVersion"] == target.kind.api_version()
+                && o["kind"] == target.kind.kind
+                && o["metadata"]["name"] == name
+                && (!target.kind.namespaced
+                    || o["metadata"]["namespace"].as_str() == target.namespace.as_deref())
+        })
+    }
+
+    /// The object at `idx` as seen through the subresource of `target`
+    fn view(&self, target: &Target, idx: usize) -> Value {
+        match target.subresource.as_deref() {
+            Some("scale") => scale_of(&self.objects[idx]),
+            _ => self.objects[idx].clone(),
+        }
+    }
+
+    fn list(&self, target: &Target, query: Option<&str>) -> (StatusCode, Value) {
+        let selector = query
+            .into_iter()
+            .flat_map(|query| query.split('&'))
+            .filter_map(|param| param.split_once('='))
+            .find(|(key, _)| *key == "labelSelector")
+            .map(|(_, value)| k8s::parse_selector(&percent_decode(value)))
+            .transpose();
+
+        let selector = match selector {
+            Ok(selector) => selector,
+            Err(e) => return status(StatusCode::BAD_REQUEST, "BadRequest", e),
+        };
+
+        let items: Vec<Value> = self
+            .objects
+            .iter()
+            .filter(|o| {
+                o["apiVersion"] == target.kind.api_version() && o["kind"] == target.kind.kind
+            })
+            .filter(|o| {
+                target
+                    .namespace
+                    .as_deref()
+                    .is_none_or(|ns| o["metadata"]["namespace"] == ns)
+            })
+            .filter(|o| {
+                selector.as_ref().is_none_or(|selector| {
+                    let labels =
+                        serde_json::from_value(o["metadata"]["labels"].clone()).unwrap_or_default();
+
+                    selector.matches(&labels)
+                })
+            })
+            .cloned()
+            .collect();
+
+        (
+            StatusCode::OK,
+            json!({
+                "apiVersion": target.kind.api_version(),
+                "kind": format!("{}List", target.kind.kind),
+                "metadata": { "resourceVersion": self.revision.to_string() },
+                "items": items
+            }),
+        )
+    }
+
+    fn create(&mut self, target: &Target, mut object: Value) -> (StatusCode, Value) {
+        self.revision += 1;
+
+        if !object["metadata"].is_object() {
+            object["metadata"] = json!({});
+        }
+
+        let metadata = &mut object["metadata"];
+
+        if metadata["name"].is_null() {
+            if let Some(prefix) = metadata["generateName"].as_str() {
+                metadata["name"] = json!(format!("{prefix}{:05}", self.revision));
+            }
+        }
+
+        let Some(name) = metadata["name"].as_str().map(str::to_owned) else {
+            return status(
+                StatusCode::UNPROCESSABLE_ENTITY,
+                "Invalid",
+                "metadata.name or metadata.generateName is required".to_owned(),
+            );
+        };
+
+        if target.kind.namespaced {
+            metadata["namespace"] = json!(target.namespace);
+        }
+
+        if self.position(target, &name).is_some() {
+            return status(
+                StatusCode::CONFLICT,
+                "AlreadyExists",
+                format!("{} \"{name}\" already exists", target.kind.plural),
+            );
+        }
+
+        if metadata["uid"].is_null() {
+            metadata["uid"] = json!(format!("fake-uid-{}", self.revision));
+        }
+        metadata["resourceVersion"] = json!(self.revision.to_string());
+
+        object["apiVersion"] = json!(target.kind.api_version());
+        object["kind"] = json!(target.kind.kind);
+
+        self.objects.push(object.clone());
+
+        (StatusCode::CREATED, object)
+    }
+
+    fn patch(
+        &mut self,
+        target: &Target,
+        name: &str,
+        content_type: Option<&str>,
+        patch: Value,
+    ) -> (StatusCode, Value) {
+        let content_type = content_type.unwrap_or_default();
+
+        let Some(idx) = self.position(target, name) else {
+            // Server-side apply creates what does not exist yet
+            if content_type.starts_with("application/apply-patch") && target.subresource.is_none() {
+                return self.create(target, patch);
+            }
+
+            return not_found(target, name);
+        };
+
+        let mut patched = self.view(target, idx);
+
+        if content_type.starts_with("application/json-patch") {
+            let result = serde_json::from_value::<json_patch::Patch>(patch)
+                .map_err(|e| e.to_string())
+                .and_then(|patch| {
+                    json_patch::patch(&mut patched, &patch).map_err(|e| e.to_string())
+                });
+
+            if let Err(e) = result {
+                return status(StatusCode::UNPROCESSABLE_ENTITY, "Invalid", e);
+            }
+        } else {
+            json_patch::merge(&mut patched, &patch);
+        }
+
+        self.store(target, idx, patched)
+    }
+
+    fn replace(&mut self, target: &Target, name: &str, object: Value) -> (StatusCode, Value) {
+        match self.position(target, name) {
+            Some(idx) => self.store(target, idx, object),
+            None => not_found(target, name),
+        }
+    }
+
+    /// Writes the new state of the object at `idx` as seen through the subresource of `target`.
+    fn store(&mut self, target: &Target, idx: usize, new: Value) -> (StatusCode, Value) {
+        self.revision += 1;
+
+        let object = &mut self.objects[idx];
+
+        match target.subresource.as_deref() {
+            Some("scale") => object["spec"]["replicas"] = new["spec"]["replicas"].clone(),
+            _ => *object = new,
+        }
+
+        object["metadata"]["resourceVersion"] = json!(self.revision.to_string());
+
+        (StatusCode::OK, self.view(target, idx))
+    }
+}
+
+fn scale_of(object: &Value) -> Value {
+    json!({
+        "apiVersion": "autoscaling/v1",
+        "kind": "Scale",
+        "metadata": {
+            "namespace": object["metadata"]["namespace"],
+            "name": object["metadata"]["name"]
+        },
+        "spec": { "replicas": object["spec"]["replicas"].as_i64().unwrap_or(1) },
+        "status": { "replicas": object["status"]["replicas"].as_i64().unwrap_or(0) }
+    })
+}
+
+fn status(code: StatusCode, reason: &str, message: String) -> (StatusCode, Value) {
+    (
+        code,
+        json!({
+            "kind": "Status",
+            "apiVersion": "v1",
+            "metadata": {},
+            "status": "Failure",
+            "message": message,
+            "reason": reason,
+            "code": code.as_u16()
+        }),
+    )
+}
+
+fn not_found(target: &Target, name: &str) -> (StatusCode, Value) {
+    status(
+        StatusCode::NOT_FOUND,
+        "NotFound",
+        format!("{} \"{name}\" not found", target.kind.plural),
+    )
+}
+
+/// Decodes a query parameter value, e.g. `app%3Dweb` to `app=web`.
+fn percent_decode(value: &str) -> String {
+    let mut bytes = Vec::with_capacity(value.len());
+    let mut input = value.bytes();
+
+    while let Some(byte) = input.next() {
+        match byte {
+            b'+' => bytes.push(b' '),
+            b'%' => {
+                let hex: Vec<u8> = input.by_ref().take(2).collect();
+                let decoded = std::str::from_utf8(&hex)
+                    .ok()
+                    .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+
+                match decoded {
+                    Some(decoded) => bytes.push(decoded),
+                    None => bytes.extend([b'%'].iter().chain(&hex)),
+                }
+            }
+            byte => bytes.push(byte),
+        }
+    }
+
+    String::from_utf8_lossy(&bytes).into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use k8s_openapi::api::{apps::v1::Deployment, core::v1::ConfigMap};
+    use kube::api::{Api, ListParams, Patch, PatchParams, PostParams};
+
+    use super::*;
+
+    fn config_map(name: &str, app: &str) -> Value {
+        json!({
+            "apiVersion": "v1",
+            "kind": "ConfigMap",
+            "metadata": { "namespace": "default", "name": name, "labels": { "app": app } },
+            "data": { "key": "value" }
+        })
+    }
+
+    #[tokio::test]
+    pub async fn test_list_by_label_selector() {
+        let cluster = FakeCluster::new(vec![config_map("web", "web"), config_map("db", "db")]);
+        let api = Api::<ConfigMap>::namespaced(cluster.client(), "default");
+
+        let all = api.list(&ListParams::default()).await.unwrap();
+        let web = api
+            .list(&ListParams::default().labels("app=web"))
+            .await
+            .unwrap();
+
+        assert_eq!(2, all.items.len());
+        assert_eq!(1, web.items.len());
+        assert_eq!(Some("web".to_owned()), web.items[0].metadata.name);
+        assert!(Api::<ConfigMap>::namespaced(cluster.client(), "other")
+            .list(&ListParams::default())
+            .await
+            .unwrap()
+            .items
+            .is_empty());
+    }
+
+    #[tokio::test]
+    pub async fn test_create_and_conflict() {
+        let cluster = FakeCluster::new(vec![config_map("web", "web")]);
+        let api = Api::<ConfigMap>::namespaced(cluster.client(), "default");
+
+        let mut generated: ConfigMap =
+            serde_json::from_value(config_map("ignored", "web")).unwrap();
+        generated.metadata.name = None;
+        generated.metadata.generate_name = Some("web-".to_owned());
+
+        let created = api
+            .create(&PostParams::default(), &generated)
+            .await
+            .unwrap();
+        let name = created.metadata.name.unwrap();
+
+        assert!(name.starts_with("web-"));
+        assert!(created.metadata.uid.is_some());
+        assert!(cluster.get("v1", "ConfigMap", "default", &name).is_some());
+
+        let existing: ConfigMap = serde_json::from_value(config_map("web", "web")).unwrap();
+        let conflict = api.create(&PostParams::default(), &existing).await;
+
+        assert!(matches!(conflict, Err(kube::Error::Api(status)) if status.code == 409));
+    }
+
+    #[tokio::test]
+    pub async fn test_patches() {
+        let cluster = FakeCluster::new(vec![json!({
+            "apiVersion": "apps/v1",
+            "kind": "Deployment",
+            "metadata": { "namespace": "default", "name": "web" },
+            "spec": { "replicas": 1, "paused": false }
+        })]);
+        let api = Api::<Deployment>::namespaced(cluster.client(), "default");
+        let params = PatchParams::default();
+
+        api.patch_scale(
+            "web",
+            &params,
+            &Patch::Merge(json!({ "spec": { "replicas": 4 } })),
+        )
+        .await
+        .unwrap();
+
+        let patch = serde_json::from_value(
+            json!([{ "op": "replace", "path": "/spec/paused", "value": true }]),
+        )
+        .unwrap();
+        api.patch("web", &params, &Patch::<()>::Json(patch))
+            .await
+            .unwrap();
+
+        let deployment = cluster
+            .get("apps/v1", "Deployment", "default", "web")
+            .unwrap();
+        assert_eq!(4, deployment["spec"]["replicas"]);
+        assert_eq!(true, deployment["spec"]["paused"]);
+
+        let requests = cluster.take_requests();
+        assert_eq!(2, requests.len());
+        assert_eq!(
+            "/apis/apps/v1/namespaces/default/deployments/web/scale",
+            requests[0].path
+        );
+        assert!(requests[1]
+            .content_type
+            .as_deref()
+            .unwrap()
+            .starts_with("application/json-patch"));
+
+        let missing = api.get("nope").await;
+        assert!(matches!(missing, Err(kube::Error::Api(status)) if status.code == 404));
+    }
+}
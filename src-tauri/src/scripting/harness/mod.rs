@@ -0,0 +1,545 @@
+//! Runs the presentation and menu scripts of a package without the app, against a fake cluster, so that script
+//! packages can be tested in CI. See `src/bin/hyprkube-script-test.rs` for the command line.
+
+mod fake_cluster;
+mod recording_host;
+pub mod spec;
+
+use std::{
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+use kube::api::{DynamicObject, GroupVersionKind};
+use serde::Serialize;
+use serde_json::Value;
+
+pub use fake_cluster::{FakeCluster, RecordedRequest};
+pub use recording_host::{RecordedEvent, RecordingHost};
+
+pub use crate::scripting::{
+    diagnostics::ScriptDiagnostic, environment::ScriptEnvironment, modules::time::FixedClock,
+    resource_context_menu::ActionOutcome,
+};
+use crate::{
+    cluster_metrics::ClusterMetrics,
+    internal::gvk_extraction::GvkExtraction as _,
+    related_resources::{Lookup, RelatedResources},
+    scripting::{
+        diagnostics::ScriptDiagnostics,
+        resource_context_menu_facade::{ResourceContextMenuError, ResourceContextMenuFacade},
+        resource_presentation_facade::ResourcePresentationFacade,
+        scripts_provider::{self, ScriptType},
+        types::resource_presentations::PresentationComponent,
+    },
+};
+
+/// The tab that all menus of the harness are opened in
+const TAB_ID: &str = "harness";
+
+/// The kube context that scripts run for, unless the environment names one
+const CLUSTER: &str = "harness";
+
+/// How often a row is rendered again after it looked up related objects, which may look up more objects
+const MAX_RELATED_ROUNDS: usize = 4;
+
+/// The scripts of one package, loaded into the same facades that the app uses.
+///
+/// Scripts see a [FakeCluster] through the `kube` module and the `related` module, and a [RecordingHost] instead of
+/// the frontend. Metrics are never available.
+pub struct ScriptHarness {
+    runtime: tokio::runtime::Runtime,
+    cluster: FakeCluster,
+    host: Arc<RecordingHost>,
+    related: Arc<RelatedResources>,
+    diagnostics: Arc<ScriptDiagnostics>,
+    presentations: Arc<ResourcePresentationFacade>,
+    menus: Arc<ResourceContextMenuFacade>,
+}
+
+/// A column of a rendered row, as the frontend receives it
+#[derive(Clone, Debug, Serialize)]
+pub struct RenderedColumn {
+    pub title: String,
+    pub value: Result<Value, String>,
+}
+
+/// An opened menu
+pub struct Menu {
+    id: String,
+    entries: Vec<MenuEntry>,
+}
+
+/// An item of a menu, with the titles of the submenus leading to it
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MenuEntry {
+    pub path: Vec<String>,
+    pub action_ref: Option<String>,
+    pub dangerous: bool,
+}
+
+impl Menu {
+    pub fn entries(&self) -> &[MenuEntry] {
+        &self.entries
+    }
+
+    /// Finds an entry by its titles from the top of the menu, e.g. `["Copy data", "All as YAML"]`.
+    pub fn find(&self, path: &[&str]) -> Option<&MenuEntry> {
+        self.entries.iter().find(|entry| entry.path == path)
+    }
+
+    fn collect_entries(items: &Value, parent: &[String], entries: &mut Vec<MenuEntry>) {
+        for item in items.as_array().into_iter().flatten() {
+            let data = &item["data"];
+            let Some(title) = data["title"].as_str() else {
+                continue;
+            };
+
+            let path = [parent, &[title.to_owned()]].concat();
+
+            entries.push(MenuEntry {
+                path: path.clone(),
+                action_ref: data["actionRef"].as_str().map(str::to_owned),
+                dangerous: data["dangerous"].as_bool().unwrap_or_default(),
+            });
+
+            Self::collect_entries(&data["items"], &path, entries);
+        }
+    }
+}
+
+impl ScriptHarness {
+    /// Loads the presentation and menu scripts of `package` into a cluster that contains `fixtures`.
+    ///
+    /// Fails on the first script that does not compile or evaluate.
+    pub fn load(
+        package: &Path,
+        fixtures: Vec<Value>,
+        environment: ScriptEnvironment,
+    ) -> Result<Self, HarnessError> {
+        let environment = ScriptEnvironment {
+            cluster: environment.cluster.or_else(|| Some(CLUSTER.to_owned())),
+            ..environment
+        };
+
+        let runtime = tokio::runtime::Builder::new_multi_thread()
+            .worker_threads(1)
+            .enable_all()
+            .build()?;
+
+        let cluster = FakeCluster::new(fixtures);
+        let host = Arc::new(RecordingHost::default());
+        let related = RelatedResources::new();
+        let diagnostics = ScriptDiagnostics::new();
+
+        let client = {
+            let _guard = runtime.enter();
+            cluster.client()
+        };
+        let discovery = runtime
+            .block_on(kube::Discovery::new(client.clone()).run())
+            .map_err(|e| HarnessError::Cluster(e.to_string()))?;
+        cluster.take_requests();
+
+        let presentations = ResourcePresentationFacade::new(
+            ClusterMetrics::new(),
+            Arc::clone(&related),
+            Arc::clone(&diagnostics),
+            environment.clone(),
+        );
+        let menus = ResourceContextMenuFacade::new(
+            Arc::clone(&host) as _,
+            client,
+            Arc::clone(&diagnostics),
+            environment,
+        );
+        menus.set_discovery(Arc::new(discovery));
+
+        {
+            let _guard = runtime.enter();
+
+            for script in scripts_provider::scripts_in_package(package, ScriptType::Presentation) {
+                presentations
+                    .load_script(&script)
+                    .map_err(|e| HarnessError::Load(script.clone(), e.to_string()))?;
+            }
+
+            for script in scripts_provider::scripts_in_package(package, ScriptType::Menu) {
+                menus
+                    .load_script(&script)
+                    .map_err(|e| HarnessError::Load(script.clone(), e.to_string()))?;
+            }
+        }
+
+        Ok(Self {
+            runtime,
+            cluster,
+            host,
+            related,
+            diagnostics,
+            presentations,
+            menus,
+        })
+    }
+
+    pub fn cluster(&self) -> &FakeCluster {
+        &self.cluster
+    }
+
+    pub fn host(&self) -> &RecordingHost {
+        &self.host
+    }
+
+    /// Everything that went wrong in scripts so far, without their `print` and `debug` output.
+    pub fn diagnostics(&self) -> Vec<ScriptDiagnostic> {
+        let (history, _) = self.diagnostics.subscribe();
+
+        history
+            .into_iter()
+            .filter(|diagnostic| !diagnostic.kind.is_output())
+            .collect()
+    }
+
+    pub fn clear_diagnostics(&self) {
+        self.diagnostics.clear();
+    }
+
+    /// The names of the presentations that are offered for a kind, including the builtin "Simple list".
+    pub fn presentations(&self, gvk: &GroupVersionKind) -> Result<Vec<String>, HarnessError> {
+        self.presentations
+            .get_renderers(gvk, false)
+            .map_err(|e| HarnessError::Presentation(e.to_string()))
+    }
+
+    /// Renders `object` as a row of `presentation`.
+    ///
+    /// Related objects that the row looks up are served from the fake cluster, and the row is rendered again until
+    /// it has everything it asked for, like an open resource view would.
+    pub fn render(
+        &self,
+        presentation: &str,
+        object: &Value,
+    ) -> Result<Vec<RenderedColumn>, HarnessError> {
+        let (gvk, object) = Self::dynamic_object(object)?;
+
+        let renderer = self
+            .runtime
+            .block_on(self.presentations.get_renderer(&gvk, presentation));
+
+        if renderer.display_name() != presentation {
+            return Err(HarnessError::NoSuchPresentation(presentation.to_owned()));
+        }
+
+        let titles = renderer
+            .column_definitions(&gvk, None)
+            .map_err(|e| HarnessError::Presentation(e.to_string()))?;
+
+        let mut fields = Vec::new();
+
+        for _ in 0..MAX_RELATED_ROUNDS {
+            fields = renderer
+                .render(&gvk, None, &object)
+                .map_err(|e| HarnessError::Presentation(e.to_string()))?;
+
+            let queued = self.related.take_queued();
+
+            if queued.is_empty() {
+                break;
+            }
+
+            self.related.complete(queued.into_iter().map(|key| {
+                let found = self.cluster.get(
+                    &key.api_version,
+                    &key.kind,
+                    key.namespace.as_deref().unwrap_or_default(),
+                    &key.name,
+                );
+
+                let lookup = match found {
+                    Some(object) => Lookup::Found(Arc::new(object)),
+                    None => Lookup::Missing,
+                };
+
+                (key, lookup)
+            }));
+        }
+
+        Ok(titles
+            .into_iter()
+            .zip(fields)
+            .map(|(column, field)| RenderedColumn {
+                title: column.title,
+                value: field.map(|field| {
+                    serde_json::to_value(PresentationComponent::from(field))
+                        .expect("components serialize")
+                }),
+            })
+            .collect())
+    }
+
+    /// Opens the context menu of `object`.
+    pub fn menu(&self, object: &Value) -> Result<Menu, HarnessError> {
+        let (_, object) = Self::dynamic_object(object)?;
+
+        let blueprint = {
+            let _guard = self.runtime.enter();
+            self.menus.create_resource_menustack(None, object, TAB_ID)?
+        };
+
+        let blueprint = serde_json::to_value(blueprint).expect("menus serialize");
+
+        let mut entries = Vec::new();
+        for section in blueprint["items"].as_array().into_iter().flatten() {
+            Menu::collect_entries(&section["items"], &[], &mut entries);
+        }
+
+        Ok(Menu {
+            id: blueprint["id"].as_str().unwrap_or_default().to_owned(),
+            entries,
+        })
+    }
+
+    /// Calls the action at `path` of an opened menu, see [Menu::find].
+    pub fn run_action(&self, menu: &Menu, path: &[&str]) -> Result<ActionOutcome, HarnessError> {
+        let action_ref = menu
+            .find(path)
+            .and_then(|entry| entry.action_ref.as_deref())
+            .ok_or_else(|| HarnessError::NoSuchAction(path.join(" > ")))?;
+
+        let _guard = self.runtime.enter();
+
+        Ok(self.menus.call_menustack_action(&menu.id, action_ref)?)
+    }
+
+    fn dynamic_object(object: &Value) -> Result<(GroupVersionKind, DynamicObject), HarnessError> {
+        let object: DynamicObject = serde_json::from_value(object.clone())
+            .map_err(|e| HarnessError::InvalidObject(e.to_string()))?;
+
+        let gvk = object
+            .types
+            .as_ref()
+            .ok_or_else(|| HarnessError::InvalidObject("apiVersion and kind are required".into()))?
+            .try_gvk()
+            .map_err(|e| HarnessError::InvalidObject(e.to_string()))?;
+
+        Ok((gvk, object))
+    }
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum HarnessError {
+    #[error("Failed to start the runtime: {0}")]
+    Runtime(#[from] std::io::Error),
+
+    #[error("Fake cluster: {0}")]
+    Cluster(String),
+
+    #[error("{script}: {1}", script = .0.display())]
+    Load(PathBuf, String),
+
+    #[error("Invalid object: {0}")]
+    InvalidObject(String),
+
+    #[error("No presentation named {0}")]
+    NoSuchPresentation(String),
+
+    #[error("No menu action at {0}")]
+    NoSuchAction(String),
+
+    #[error("{0}")]
+    Presentation(String),
+
+    #[error(transparent)]
+    Menu(#[from] ResourceContextMenuError),
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::scripting::{scripts_provider::source_core_lib_dir, test_fixtures::TempPackages};
+
+    fn core_package() -> ScriptHarness {
+        let fixtures = vec![
+            json!({
+                "apiVersion": "apps/v1",
+                "kind": "Deployment",
+                "metadata": {
+                    "namespace": "default",
+                    "name": "web",
+                    "uid": "deploy-uid",
+                    "creationTimestamp": "2026-03-01T10:00:00Z"
+                },
+                "spec": {
+                    "replicas": 3,
+                    "selector": { "matchLabels": { "app": "web" } },
+                    "template": { "metadata": { "labels": { "app": "web" } } }
+                },
+                "status": {
+                    "replicas": 3,
+                    "unavailableReplicas": 1,
+                    "conditions": [{ "type": "Available", "status": "True" }]
+                }
+            }),
+            json!({
+                "apiVersion": "apps/v1",
+                "kind": "ReplicaSet",
+                "metadata": {
+                    "namespace": "default",
+                    "name": "web-7d4b",
+                    "ownerReferences": [{ "apiVersion": "apps/v1", "kind": "Deployment", "name": "web", "uid": "deploy-uid" }]
+                }
+            }),
+        ];
+
+        ScriptHarness::load(
+            &Path::new(env!("CARGO_MANIFEST_DIR")).join("scripts/core"),
+            fixtures,
+            ScriptEnvironment::new(Some(source_core_lib_dir())),
+        )
+        .unwrap()
+    }
+
+    fn pod() -> Value {
+        json!({
+            "apiVersion": "v1",
+            "kind": "Pod",
+            "metadata": {
+                "namespace": "default",
+                "name": "web-7d4b-x2x9k",
+                "ownerReferences": [{ "apiVersion": "apps/v1", "kind": "ReplicaSet", "name": "web-7d4b", "uid": "rs-uid" }]
+            }
+        })
+    }
+
+    #[test]
+    pub fn test_render_columns() {
+        let harness = core_package();
+        let deployment = harness
+            .cluster()
+            .get("apps/v1", "Deployment", "default", "web")
+            .unwrap();
+
+        assert!(harness
+            .presentations(&GroupVersionKind::gvk("apps", "v1", "Deployment"))
+            .unwrap()
+            .contains(&"Deployments (default)".to_owned()));
+
+        let columns = harness
+            .render("Deployments (default)", &deployment)
+            .unwrap();
+        let column = |title: &str| {
+            columns
+                .iter()
+                .find(|c| c.title == title)
+                .unwrap()
+                .value
+                .clone()
+                .unwrap()
+        };
+
+        assert_eq!("web", column("Name")["sortableValue"]);
+        assert_eq!("Available", column("Status")["sortableValue"]);
+        assert_eq!("Progress", column("Pods")["kind"]);
+
+        assert!(matches!(
+            harness.render("Nope", &deployment),
+            Err(HarnessError::NoSuchPresentation(_))
+        ));
+        assert!(
+            harness.diagnostics().is_empty(),
+            "{:?}",
+            harness.diagnostics()
+        );
+    }
+
+    #[test]
+    pub fn test_menu_actions_reach_the_cluster() {
+        let harness = core_package();
+        let menu = harness.menu(&pod()).unwrap();
+
+        // Found through the ReplicaSet in the fake cluster
+        assert!(menu.find(&["Restart Deployment"]).is_some());
+        assert!(menu.find(&["Delete"]).unwrap().dangerous);
+        harness.cluster().take_requests();
+
+        assert_eq!(
+            ActionOutcome::Ok,
+            harness.run_action(&menu, &["Restart Deployment"]).unwrap()
+        );
+
+        let requests = harness.cluster().take_requests();
+        assert_eq!(1, requests.len());
+        assert_eq!("PATCH", requests[0].method);
+        assert_eq!(
+            "/apis/apps/v1/namespaces/default/deployments/web",
+            requests[0].path
+        );
+        assert!(harness
+            .cluster()
+            .get("apps/v1", "Deployment", "default", "web")
+            .unwrap()["spec"]["template"]["metadata"]["annotations"]
+            .is_object());
+    }
+
+    #[test]
+    pub fn test_host_records_clipboard_and_events() {
+        let harness = core_package();
+        let menu = harness.menu(&pod()).unwrap();
+
+        harness.run_action(&menu, &["Copy name"]).unwrap();
+        harness.run_action(&menu, &["Select namespace"]).unwrap();
+
+        assert_eq!(
+            Some("web-7d4b-x2x9k".to_owned()),
+            harness.host().clipboard()
+        );
+
+        let events = harness.host().take_events();
+        assert!(events
+            .iter()
+            .any(|e| e.event == "hyprkube:menu:resource:pick_namespace"
+                && e.payload["namespace"] == "default"));
+        assert!(matches!(
+            harness.run_action(&menu, &["Nope"]),
+            Err(HarnessError::NoSuchAction(_))
+        ));
+    }
+
+    #[test]
+    pub fn test_extension_without_manifest_is_denied_kube_delete() {
+        let package = TempPackages::new();
+        package.write(
+            "menus/delete.rhai",
+            r#"
+            register_resource_contextmenu_section(MenuSection(#{
+                items: |obj| [
+                    ActionButton(#{
+                        title: "Delete",
+                        action: |ctx| kube::delete(obj.apiVersion, obj.kind, obj.metadata.namespace, obj.metadata.name)
+                    })
+                ]
+            }));
+            "#,
+        );
+
+        let harness =
+            ScriptHarness::load(package.root(), vec![pod()], ScriptEnvironment::default()).unwrap();
+        let menu = harness.menu(&pod()).unwrap();
+        harness.cluster().take_requests();
+
+        let ActionOutcome::Error { message, .. } = harness.run_action(&menu, &["Delete"]).unwrap()
+        else {
+            panic!("kube::delete must be denied");
+        };
+
+        assert!(message.contains("kube-delete"), "{message}");
+        assert!(harness.cluster().take_requests().is_empty());
+        assert!(harness
+            .cluster()
+            .get("v1", "Pod", "default", "web-7d4b-x2x9k")
+            .is_some());
+    }
+}
@@ -0,0 +1,77 @@
+use std::{collections::VecDeque, sync::Mutex};
+
+use rhai::{Dynamic, EvalAltResult};
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::scripting::{host::ScriptHost, prompts::PromptDefinition};
+
+/// An event that a menu action emitted to the frontend
+#[derive(Clone, Debug, Serialize)]
+pub struct RecordedEvent {
+    pub event: String,
+    pub payload: Value,
+}
+
+/// Stands in for the app while scripts are tested: records what actions send to the frontend and the clipboard,
+/// and answers prompts from a queue.
+#[derive(Default)]
+pub struct RecordingHost {
+    events: Mutex<Vec<RecordedEvent>>,
+    clipboard: Mutex<Option<String>>,
+    /// The values to submit to the next prompts, `None` cancels a prompt
+    answers: Mutex<VecDeque<Option<serde_json::Map<String, Value>>>>,
+    prompts: Mutex<Vec<PromptDefinition>>,
+}
+
+impl RecordingHost {
+    pub fn answer_prompts(
+        &self,
+        answers: impl IntoIterator<Item = Option<serde_json::Map<String, Value>>>,
+    ) {
+        self.answers.lock().unwrap().extend(answers);
+    }
+
+    pub fn take_events(&self) -> Vec<RecordedEvent> {
+        std::mem::take(&mut self.events.lock().unwrap())
+    }
+
+    pub fn take_prompts(&self) -> Vec<PromptDefinition> {
+        std::mem::take(&mut self.prompts.lock().unwrap())
+    }
+
+    pub fn clipboard(&self) -> Option<String> {
+        self.clipboard.lock().unwrap().clone()
+    }
+}
+
+impl ScriptHost for RecordingHost {
+    fn emit(&self, event: &str, payload: Value) -> Result<(), String> {
+        self.events.lock().unwrap().push(RecordedEvent {
+            event: event.to_owned(),
+            payload,
+        });
+
+        Ok(())
+    }
+
+    fn write_clipboard(&self, text: &str) -> Result<(), String> {
+        *self.clipboard.lock().unwrap() = Some(text.to_owned());
+
+        Ok(())
+    }
+
+    /// Prompts without a queued answer are cancelled, like a user closing the dialog.
+    fn prompt(
+        &self,
+        _tab_id: &str,
+        definition: &PromptDefinition,
+    ) -> Result<Dynamic, Box<EvalAltResult>> {
+        self.prompts.lock().unwrap().push(definition.clone());
+
+        match self.answers.lock().unwrap().pop_front().flatten() {
+            Some(values) => definition.read_answer(values).map(Dynamic::from),
+            None => Ok(Dynamic::UNIT),
+        }
+    }
+}
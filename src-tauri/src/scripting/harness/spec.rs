@@ -0,0 +1,401 @@
+use std::{collections::BTreeMap, path::Path, sync::Arc};
+
+use chrono::{DateTime, Utc};
+use serde::Deserialize;
+use serde_json::Value;
+
+use crate::scripting::{
+    diagnostics::DiagnosticKind,
+    environment::ScriptEnvironment,
+    harness::{HarnessError, ScriptHarness},
+    modules::{storage::MemoryStorage, time::FixedClock},
+};
+
+/// Separates the titles of submenus and the action in an action path, e.g. `Copy data > All as YAML`
+const PATH_SEPARATOR: &str = " > ";
+
+/// A YAML file of test cases for a script package.
+///
+/// ```yaml
+/// now: 2026-03-01T12:00:00Z
+/// fixtures:
+///   - &web
+///     apiVersion: apps/v1
+///     kind: Deployment
+///     metadata: { namespace: default, name: web }
+///     spec: { replicas: 2 }
+/// tests:
+///   - name: shows replicas
+///     object: *web
+///     presentation: Deployments
+///     columns:
+///       Replicas: "2"
+///   - name: scales up
+///     object: *web
+///     menu:
+///       contains: ["Scale > Up"]
+///     action:
+///       path: Scale > Up
+///       outcome: ok
+///       requests:
+///         - { method: PATCH, path: /apis/apps/v1/namespaces/default/deployments/web/scale }
+/// ```
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct TestSpec {
+    /// The current time as seen by scripts, so that ages don't change between runs
+    #[serde(default)]
+    pub now: Option<DateTime<Utc>>,
+    /// The objects in the fake cluster at the start of every test
+    #[serde(default)]
+    pub fixtures: Vec<Value>,
+    pub tests: Vec<TestCase>,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct TestCase {
+    pub name: String,
+    /// The resource that is rendered and whose menu is opened
+    pub object: Value,
+    #[serde(default)]
+    pub presentation: Option<String>,
+    /// Expected columns by title. A string is compared with the text of the column, anything else must be contained
+    /// in the rendered component, e.g. `{ kind: ColoredBox, args: { color: green } }`.
+    #[serde(default)]
+    pub columns: BTreeMap<String, Value>,
+    #[serde(default)]
+    pub menu: Option<MenuExpectation>,
+    #[serde(default)]
+    pub action: Option<ActionCase>,
+}
+
+#[derive(Deserialize, Debug, Default)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct MenuExpectation {
+    /// Paths of items that must be in the menu
+    #[serde(default)]
+    pub contains: Vec<String>,
+    /// Paths of items that must not be in the menu
+    #[serde(default)]
+    pub absent: Vec<String>,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct ActionCase {
+    pub path: String,
+    /// The values submitted to the prompts of the action, in order. `null` cancels a prompt, and so does running
+    /// out of answers.
+    #[serde(default)]
+    pub answers: Vec<Option<serde_json::Map<String, Value>>>,
+    /// `ok`, `error` or what the outcome must contain
+    #[serde(default)]
+    pub outcome: Option<Value>,
+    /// The requests to the cluster, all of them and in order. Each one only needs to contain what is given.
+    #[serde(default)]
+    pub requests: Option<Vec<Value>>,
+    /// Events that must have been sent to the frontend, in any order
+    #[serde(default)]
+    pub events: Vec<Value>,
+    #[serde(default)]
+    pub clipboard: Option<String>,
+    /// What objects in the cluster must contain afterwards
+    #[serde(default)]
+    pub cluster: Vec<Value>,
+}
+
+/// The result of a test case, it passed if there are no failures.
+#[derive(Debug)]
+pub struct TestResult {
+    pub name: String,
+    pub failures: Vec<String>,
+}
+
+impl TestResult {
+    pub fn passed(&self) -> bool {
+        self.failures.is_empty()
+    }
+}
+
+impl TestSpec {
+    pub fn read(path: &Path) -> Result<Self, String> {
+        let content = std::fs::read_to_string(path).map_err(|e| e.to_string())?;
+
+        serde_yaml::from_str(&content).map_err(|e| e.to_string())
+    }
+
+    /// Runs every test case against a freshly loaded package and empty storage, so that they can't affect each other.
+    pub fn run(&self, package: &Path, environment: &ScriptEnvironment) -> Vec<TestResult> {
+        let mut environment = environment.clone();
+
+        if let Some(now) = self.now {
+            environment.clock = Arc::new(FixedClock(now));
+        }
+
+        self.tests
+            .iter()
+            .map(|test| {
+                let environment = ScriptEnvironment {
+                    storage: Arc::new(MemoryStorage::default()),
+                    ..environment.clone()
+                };
+
+                let failures = ScriptHarness::load(package, self.fixtures.clone(), environment)
+                    .and_then(|harness| test.run(&harness))
+                    .unwrap_or_else(|e| vec![e.to_string()]);
+
+                TestResult {
+                    name: test.name.clone(),
+                    failures,
+                }
+            })
+            .collect()
+    }
+}
+
+impl TestCase {
+    fn run(&self, harness: &ScriptHarness) -> Result<Vec<String>, HarnessError> {
+        let mut failures = Vec::new();
+
+        if let Some(presentation) = &self.presentation {
+            let columns = harness.render(presentation, &self.object)?;
+
+            for (title, expected) in &self.columns {
+                match columns.iter().find(|column| &column.title == title) {
+                    None => failures.push(format!("column `{title}`: no such column")),
+                    Some(column) => match &column.value {
+                        Err(e) => failures.push(format!("column `{title}`: {e}")),
+                        Ok(actual) if !column_matches(actual, expected) => failures.push(format!(
+                            "column `{title}`: expected {expected}, got {actual}"
+                        )),
+                        Ok(_) => {}
+                    },
+                }
+            }
+        } else if !self.columns.is_empty() {
+            failures.push("`columns` need a `presentation`".to_owned());
+        }
+
+        if self.menu.is_some() || self.action.is_some() {
+            let menu = harness.menu(&self.object)?;
+            let expectation = self.menu.as_ref();
+
+            for path in expectation.iter().flat_map(|m| &m.contains) {
+                if menu.find(&split_path(path)).is_none() {
+                    failures.push(format!("menu: `{path}` is missing"));
+                }
+            }
+
+            for path in expectation.iter().flat_map(|m| &m.absent) {
+                if menu.find(&split_path(path)).is_some() {
+                    failures.push(format!("menu: `{path}` should not be there"));
+                }
+            }
+
+            if let Some(action) = &self.action {
+                failures.extend(action.run(harness, &menu)?);
+            }
+        }
+
+        // Failed actions are checked by their outcome, everything else is a bug in the scripts
+        failures.extend(
+            harness
+                .diagnostics()
+                .into_iter()
+                .filter(|diagnostic| diagnostic.kind != DiagnosticKind::Action)
+                .map(|diagnostic| format!("script error: {diagnostic}")),
+        );
+
+        Ok(failures)
+    }
+}
+
+impl ActionCase {
+    fn run(
+        &self,
+        harness: &ScriptHarness,
+        menu: &super::Menu,
+    ) -> Result<Vec<String>, HarnessError> {
+        let mut failures = Vec::new();
+
+        harness.cluster().take_requests();
+        harness.host().answer_prompts(self.answers.iter().cloned());
+
+        let outcome = harness.run_action(menu, &split_path(&self.path))?;
+        let outcome = serde_json::to_value(&outcome).expect("outcomes serialize");
+
+        let expected_outcome = match &self.outcome {
+            Some(Value::String(status)) => Some(serde_json::json!({ "status": status })),
+            other => other.clone(),
+        };
+
+        if let Some(expected) = expected_outcome {
+            if !contains(&outcome, &expected) {
+                failures.push(format!("outcome: expected {expected}, got {outcome}"));
+            }
+        }
+
+        let requests =
+            serde_json::to_value(harness.cluster().take_requests()).expect("requests serialize");
+
+        if let Some(expected) = &self.requests {
+            let expected = Value::Array(expected.clone());
+
+            if !contains(&requests, &expected) {
+                failures.push(format!("requests: expected {expected}, got {requests}"));
+            }
+        }
+
+        let events = harness.host().take_events();
+
+        for expected in &self.events {
+            let found = events.iter().any(|event| {
+                contains(
+                    &serde_json::to_value(event).expect("events serialize"),
+                    expected,
+                )
+            });
+
+            if !found {
+                failures.push(format!("events: no event contains {expected}"));
+            }
+        }
+
+        if let Some(expected) = &self.clipboard {
+            let actual = harness.host().clipboard();
+
+            if actual.as_ref() != Some(expected) {
+                failures.push(format!("clipboard: expected {expected:?}, got {actual:?}"));
+            }
+        }
+
+        for expected in &self.cluster {
+            let actual = harness.cluster().get(
+                expected["apiVersion"].as_str().unwrap_or_default(),
+                expected["kind"].as_str().unwrap_or_default(),
+                expected["metadata"]["namespace"]
+                    .as_str()
+                    .unwrap_or_default(),
+                expected["metadata"]["name"].as_str().unwrap_or_default(),
+            );
+
+            match actual {
+                None => failures.push(format!("cluster: no object matches {expected}")),
+                Some(actual) if !contains(&actual, expected) => {
+                    failures.push(format!("cluster: expected {expected}, got {actual}"))
+                }
+                Some(_) => {}
+            }
+        }
+
+        Ok(failures)
+    }
+}
+
+fn split_path(path: &str) -> Vec<&str> {
+    path.split(PATH_SEPARATOR).map(str::trim).collect()
+}
+
+fn column_matches(actual: &Value, expected: &Value) -> bool {
+    match expected {
+        Value::String(text) => actual["sortableValue"] == *text,
+        expected => contains(actual, expected),
+    }
+}
+
+/// Whether `actual` has everything that `expected` has. Objects may have additional keys, arrays must have the same
+/// length and contain their expected elements in the same order.
+pub fn contains(actual: &Value, expected: &Value) -> bool {
+    match (actual, expected) {
+        (Value::Object(actual), Value::Object(expected)) => expected.iter().all(|(key, value)| {
+            actual
+                .get(key)
+                .is_some_and(|actual| contains(actual, value))
+        }),
+        (Value::Array(actual), Value::Array(expected)) => {
+            actual.len() == expected.len()
+                && actual.iter().zip(expected).all(|(a, e)| contains(a, e))
+        }
+        (actual, expected) => actual == expected,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::scripting::{scripts_provider::source_core_lib_dir, test_fixtures::TempPackages};
+
+    #[test]
+    pub fn test_contains() {
+        let actual = json!({ "kind": "Text", "args": ["web"], "properties": { "color": "lime" } });
+
+        assert!(contains(&actual, &json!({ "kind": "Text" })));
+        assert!(contains(
+            &actual,
+            &json!({ "properties": { "color": "lime" } })
+        ));
+        assert!(!contains(
+            &actual,
+            &json!({ "properties": { "color": "red" } })
+        ));
+        assert!(!contains(&actual, &json!({ "missing": null })));
+        assert!(contains(&json!([{ "a": 1, "b": 2 }]), &json!([{ "a": 1 }])));
+        assert!(!contains(&json!([1, 2]), &json!([1])));
+    }
+
+    #[test]
+    pub fn test_core_package_specs() {
+        let core = Path::new(env!("CARGO_MANIFEST_DIR")).join("scripts/core");
+        let spec = TestSpec::read(&core.join("tests/core.yaml")).unwrap();
+        let environment = ScriptEnvironment::new(Some(source_core_lib_dir()));
+
+        for result in spec.run(&core, &environment) {
+            assert!(result.passed(), "{}: {:?}", result.name, result.failures);
+        }
+    }
+
+    #[test]
+    pub fn test_failures_are_reported() {
+        let package = TempPackages::new();
+        package.write(
+            "menus/a.rhai",
+            r#"
+            register_resource_contextmenu_section(MenuSection(#{
+                items: |obj| [
+                    ActionButton(#{ title: "Fail", action: |ctx| { throw "nope"; } }),
+                ]
+            }));
+            "#,
+        );
+
+        let spec: TestSpec = serde_yaml::from_str(
+            r#"
+            tests:
+              - name: expected failure
+                object: { apiVersion: v1, kind: Pod, metadata: { name: a } }
+                action: { path: Fail, outcome: error }
+              - name: wrong expectations
+                object: { apiVersion: v1, kind: Pod, metadata: { name: a } }
+                menu: { contains: [Missing], absent: [Fail] }
+                action: { path: Fail, outcome: ok, clipboard: text }
+            "#,
+        )
+        .unwrap();
+
+        let results = spec.run(package.root(), &ScriptEnvironment::default());
+
+        assert!(results[0].passed(), "{:?}", results[0].failures);
+        assert_eq!(
+            vec![
+                "menu: `Missing` is missing",
+                "menu: `Fail` should not be there",
+            ],
+            results[1].failures[..2]
+        );
+        assert!(results[1].failures[2].starts_with("outcome: expected"));
+        assert!(results[1].failures[3].starts_with("clipboard: expected"));
+    }
+}
@@ -0,0 +1,47 @@
+use rhai::{Dynamic, EvalAltResult};
+use serde::Serialize;
+use tauri_plugin_clipboard_manager::ClipboardExt as _;
+
+use crate::scripting::prompts::{self, PromptDefinition};
+
+/// Everything menu actions do outside of their engine: notifying the frontend, writing to the clipboard and asking
+/// the user for input.
+///
+/// The app is the host of its scripts, the script test harness stands in for it with a host that records.
+pub trait ScriptHost: Send + Sync {
+    fn emit(&self, event: &str, payload: serde_json::Value) -> Result<(), String>;
+
+    fn write_clipboard(&self, text: &str) -> Result<(), String>;
+
+    /// Shows a prompt in the tab `tab_id` and blocks until it has been answered, `()` if the user cancelled.
+    fn prompt(
+        &self,
+        tab_id: &str,
+        definition: &PromptDefinition,
+    ) -> Result<Dynamic, Box<EvalAltResult>>;
+}
+
+impl ScriptHost for tauri::AppHandle {
+    fn emit(&self, event: &str, payload: serde_json::Value) -> Result<(), String> {
+        tauri::Emitter::emit(self, event, payload).map_err(|e| e.to_string())
+    }
+
+    fn write_clipboard(&self, text: &str) -> Result<(), String> {
+        self.clipboard().write_text(text).map_err(|e| e.to_string())
+    }
+
+    fn prompt(
+        &self,
+        tab_id: &str,
+        definition: &PromptDefinition,
+    ) -> Result<Dynamic, Box<EvalAltResult>> {
+        prompts::ask(self, tab_id, definition)
+    }
+}
+
+/// Emits any serializable payload to the frontend of `host`.
+pub fn emit(host: &dyn ScriptHost, event: &str, payload: impl Serialize) -> Result<(), String> {
+    let payload = serde_json::to_value(payload).map_err(|e| e.to_string())?;
+
+    host.emit(event, payload)
+}
@@ -0,0 +1,331 @@
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+    sync::Arc,
+    time::{Duration, SystemTime},
+};
+
+use tauri::async_runtime::spawn;
+use tokio::{task::spawn_blocking, time::MissedTickBehavior};
+
+use crate::{
+    cluster_profiles::ClusterProfileId,
+    scripting::{
+        dashboard_facade::DashboardFacade,
+        module_resolver,
+        resource_context_menu_facade::ResourceContextMenuFacade,
+        resource_details_facade::ResourceDetailsFacade,
+        resource_presentation_facade::ResourcePresentationFacade,
+        scripts_provider::{self, PackageStatus, ScriptType, ScriptsProvider},
+    },
+};
+
+const POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+/// Last modification time of every script of one [ScriptType], or of a library that it could import if that is
+/// more recent.
+type Snapshot = HashMap<PathBuf, Option<SystemTime>>;
+
+#[derive(Debug, Default, PartialEq)]
+struct Changes {
+    /// Scripts that are new or have been modified
+    modified: Vec<PathBuf>,
+    removed: Vec<PathBuf>,
+}
+
+impl Changes {
+    fn is_empty(&self) -> bool {
+        self.modified.is_empty() && self.removed.is_empty()
+    }
+
+    /// Unloads the removed scripts and reloads the modified ones, returns whether anything changed.
+    fn reload<E: std::fmt::Display>(
+        &self,
+        unload: impl Fn(&Path),
+        load: impl Fn(&Path) -> Result<(), E>,
+    ) -> bool {
+        for script in &self.removed {
+            tracing::info!("Unloading {}", script.to_string_lossy());
+            unload(script);
+        }
+
+        for script in &self.modified {
+            tracing::info!("Reloading {}", script.to_string_lossy());
+
+            if let Err(e) = load(script) {
+                tracing::warn!("Keeping previous version of {}: {e}", script.display());
+            }
+        }
+
+        !self.is_empty()
+    }
+}
+
+/// Watches the builtin and extension script directories and reloads changed scripts into the given facades.
+///
+/// Packages that are enabled or disabled for `profile` in the meantime are loaded or unloaded as well.
+/// There are no file system notifications involved, the directories are polled instead. Each poll runs on a
+/// blocking thread, as it reads the package manifests and evaluates the changed scripts. The watcher only holds
+/// weak references to the facades and stops as soon as they are dropped, e.g. when the cluster is reconnected.
+pub fn watch(
+    scripts_provider: Arc<ScriptsProvider>,
+    profile: ClusterProfileId,
+    presentations: &Arc<ResourcePresentationFacade>,
+    details: &Arc<ResourceDetailsFacade>,
+    dashboards: &Arc<DashboardFacade>,
+    menus: &Arc<ResourceContextMenuFacade>,
+) {
+    let presentations = Arc::downgrade(presentations);
+    let details = Arc::downgrade(details);
+    let dashboards = Arc::downgrade(dashboards);
+    let menus = Arc::downgrade(menus);
+
+    spawn(async move {
+        let mut previous = {
+            let scripts_provider = Arc::clone(&scripts_provider);
+            let profile = profile.clone();
+
+            match spawn_blocking(move || Snapshots::take(&scripts_provider, &profile)).await {
+                Ok(snapshots) => snapshots,
+                Err(e) => {
+                    tracing::error!("Failed to take the initial snapshot of scripts: {e}");
+                    return;
+                }
+            }
+        };
+
+        let mut interval = tokio::time::interval(POLL_INTERVAL);
+        interval.set_missed_tick_behavior(MissedTickBehavior::Delay);
+
+        loop {
+            interval.tick().await;
+
+            let (Some(presentations), Some(details), Some(dashboards), Some(menus)) = (
+                presentations.upgrade(),
+                details.upgrade(),
+                dashboards.upgrade(),
+                menus.upgrade(),
+            ) else {
+                tracing::debug!("Scripting facades dropped, stopping script watcher");
+                break;
+            };
+
+            let scripts_provider = Arc::clone(&scripts_provider);
+            let profile = profile.clone();
+
+            let poll = spawn_blocking(move || {
+                let current = Snapshots::take(&scripts_provider, &profile);
+
+                if diff(&previous.presentations, &current.presentations).reload(
+                    |script| presentations.unload_script(script),
+                    |script| presentations.load_script(script),
+                ) {
+                    presentations.notify_reloaded();
+                }
+
+                diff(&previous.details, &current.details).reload(
+                    |script| details.unload_script(script),
+                    |script| details.load_script(script),
+                );
+
+                if diff(&previous.dashboards, &current.dashboards).reload(
+                    |script| dashboards.unload_script(script),
+                    |script| dashboards.load_script(script),
+                ) {
+                    dashboards.notify_reloaded();
+                }
+
+                diff(&previous.menus, &current.menus).reload(
+                    |script| menus.unload_script(script),
+                    |script| menus.load_script(script),
+                );
+
+                current
+            });
+
+            previous = match poll.await {
+                Ok(current) => current,
+                Err(e) => {
+                    tracing::error!("Stopping script watcher, polling failed: {e}");
+                    break;
+                }
+            };
+        }
+    });
+}
+
+/// The snapshots of all script types, taken from a single listing of the active packages.
+struct Snapshots {
+    presentations: Snapshot,
+    details: Snapshot,
+    dashboards: Snapshot,
+    menus: Snapshot,
+}
+
+impl Snapshots {
+    fn take(scripts_provider: &ScriptsProvider, profile: &ClusterProfileId) -> Self {
+        let packages = scripts_provider
+            .list_packages(profile)
+            .unwrap_or_else(|e| {
+                tracing::warn!("Failed to list script packages: {e}");
+                Vec::new()
+            })
+            .into_iter()
+            .filter(|package| package.status == PackageStatus::Active)
+            .map(|package| package.path)
+            .collect::<Vec<_>>();
+
+        let mut libs = LibraryTimes::new(scripts_provider);
+        let mut snapshot = |script_type| snapshot(&packages, script_type, &mut libs);
+
+        Self {
+            presentations: snapshot(ScriptType::Presentation),
+            details: snapshot(ScriptType::Details),
+            dashboards: snapshot(ScriptType::Dashboard),
+            menus: snapshot(ScriptType::Menu),
+        }
+    }
+}
+
+/// Last modification times of the libraries that scripts can import, each one is looked up at most once per poll.
+struct LibraryTimes {
+    core_lib: Option<SystemTime>,
+    package_libs: HashMap<PathBuf, Option<SystemTime>>,
+}
+
+impl LibraryTimes {
+    fn new(scripts_provider: &ScriptsProvider) -> Self {
+        Self {
+            core_lib: scripts_provider
+                .get_core_lib_dir()
+                .ok()
+                .and_then(|dir| module_resolver::last_modified(&dir)),
+            package_libs: HashMap::new(),
+        }
+    }
+
+    /// The most recent modification of the libraries that `script` could import.
+    fn of_script(&mut self, script: &Path) -> Option<SystemTime> {
+        let package_lib = scripts_provider::package_dir_of(script).and_then(|package| {
+            *self
+                .package_libs
+                .entry(package.to_owned())
+                .or_insert_with(|| {
+                    module_resolver::last_modified(&scripts_provider::lib_dir_of(package))
+                })
+        });
+
+        package_lib.max(self.core_lib)
+    }
+}
+
+fn snapshot(packages: &[PathBuf], script_type: ScriptType, libs: &mut LibraryTimes) -> Snapshot {
+    packages
+        .iter()
+        .flat_map(|package| scripts_provider::scripts_in_package(package, script_type))
+        .map(|script| {
+            let modified = std::fs::metadata(&script).and_then(|m| m.modified()).ok();
+            let libs_modified = libs.of_script(&script);
+
+            (script, modified.max(libs_modified))
+        })
+        .collect()
+}
+
+fn diff(previous: &Snapshot, current: &Snapshot) -> Changes {
+    let mut changes = Changes {
+        modified: current
+            .iter()
+            .filter(|(script, modified)| previous.get(*script) != Some(modified))
+            .map(|(script, _)| script.clone())
+            .collect(),
+        removed: previous
+            .keys()
+            .filter(|script| !current.contains_key(*script))
+            .cloned()
+            .collect(),
+    };
+
+    // Reload in the same order as the initial evaluation
+    changes.modified.sort();
+    changes.removed.sort();
+
+    changes
+}
+
+/// Replaces all entries that were registered by `script` with `replacements`.
+///
+/// The replacements take the place of the first replaced entry, so that a reloaded script keeps its
+/// position, e.g. for the choice of the default presentation. Entries of new scripts are appended.
+pub fn splice_script_entries<T>(
+    entries: &mut Vec<T>,
+    script: &Path,
+    replacements: Vec<T>,
+    script_of: impl Fn(&T) -> &Path,
+) {
+    let position = entries
+        .iter()
+        .position(|entry| script_of(entry) == script)
+        .unwrap_or(entries.len());
+
+    entries.retain(|entry| script_of(entry) != script);
+
+    let position = position.min(entries.len());
+    entries.splice(position..position, replacements);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(secs: u64) -> Option<SystemTime> {
+        Some(SystemTime::UNIX_EPOCH + Duration::from_secs(secs))
+    }
+
+    #[test]
+    pub fn test_diff() {
+        let previous = Snapshot::from([
+            ("a.rhai".into(), at(1)),
+            ("b.rhai".into(), at(1)),
+            ("c.rhai".into(), at(1)),
+        ]);
+
+        let current = Snapshot::from([
+            ("a.rhai".into(), at(1)),
+            ("b.rhai".into(), at(2)),
+            ("d.rhai".into(), at(1)),
+        ]);
+
+        assert_eq!(
+            Changes {
+                modified: vec!["b.rhai".into(), "d.rhai".into()],
+                removed: vec!["c.rhai".into()],
+            },
+            diff(&previous, &current)
+        );
+        assert!(diff(&current, &current).is_empty());
+    }
+
+    #[test]
+    pub fn test_splice_keeps_position() {
+        let mut entries = vec![("a", 1), ("b", 1), ("b", 2), ("c", 1)];
+
+        splice_script_entries(&mut entries, Path::new("b"), vec![("b", 3)], |e| {
+            Path::new(e.0)
+        });
+
+        assert_eq!(vec![("a", 1), ("b", 3), ("c", 1)], entries);
+    }
+
+    #[test]
+    pub fn test_splice_appends_new_and_removes() {
+        let mut entries = vec![("a", 1), ("b", 1)];
+
+        splice_script_entries(&mut entries, Path::new("c"), vec![("c", 1)], |e| {
+            Path::new(e.0)
+        });
+        splice_script_entries(&mut entries, Path::new("a"), vec![], |e| Path::new(e.0));
+
+        assert_eq!(vec![("b", 1), ("c", 1)], entries);
+    }
+}
@@ -0,0 +1,297 @@
+use std::{
+    cell::Cell,
+    fmt::Display,
+    io,
+    time::{Duration, Instant},
+};
+
+use rhai::EvalAltResult;
+
+/// Operations per call, enough for any reasonable loop over the fields of a resource
+const MAX_OPERATIONS: u64 = 1_000_000;
+const MAX_CALL_LEVELS: usize = 64;
+const MAX_STRING_SIZE: usize = 8 * 1024 * 1024;
+const MAX_ARRAY_SIZE: usize = 100_000;
+const MAX_MAP_SIZE: usize = 100_000;
+/// Text that the `json` and `yaml` modules parse or produce, a generous bound for any ConfigMap or Secret
+pub const MAX_DOCUMENT_SIZE: usize = 1024 * 1024;
+
+/// Checking the clock on every single operation would be needlessly expensive
+const CLOCK_CHECK_INTERVAL: u64 = 1024;
+
+/// Evaluation of a whole script, i.e. registering its sections or presentations
+pub const EVALUATION_TIME_LIMIT: Duration = Duration::from_secs(5);
+/// A `matcher` function, called for every kind that is displayed
+pub const MATCHER_TIME_LIMIT: Duration = Duration::from_millis(250);
+/// A column `render` function, called for every cell of a resource table
+pub const RENDER_TIME_LIMIT: Duration = Duration::from_millis(250);
+/// A menu `items` function, called whenever a context menu opens
+pub const ITEMS_TIME_LIMIT: Duration = Duration::from_secs(1);
+/// A menu action, which may wait for the cluster
+pub const ACTION_TIME_LIMIT: Duration = Duration::from_secs(30);
+/// How long a menu action waits for the user to answer a prompt, not counted against [ACTION_TIME_LIMIT]
+pub const PROMPT_TIME_LIMIT: Duration = Duration::from_secs(5 * 60);
+
+thread_local! {
+    /// Deadline of the call that is currently running on this thread, along with its time limit
+    static DEADLINE: Cell<Option<(Instant, Duration)>> = const { Cell::new(None) };
+}
+
+/// Configures operation and size limits and the watchdog that enforces [with_time_limit].
+///
+/// Exceeding a limit fails the running call with an error, so that a runaway script can never
+/// freeze the task that called it.
+pub fn apply(engine: &mut rhai::Engine) {
+    engine
+        .set_max_expr_depths(64, 32)
+        .set_max_operations(MAX_OPERATIONS)
+        .set_max_call_levels(MAX_CALL_LEVELS)
+        .set_max_string_size(MAX_STRING_SIZE)
+        .set_max_array_size(MAX_ARRAY_SIZE)
+        .set_max_map_size(MAX_MAP_SIZE);
+
+    engine.on_progress(|operations| {
+        if operations % CLOCK_CHECK_INTERVAL != 0 {
+            return None;
+        }
+
+        DEADLINE.with(|deadline| match deadline.get() {
+            Some((deadline, _)) if Instant::now() >= deadline => Some(rhai::Dynamic::UNIT),
+            _ => None,
+        })
+    });
+}
+
+/// Runs a script call that is aborted once it takes longer than `limit`.
+///
+/// Nested calls, e.g. a script function invoked from within a native function, never extend the
+/// deadline of the outer call.
+pub fn with_time_limit<T>(
+    limit: Duration,
+    call: impl FnOnce() -> Result<T, Box<EvalAltResult>>,
+) -> Result<T, Box<EvalAltResult>> {
+    let outer = DEADLINE.get();
+
+    let own = (Instant::now() + limit, limit);
+    let effective = match outer {
+        Some(outer) if outer.0 < own.0 => outer,
+        _ => own,
+    };
+
+    DEADLINE.set(Some(effective));
+    let result = {
+        let _restore = RestoreDeadline(outer);
+        call()
+    };
+
+    result.map_err(|e| match *e {
+        EvalAltResult::ErrorTerminated(_, position) => EvalAltResult::ErrorRuntime(
+            format!(
+                "Script took longer than the limit of {}ms and was stopped",
+                effective.1.as_millis()
+            )
+            .into(),
+            position,
+        )
+        .into(),
+        _ => e,
+    })
+}
+
+/// Puts back the deadline of the outer call, even if the inner one panics.
+struct RestoreDeadline(Option<(Instant, Duration)>);
+
+impl Drop for RestoreDeadline {
+    fn drop(&mut self) {
+        DEADLINE.set(self.0);
+    }
+}
+
+/// Runs `wait` without counting the time it takes against the deadline of the running call.
+///
+/// Meant for native functions that wait for the user, e.g. to fill in a prompt, who must not be rushed by
+/// the time limit of a menu action.
+pub fn paused<T>(wait: impl FnOnce() -> T) -> T {
+    let started = Instant::now();
+    let result = wait();
+
+    if let Some((deadline, limit)) = DEADLINE.get() {
+        DEADLINE.set(Some((deadline + started.elapsed(), limit)));
+    }
+
+    result
+}
+
+/// Fails if a document that a script parses or serializes is larger than [MAX_DOCUMENT_SIZE].
+pub fn check_document_size(function: &str, size: usize) -> Result<(), Box<EvalAltResult>> {
+    if size > MAX_DOCUMENT_SIZE {
+        return Err(format!(
+            "{function}: document of {size} bytes exceeds the limit of {MAX_DOCUMENT_SIZE} bytes"
+        )
+        .into());
+    }
+
+    Ok(())
+}
+
+/// Serializes a document for a script into a buffer that refuses to grow beyond [MAX_DOCUMENT_SIZE], so that an
+/// oversized document fails early instead of being built in full first.
+pub fn serialize_document<E: Display>(
+    function: &str,
+    serialize: impl FnOnce(&mut DocumentWriter) -> Result<(), E>,
+) -> Result<String, Box<EvalAltResult>> {
+    let mut writer = DocumentWriter::default();
+    let result = serialize(&mut writer);
+
+    if writer.exceeded {
+        return Err(
+            format!("{function}: document exceeds the limit of {MAX_DOCUMENT_SIZE} bytes").into(),
+        );
+    }
+    result.map_err(|e| format!("{function}: {e}"))?;
+
+    String::from_utf8(writer.document).map_err(|e| format!("{function}: {e}").into())
+}
+
+#[derive(Default)]
+pub struct DocumentWriter {
+    document: Vec<u8>,
+    exceeded: bool,
+}
+
+impl io::Write for DocumentWriter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        if self.document.len() + buf.len() > MAX_DOCUMENT_SIZE {
+            self.exceeded = true;
+            return Err(io::Error::other("document size limit exceeded"));
+        }
+
+        self.document.extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn engine() -> rhai::Engine {
+        let mut engine = rhai::Engine::new();
+        apply(&mut engine);
+        engine
+    }
+
+    fn call(engine: &rhai::Engine, script: &str, limit: Duration) -> Box<EvalAltResult> {
+        let ast = engine.compile(script).unwrap();
+        let render = engine.eval_ast::<rhai::FnPtr>(&ast).unwrap();
+
+        with_time_limit(limit, || {
+            render.call::<rhai::Dynamic>(engine, &ast, (rhai::Map::new(),))
+        })
+        .unwrap_err()
+    }
+
+    #[test]
+    pub fn test_infinite_loop_is_stopped_by_watchdog() {
+        // Without an operation limit, only the watchdog can stop this
+        let mut engine = engine();
+        engine.set_max_operations(0);
+
+        let started = Instant::now();
+        let error = call(&engine, "|obj| { loop {} }", Duration::from_millis(50));
+
+        assert!(started.elapsed() < Duration::from_secs(5));
+        assert!(error.to_string().contains("limit of 50ms"), "{error}");
+    }
+
+    #[test]
+    pub fn test_operation_limit() {
+        let error = call(
+            &engine(),
+            "|obj| { let i = 0; loop { i += 1; } }",
+            Duration::from_secs(60),
+        );
+
+        assert!(
+            matches!(*error, EvalAltResult::ErrorTooManyOperations(_)),
+            "{error}"
+        );
+    }
+
+    #[test]
+    pub fn test_size_limits() {
+        let string = call(
+            &engine(),
+            r#"|obj| { let s = "x"; loop { s += s; } }"#,
+            Duration::from_secs(60),
+        );
+        let array = call(
+            &engine(),
+            "|obj| { let a = [1]; loop { a += a; } }",
+            Duration::from_secs(60),
+        );
+
+        assert!(
+            matches!(*string, EvalAltResult::ErrorDataTooLarge(..)),
+            "{string}"
+        );
+        assert!(
+            matches!(*array, EvalAltResult::ErrorDataTooLarge(..)),
+            "{array}"
+        );
+    }
+
+    #[test]
+    pub fn test_nested_calls_keep_outer_deadline() {
+        let result = with_time_limit(Duration::from_millis(10), || {
+            with_time_limit(Duration::from_secs(60), || Ok(DEADLINE.get().unwrap().1))
+        });
+
+        assert_eq!(Duration::from_millis(10), result.unwrap());
+        assert!(DEADLINE.get().is_none());
+    }
+
+    #[test]
+    pub fn test_panicking_call_restores_deadline() {
+        let panicked = std::panic::catch_unwind(|| {
+            with_time_limit(Duration::from_millis(10), || -> Result<(), _> {
+                panic!("native function failed")
+            })
+        });
+
+        assert!(panicked.is_err());
+        assert!(DEADLINE.get().is_none());
+    }
+
+    #[test]
+    pub fn test_paused_time_does_not_count() {
+        let result = with_time_limit(Duration::from_millis(20), || {
+            let before = DEADLINE.get().unwrap().0;
+            paused(|| std::thread::sleep(Duration::from_millis(40)));
+
+            Ok(DEADLINE.get().unwrap().0 - before)
+        });
+
+        assert!(result.unwrap() >= Duration::from_millis(40));
+        assert!(DEADLINE.get().is_none());
+    }
+
+    #[test]
+    pub fn test_well_behaved_scripts_are_unaffected() {
+        let engine = engine();
+        let ast = engine
+            .compile("|obj| { let sum = 0; for i in 0..1000 { sum += i; } sum }")
+            .unwrap();
+        let render = engine.eval_ast::<rhai::FnPtr>(&ast).unwrap();
+
+        let sum = with_time_limit(Duration::from_secs(1), || {
+            render.call::<i64>(&engine, &ast, (rhai::Map::new(),))
+        });
+
+        assert_eq!(499500, sum.unwrap());
+    }
+}
@@ -1,6 +1,23 @@
+pub mod capabilities;
+pub mod dashboard_facade;
+pub mod diagnostics;
+pub mod environment;
+#[cfg(any(test, feature = "script-test"))]
+pub mod harness;
+pub mod host;
+pub mod hot_reload;
+pub mod limits;
+pub mod module_resolver;
 pub mod modules;
+pub mod package_installer;
+pub mod package_manifest;
+pub mod prompts;
 pub mod resource_context_menu;
 pub mod resource_context_menu_facade;
+pub mod resource_details_facade;
 pub mod resource_presentation_facade;
+pub mod script_registry;
 pub mod scripts_provider;
+#[cfg(test)]
+pub mod test_fixtures;
 pub mod types;
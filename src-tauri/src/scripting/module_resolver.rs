@@ -0,0 +1,262 @@
+use std::{
+    cell::RefCell,
+    collections::HashMap,
+    path::{Path, PathBuf},
+    sync::RwLock,
+    time::SystemTime,
+};
+
+use rhai::{EvalAltResult, Module, ModuleResolver, Position, Scope, Shared};
+
+use crate::scripting::{environment, scripts_provider};
+
+struct CachedLibrary {
+    modified: Option<SystemTime>,
+    module: Shared<Module>,
+}
+
+thread_local! {
+    /// Libraries that are being evaluated on this thread right now, to detect circular imports
+    static IMPORTING: RefCell<Vec<PathBuf>> = const { RefCell::new(Vec::new()) };
+}
+
+/// Resolves `import "name"` statements to library scripts, e.g. `import "k8s_helpers" as h;`.
+///
+/// A library is looked up in the `lib` directory of the importing script's package first, then in the `lib`
+/// directory of the builtin core package. Library names are plain file names without the `.rhai` extension,
+/// so a script can never import from outside of these directories.
+///
+/// Libraries are evaluated with the engine that imports them and cached until their file changes. Every
+/// engine needs its own resolver, because the same library behaves differently depending on the modules of the
+/// engine.
+pub struct LibraryResolver {
+    core_lib: Option<PathBuf>,
+    cache: RwLock<HashMap<PathBuf, CachedLibrary>>,
+}
+
+impl LibraryResolver {
+    pub fn new(core_lib: Option<PathBuf>) -> Self {
+        Self {
+            core_lib,
+            cache: RwLock::new(HashMap::new()),
+        }
+    }
+
+    /// The files that `name` could refer to when imported by `source`, in order of precedence.
+    fn candidates(&self, source: Option<&str>, name: &str) -> Vec<PathBuf> {
+        let package_lib = source
+            .map(Path::new)
+            .and_then(scripts_provider::package_dir_of)
+            .map(scripts_provider::lib_dir_of);
+
+        package_lib
+            .into_iter()
+            .chain(self.core_lib.clone())
+            .map(|dir| dir.join(format!("{name}.rhai")))
+            .collect()
+    }
+
+    fn load(
+        &self,
+        engine: &rhai::Engine,
+        name: &str,
+        path: &Path,
+        pos: Position,
+    ) -> Result<Shared<Module>, Box<EvalAltResult>> {
+        let modified = std::fs::metadata(path).and_then(|m| m.modified()).ok();
+
+        if let Some(cached) = self.cache.read().unwrap().get(path) {
+            if cached.modified == modified {
+                return Ok(Shared::clone(&cached.module));
+            }
+        }
+
+        if IMPORTING.with_borrow(|importing| importing.iter().any(|p| p == path)) {
+            return Err(EvalAltResult::ErrorInModule(
+                name.to_owned(),
+                format!("Circular import of {}", path.display()).into(),
+                pos,
+            )
+            .into());
+        }
+
+        IMPORTING.with_borrow_mut(|importing| importing.push(path.to_owned()));
+        let module: Result<_, Box<EvalAltResult>> = environment::compile_file(engine, path)
+            .and_then(|ast| Module::eval_ast_as_new(Scope::new(), &ast, engine))
+            .map(Shared::new)
+            .map_err(|e| EvalAltResult::ErrorInModule(name.to_owned(), e, pos).into());
+        IMPORTING.with_borrow_mut(|importing| importing.pop());
+
+        let module = module?;
+
+        self.cache.write().unwrap().insert(
+            path.to_owned(),
+            CachedLibrary {
+                modified,
+                module: Shared::clone(&module),
+            },
+        );
+
+        Ok(module)
+    }
+}
+
+impl ModuleResolver for LibraryResolver {
+    fn resolve(
+        &self,
+        engine: &rhai::Engine,
+        source: Option<&str>,
+        path: &str,
+        pos: Position,
+    ) -> Result<Shared<Module>, Box<EvalAltResult>> {
+        let is_plain_name = !path.is_empty()
+            && path
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-');
+
+        if !is_plain_name {
+            return Err(EvalAltResult::ErrorInModule(
+                path.to_owned(),
+                "Libraries are imported by their file name without extension, e.g. \"k8s_helpers\""
+                    .into(),
+                pos,
+            )
+            .into());
+        }
+
+        let file = self
+            .candidates(source, path)
+            .into_iter()
+            .find(|file| file.is_file())
+            .ok_or_else(|| EvalAltResult::ErrorModuleNotFound(path.to_owned(), pos))?;
+
+        self.load(engine, path, &file, pos)
+    }
+}
+
+/// The latest modification of the library files in `dir`, including additions and removals.
+pub fn last_modified(dir: &Path) -> Option<SystemTime> {
+    let modified = |path: &Path| std::fs::metadata(path).and_then(|m| m.modified()).ok();
+
+    let files = std::fs::read_dir(dir).into_iter().flatten().flatten();
+
+    files
+        .map(|entry| modified(&entry.path()))
+        .chain([modified(dir)])
+        .max()
+        .flatten()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scripting::test_fixtures::TempPackages;
+
+    fn engine(packages: &TempPackages) -> rhai::Engine {
+        let mut engine = rhai::Engine::new();
+        engine.set_module_resolver(LibraryResolver::new(Some(packages.root().join("core/lib"))));
+        engine
+    }
+
+    fn eval(engine: &rhai::Engine, script: &Path) -> Result<String, String> {
+        engine
+            .compile_file(script.to_owned())
+            .and_then(|ast| engine.eval_ast::<String>(&ast))
+            .map_err(|e| e.to_string())
+    }
+
+    #[test]
+    pub fn test_own_package_before_core() {
+        let packages = TempPackages::new();
+        packages.write("core/lib/helpers.rhai", r#"fn origin() { "core" }"#);
+        packages.write("core/lib/shared.rhai", r#"fn origin() { "core" }"#);
+        packages.write("fleet/lib/helpers.rhai", r#"fn origin() { "fleet" }"#);
+
+        let fleet = packages.write(
+            "fleet/menus/a.rhai",
+            r#"import "helpers" as h; import "shared" as s; `${h::origin()}+${s::origin()}`"#,
+        );
+        let core = packages.write("core/menus/a.rhai", r#"import "helpers" as h; h::origin()"#);
+
+        let engine = engine(&packages);
+
+        assert_eq!(Ok("fleet+core".to_owned()), eval(&engine, &fleet));
+        assert_eq!(Ok("core".to_owned()), eval(&engine, &core));
+    }
+
+    #[test]
+    pub fn test_libraries_import_from_their_package() {
+        let packages = TempPackages::new();
+        packages.write("fleet/lib/base.rhai", r#"fn name() { "fleet" }"#);
+        packages.write(
+            "fleet/lib/helpers.rhai",
+            r#"import "base" as b; fn greet() { `hello ${b::name()}` }"#,
+        );
+
+        let script = packages.write(
+            "fleet/details/a.rhai",
+            r#"import "helpers" as h; let f = || h::greet(); f.call()"#,
+        );
+
+        assert_eq!(
+            Ok("hello fleet".to_owned()),
+            eval(&engine(&packages), &script)
+        );
+    }
+
+    #[test]
+    pub fn test_cached_until_modified() {
+        let packages = TempPackages::new();
+        let lib = packages.write("fleet/lib/counter.rhai", "export const VALUE = 1;");
+        let script = packages.write(
+            "fleet/menus/a.rhai",
+            r#"import "counter" as c; `${c::VALUE}`"#,
+        );
+
+        let engine = engine(&packages);
+        assert_eq!(Ok("1".to_owned()), eval(&engine, &script));
+
+        // Same modification time, the cached module is used
+        let modified = std::fs::metadata(&lib).unwrap().modified().unwrap();
+        std::fs::write(&lib, "export const VALUE = 2;").unwrap();
+        std::fs::File::options()
+            .write(true)
+            .open(&lib)
+            .unwrap()
+            .set_modified(modified)
+            .unwrap();
+        assert_eq!(Ok("1".to_owned()), eval(&engine, &script));
+
+        std::fs::File::options()
+            .write(true)
+            .open(&lib)
+            .unwrap()
+            .set_modified(modified + std::time::Duration::from_secs(1))
+            .unwrap();
+        assert_eq!(Ok("2".to_owned()), eval(&engine, &script));
+    }
+
+    #[test]
+    pub fn test_resolution_errors() {
+        let packages = TempPackages::new();
+        packages.write("fleet/lib/a.rhai", r#"import "b" as b;"#);
+        packages.write("fleet/lib/b.rhai", r#"import "a" as a;"#);
+
+        let engine = engine(&packages);
+
+        let missing = packages.write("fleet/menus/missing.rhai", r#"import "nope" as n; """#);
+        let escaping = packages.write(
+            "fleet/menus/escaping.rhai",
+            r#"import "../menus/x" as n; """#,
+        );
+        let circular = packages.write("fleet/menus/circular.rhai", r#"import "a" as a; """#);
+
+        assert!(eval(&engine, &missing).unwrap_err().contains("nope"));
+        assert!(eval(&engine, &escaping)
+            .unwrap_err()
+            .contains("file name without extension"));
+        assert!(eval(&engine, &circular)
+            .unwrap_err()
+            .contains("Circular import"));
+    }
+}
@@ -1,26 +1,25 @@
 use std::sync::Arc;
 
 use rhai::{FuncRegistration, Module};
-use tauri_plugin_clipboard_manager::ClipboardExt as _;
 
-use crate::scripting::types::commons::CallbackContext;
+use crate::scripting::{
+    capabilities::{self, Capabilities, Capability},
+    types::commons::CallbackContext,
+};
 
-pub fn build_module(app: tauri::AppHandle) -> Module {
+pub fn build_module(capabilities: &Capabilities) -> Module {
     let mut clipboard_module = Module::new();
+    let granted = capabilities.contains(&Capability::Clipboard);
 
-    {
-        let app = app.clone();
+    FuncRegistration::new("write_text").set_into_module(
+        &mut clipboard_module,
+        move |ctx: Arc<CallbackContext>, data: &str| -> Result<(), Box<rhai::EvalAltResult>> {
+            capabilities::require(granted, "clipboard::write_text", Capability::Clipboard)?;
 
-        FuncRegistration::new("write_text").set_into_module(
-            &mut clipboard_module,
-            move |_: Arc<CallbackContext>, data: &str| -> Result<(), Box<rhai::EvalAltResult>> {
-                app.clipboard()
-                    .write_text(data)
-                    .map_err(|e| e.to_string())?;
-                Ok(())
-            },
-        );
-    }
+            ctx.host.write_clipboard(data)?;
+            Ok(())
+        },
+    );
 
     clipboard_module
 }
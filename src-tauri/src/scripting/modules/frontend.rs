@@ -2,6 +2,17 @@ use kube::api::GroupVersionKind;
 use rhai::plugin::*;
 use serde::Serialize;
 
+use crate::scripting::capabilities::{Capabilities, Capability};
+
+/// Builds the Rhai `frontend` module. Without the `frontend` capability, every function of it fails.
+pub fn build_module(capabilities: &Capabilities) -> Module {
+    if capabilities.contains(&Capability::Frontend) {
+        exported_module!(frontend_rhai)
+    } else {
+        exported_module!(frontend_denied_rhai)
+    }
+}
+
 #[derive(Serialize, Clone)]
 #[serde(rename_all = "camelCase")]
 struct FrontendTriggerResourceEdit {
@@ -43,15 +54,59 @@ struct FrontendTriggerLogView {
     pub tab_id: String,
 }
 
+#[derive(Serialize, Clone, Copy, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+enum NotificationLevel {
+    Info,
+    Success,
+    Warning,
+    Error,
+}
+
+impl NotificationLevel {
+    fn parse(level: &str) -> Result<Self, Box<rhai::EvalAltResult>> {
+        match level {
+            "info" => Ok(Self::Info),
+            "success" => Ok(Self::Success),
+            "warning" => Ok(Self::Warning),
+            "error" => Ok(Self::Error),
+            _ => Err(format!(
+                "frontend::notify: unknown level `{level}`, expected info, success, warning or error"
+            )
+            .into()),
+        }
+    }
+}
+
+#[derive(Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+struct FrontendNotification {
+    pub level: NotificationLevel,
+    pub message: String,
+    pub tab_id: String,
+}
+
+#[derive(Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+struct FrontendProgress {
+    pub message: String,
+    /// `None` for progress that cannot be measured
+    pub percent: Option<i64>,
+    pub tab_id: String,
+}
+
 #[export_module]
 pub mod frontend_rhai {
     use std::sync::Arc;
 
     use kube::core::GroupVersion;
     use serde::Serialize;
-    use tauri::Emitter as _;
 
-    use crate::scripting::types::{commons::CallbackContext, ResourceKind, ResourceRef};
+    use crate::scripting::{
+        host,
+        prompts::PromptDefinition,
+        types::{commons::CallbackContext, ResourceKind, ResourceRef},
+    };
 
     #[rhai_fn(return_raw)]
     pub fn open_resource_editor(
@@ -151,13 +206,188 @@ pub mod frontend_rhai {
         )
     }
 
-    fn emit<T: Serialize + Clone>(
+    /// Asks the user to fill in a form and waits for the answer, `()` if the user cancelled.
+    #[rhai_fn(return_raw)]
+    pub fn prompt(
+        ctx: Arc<CallbackContext>,
+        definition: rhai::Map,
+    ) -> Result<rhai::Dynamic, Box<rhai::EvalAltResult>> {
+        ctx.host
+            .prompt(&ctx.frontend_tab, &PromptDefinition::parse(definition)?)
+    }
+
+    /// Shows a toast in the tab that opened the menu, `level` is one of info, success, warning or error.
+    #[rhai_fn(return_raw)]
+    pub fn notify(
+        ctx: Arc<CallbackContext>,
+        level: &str,
+        message: &str,
+    ) -> Result<(), Box<rhai::EvalAltResult>> {
+        let frontend_tab = ctx.frontend_tab.to_owned();
+
+        emit(
+            ctx,
+            "hyprkube:menu:notify",
+            FrontendNotification {
+                level: NotificationLevel::parse(level)?,
+                message: message.to_owned(),
+                tab_id: frontend_tab,
+            },
+        )
+    }
+
+    /// Reports what a long-running action is doing. The progress is hidden once the action returned.
+    #[rhai_fn(return_raw)]
+    pub fn progress(
+        ctx: Arc<CallbackContext>,
+        message: &str,
+    ) -> Result<(), Box<rhai::EvalAltResult>> {
+        emit_progress(ctx, message, None)
+    }
+
+    /// Like `progress(ctx, message)` with a percentage, which is clamped to 0..=100.
+    #[rhai_fn(name = "progress", return_raw)]
+    pub fn progress_percent(
+        ctx: Arc<CallbackContext>,
+        message: &str,
+        percent: i64,
+    ) -> Result<(), Box<rhai::EvalAltResult>> {
+        emit_progress(ctx, message, Some(percent.clamp(0, 100)))
+    }
+
+    fn emit_progress(
+        ctx: Arc<CallbackContext>,
+        message: &str,
+        percent: Option<i64>,
+    ) -> Result<(), Box<rhai::EvalAltResult>> {
+        let frontend_tab = ctx.frontend_tab.to_owned();
+
+        emit(
+            ctx,
+            "hyprkube:menu:progress",
+            FrontendProgress {
+                message: message.to_owned(),
+                percent,
+                tab_id: frontend_tab,
+            },
+        )
+    }
+
+    fn emit<T: Serialize>(
         ctx: Arc<CallbackContext>,
         event: &str,
         payload: T,
     ) -> Result<(), Box<rhai::EvalAltResult>> {
-        ctx.app_handle
-            .emit(event, payload)
-            .map_err(|e| e.to_string().into())
+        host::emit(&*ctx.host, event, payload).map_err(|e| e.into())
+    }
+}
+
+/// Same signatures as [frontend_rhai], so that scripts get a clear error instead of "function not found".
+#[export_module]
+mod frontend_denied_rhai {
+    use std::sync::Arc;
+
+    use crate::scripting::{
+        capabilities::{self, Capability},
+        types::{commons::CallbackContext, ResourceKind, ResourceRef},
+    };
+
+    #[rhai_fn(return_raw)]
+    pub fn open_resource_editor(
+        _ctx: Arc<CallbackContext>,
+        _resource: ResourceRef,
+    ) -> Result<(), Box<rhai::EvalAltResult>> {
+        denied("frontend::open_resource_editor")
+    }
+
+    #[rhai_fn(return_raw)]
+    pub fn exec_shell(
+        _ctx: Arc<CallbackContext>,
+        _namespace: &str,
+        _name: &str,
+        _container: &str,
+    ) -> Result<(), Box<rhai::EvalAltResult>> {
+        denied("frontend::exec_shell")
+    }
+
+    #[rhai_fn(return_raw)]
+    pub fn open_logs(
+        _ctx: Arc<CallbackContext>,
+        _namespace: &str,
+        _name: &str,
+        _container: &str,
+    ) -> Result<(), Box<rhai::EvalAltResult>> {
+        denied("frontend::open_logs")
+    }
+
+    #[rhai_fn(return_raw)]
+    pub fn pick_namespace(
+        _ctx: Arc<CallbackContext>,
+        _namespace: &str,
+    ) -> Result<(), Box<rhai::EvalAltResult>> {
+        denied("frontend::pick_namespace")
+    }
+
+    #[rhai_fn(return_raw)]
+    pub fn pick_kind(
+        _ctx: Arc<CallbackContext>,
+        _kind: ResourceKind,
+    ) -> Result<(), Box<rhai::EvalAltResult>> {
+        denied("frontend::pick_kind")
+    }
+
+    #[rhai_fn(return_raw)]
+    pub fn prompt(
+        _ctx: Arc<CallbackContext>,
+        _definition: rhai::Map,
+    ) -> Result<rhai::Dynamic, Box<rhai::EvalAltResult>> {
+        denied("frontend::prompt").map(|_| rhai::Dynamic::UNIT)
+    }
+
+    #[rhai_fn(return_raw)]
+    pub fn notify(
+        _ctx: Arc<CallbackContext>,
+        _level: &str,
+        _message: &str,
+    ) -> Result<(), Box<rhai::EvalAltResult>> {
+        denied("frontend::notify")
+    }
+
+    #[rhai_fn(return_raw)]
+    pub fn progress(
+        _ctx: Arc<CallbackContext>,
+        _message: &str,
+    ) -> Result<(), Box<rhai::EvalAltResult>> {
+        denied("frontend::progress")
+    }
+
+    #[rhai_fn(name = "progress", return_raw)]
+    pub fn progress_percent(
+        _ctx: Arc<CallbackContext>,
+        _message: &str,
+        _percent: i64,
+    ) -> Result<(), Box<rhai::EvalAltResult>> {
+        denied("frontend::progress")
+    }
+
+    fn denied(function: &str) -> Result<(), Box<rhai::EvalAltResult>> {
+        capabilities::require(false, function, Capability::Frontend)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    pub fn test_notification_levels() {
+        assert_eq!(
+            NotificationLevel::Warning,
+            NotificationLevel::parse("warning").unwrap()
+        );
+        assert!(NotificationLevel::parse("fatal")
+            .unwrap_err()
+            .to_string()
+            .contains("unknown level `fatal`"));
     }
 }
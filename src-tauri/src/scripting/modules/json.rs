@@ -0,0 +1,101 @@
+use rhai::plugin::*;
+
+#[export_module]
+pub mod json_rhai {
+    use rhai::{Dynamic, EvalAltResult};
+
+    use crate::scripting::limits;
+
+    /// Parses a JSON document into maps, arrays and primitive values.
+    #[rhai_fn(return_raw)]
+    pub fn parse(text: &str) -> Result<Dynamic, Box<EvalAltResult>> {
+        limits::check_document_size("json::parse", text.len())?;
+
+        let value: serde_json::Value =
+            serde_json::from_str(text).map_err(|e| format!("json::parse: {e}"))?;
+
+        rhai::serde::to_dynamic(value)
+    }
+
+    #[rhai_fn(name = "to_string", return_raw)]
+    pub fn to_string(value: Dynamic) -> Result<String, Box<EvalAltResult>> {
+        limits::serialize_document("json::to_string", |writer| {
+            serde_json::to_writer(writer, &value)
+        })
+    }
+
+    /// Like `to_string`, but indented for humans.
+    #[rhai_fn(return_raw)]
+    pub fn to_string_pretty(value: Dynamic) -> Result<String, Box<EvalAltResult>> {
+        limits::serialize_document("json::to_string_pretty", |writer| {
+            serde_json::to_writer_pretty(writer, &value)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rhai::{exported_module, Dynamic};
+
+    use super::json_rhai;
+
+    fn engine() -> rhai::Engine {
+        let mut engine = rhai::Engine::new();
+        engine.register_static_module("json", exported_module!(json_rhai).into());
+        engine
+    }
+
+    #[test]
+    pub fn test_roundtrip() {
+        let engine = engine();
+
+        assert_eq!(
+            3,
+            engine
+                .eval::<i64>(r#"json::parse(`{"replicas": 3, "ports": [80]}`).replicas"#)
+                .unwrap()
+        );
+        assert_eq!(
+            r#"{"a":[1,true,null]}"#,
+            engine
+                .eval::<String>(r#"json::to_string(#{ a: [1, true, ()] })"#)
+                .unwrap()
+        );
+        assert_eq!(
+            "{\n  \"a\": 1\n}",
+            engine
+                .eval::<String>(r#"json::to_string_pretty(json::parse(`{"a":1}`))"#)
+                .unwrap()
+        );
+    }
+
+    #[test]
+    pub fn test_invalid_document() {
+        let error = json_rhai::parse("{ not json").unwrap_err();
+
+        assert!(error.to_string().contains("json::parse"), "{error}");
+    }
+
+    #[test]
+    pub fn test_size_limit() {
+        let text = format!(
+            r#""{}""#,
+            "x".repeat(crate::scripting::limits::MAX_DOCUMENT_SIZE)
+        );
+        let error = json_rhai::parse(&text).unwrap_err();
+
+        assert!(error.to_string().contains("exceeds the limit"), "{error}");
+
+        let value = Dynamic::from(vec![
+            Dynamic::from("x".repeat(
+                crate::scripting::limits::MAX_DOCUMENT_SIZE / 2
+            ));
+            2
+        ]);
+        let error = json_rhai::to_string(value.clone()).unwrap_err();
+
+        assert!(error.to_string().contains("json::to_string"), "{error}");
+        assert!(error.to_string().contains("exceeds the limit"), "{error}");
+        assert!(json_rhai::to_string_pretty(value).is_err());
+    }
+}
@@ -0,0 +1,553 @@
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    sync::Arc,
+};
+
+use k8s_openapi::{
+    api::core::v1::{ContainerStatus, Pod},
+    apimachinery::pkg::apis::meta::v1::LabelSelector,
+};
+use kube::core::{Expression, Selector, SelectorExt as _};
+use rhai::{plugin::*, Dynamic};
+
+use crate::scripting::{modules::time::Clock, types::ResourceRef};
+
+/// Looks up a nested field of a Kubernetes object, `None` if any part of the path is missing.
+fn field(obj: &Dynamic, path: &[&str]) -> Option<Dynamic> {
+    path.iter().try_fold(obj.clone(), |current, key| {
+        current
+            .read_lock::<rhai::Map>()
+            .and_then(|map| map.get(*key).cloned())
+            .filter(|value| !value.is_unit())
+    })
+}
+
+fn string_field(obj: &Dynamic, path: &[&str]) -> Option<String> {
+    field(obj, path).and_then(|value| value.into_string().ok())
+}
+
+fn conditions_of(obj: &Dynamic) -> rhai::Array {
+    field(obj, &["status", "conditions"])
+        .and_then(|conditions| conditions.try_cast::<rhai::Array>())
+        .unwrap_or_default()
+}
+
+fn condition_status_of(obj: &Dynamic, kind: &str) -> Option<String> {
+    conditions_of(obj)
+        .iter()
+        .find(|condition| string_field(condition, &["type"]).as_deref() == Some(kind))
+        .and_then(|condition| string_field(condition, &["status"]))
+}
+
+/// The controller among the owners of `obj`, or its first owner if none of them is the controller.
+fn owner_of(obj: &Dynamic) -> Option<ResourceRef> {
+    let owners = field(obj, &["metadata", "ownerReferences"])?.try_cast::<rhai::Array>()?;
+
+    let owner = owners
+        .iter()
+        .find(|owner| {
+            field(owner, &["controller"])
+                .and_then(|controller| controller.as_bool().ok())
+                .unwrap_or(false)
+        })
+        .or(owners.first())?;
+
+    Some(ResourceRef {
+        api_version: string_field(owner, &["apiVersion"])?,
+        kind: string_field(owner, &["kind"])?,
+        namespace: string_field(obj, &["metadata", "namespace"]),
+        name: string_field(owner, &["name"])?,
+    })
+}
+
+/// Seconds since the object was created, as seen at `now`.
+fn age_at(obj: &Dynamic, now: chrono::DateTime<chrono::Utc>) -> Option<i64> {
+    let created = string_field(obj, &["metadata", "creationTimestamp"])?
+        .parse::<chrono::DateTime<chrono::Utc>>()
+        .ok()?;
+
+    Some((now - created).num_seconds())
+}
+
+/// Summarizes the state of a Pod the same way `kubectl get pods` does in its STATUS column, e.g. `CrashLoopBackOff`,
+/// `Init:0/2` or `Terminating`.
+fn phase_reason_of(pod: &Pod) -> String {
+    let status = pod.status.clone().unwrap_or_default();
+
+    let mut reason = status
+        .reason
+        .clone()
+        .filter(|reason| !reason.is_empty())
+        .or(status.phase.clone())
+        .unwrap_or_else(|| "Unknown".to_owned());
+
+    let init_statuses = status.init_container_statuses.unwrap_or_default();
+    let init_count = pod
+        .spec
+        .as_ref()
+        .map(|spec| spec.init_containers.as_ref().map_or(0, Vec::len))
+        .unwrap_or(init_statuses.len());
+
+    let mut initializing = false;
+    for (idx, container) in init_statuses.iter().enumerate() {
+        let state = container.state.clone().unwrap_or_default();
+
+        match (state.terminated, state.waiting) {
+            (Some(terminated), _) if terminated.exit_code == 0 => continue,
+            (Some(terminated), _) => {
+                reason = match terminated.reason.filter(|reason| !reason.is_empty()) {
+                    Some(reason) => format!("Init:{reason}"),
+                    None => match terminated.signal {
+                        Some(signal) if signal != 0 => format!("Init:Signal:{signal}"),
+                        _ => format!("Init:ExitCode:{}", terminated.exit_code),
+                    },
+                };
+            }
+            (None, Some(waiting))
+                if waiting
+                    .reason
+                    .as_deref()
+                    .is_some_and(|reason| !reason.is_empty() && reason != "PodInitializing") =>
+            {
+                reason = format!("Init:{}", waiting.reason.unwrap_or_default());
+            }
+            _ => reason = format!("Init:{idx}/{init_count}"),
+        }
+
+        initializing = true;
+        break;
+    }
+
+    if !initializing {
+        let mut has_running = false;
+
+        for container in status.container_statuses.iter().flatten().rev() {
+            if let Some(container_reason) = container_reason(container) {
+                reason = container_reason;
+            } else if container.ready
+                && container
+                    .state
+                    .as_ref()
+                    .is_some_and(|state| state.running.is_some())
+            {
+                has_running = true;
+            }
+        }
+
+        if reason == "Completed" && has_running {
+            let ready = status
+                .conditions
+                .iter()
+                .flatten()
+                .any(|condition| condition.type_ == "Ready" && condition.status == "True");
+
+            reason = if ready { "Running" } else { "NotReady" }.to_owned();
+        }
+    }
+
+    if pod.metadata.deletion_timestamp.is_some() {
+        reason = "Terminating".to_owned();
+    }
+
+    reason
+}
+
+/// Why a container is not running, if it is waiting or has terminated.
+fn container_reason(container: &ContainerStatus) -> Option<String> {
+    let state = container.state.as_ref()?;
+
+    if let Some(reason) = state
+        .waiting
+        .as_ref()
+        .and_then(|waiting| waiting.reason.clone())
+        .filter(|reason| !reason.is_empty())
+    {
+        return Some(reason);
+    }
+
+    let terminated = state.terminated.as_ref()?;
+
+    Some(
+        match terminated
+            .reason
+            .clone()
+            .filter(|reason| !reason.is_empty())
+        {
+            Some(reason) => reason,
+            None => match terminated.signal {
+                Some(signal) if signal != 0 => format!("Signal:{signal}"),
+                _ => format!("ExitCode:{}", terminated.exit_code),
+            },
+        },
+    )
+}
+
+fn labels_of(obj: &Dynamic) -> BTreeMap<String, String> {
+    field(obj, &["metadata", "labels"])
+        .and_then(|labels| labels.try_cast::<rhai::Map>())
+        .unwrap_or_default()
+        .into_iter()
+        .filter_map(|(key, value)| Some((key.to_string(), value.into_string().ok()?)))
+        .collect()
+}
+
+/// Parses a label selector in the syntax of `kubectl -l`, e.g. `app=web,tier!=cache,env in (prod,staging),!legacy`.
+pub(crate) fn parse_selector(selector: &str) -> Result<Selector, String> {
+    let mut terms = Vec::new();
+    let mut depth = 0;
+    let mut start = 0;
+
+    for (idx, char) in selector.char_indices() {
+        match char {
+            '(' => depth += 1,
+            ')' => depth -= 1,
+            ',' if depth == 0 => {
+                terms.push(&selector[start..idx]);
+                start = idx + 1;
+            }
+            _ => {}
+        }
+    }
+    terms.push(&selector[start..]);
+
+    terms
+        .into_iter()
+        .map(str::trim)
+        .filter(|term| !term.is_empty())
+        .map(|term| {
+            parse_expression(term).ok_or_else(|| format!("invalid label selector `{term}`"))
+        })
+        .collect::<Result<Vec<_>, _>>()
+        .map(Selector::from_iter)
+}
+
+fn parse_expression(term: &str) -> Option<Expression> {
+    let key = |key: &str| Some(key.trim().to_owned()).filter(|key| is_label_key(key));
+
+    if let Some((name, value)) = term.split_once("!=") {
+        return Some(Expression::NotEqual(key(name)?, value.trim().to_owned()));
+    }
+
+    if let Some((name, value)) = term.split_once("==").or_else(|| term.split_once('=')) {
+        return Some(Expression::Equal(key(name)?, value.trim().to_owned()));
+    }
+
+    if let Some((name_and_operator, values)) = term.split_once('(') {
+        let values: BTreeSet<String> = values
+            .strip_suffix(')')?
+            .split(',')
+            .map(|value| value.trim().to_owned())
+            .collect();
+
+        return match name_and_operator.split_whitespace().collect::<Vec<_>>()[..] {
+            [name, "in"] => Some(Expression::In(key(name)?, values)),
+            [name, "notin"] => Some(Expression::NotIn(key(name)?, values)),
+            _ => None,
+        };
+    }
+
+    match term.strip_prefix('!') {
+        Some(name) => Some(Expression::DoesNotExist(key(name)?)),
+        None => Some(Expression::Exists(key(term)?)),
+    }
+}
+
+fn is_label_key(key: &str) -> bool {
+    !key.is_empty()
+        && key
+            .chars()
+            .all(|char| char.is_ascii_alphanumeric() || "-_./".contains(char))
+}
+
+/// Either a Kubernetes `LabelSelector` like `spec.selector` of a Deployment, or a plain map of labels that must all
+/// be present with the given values.
+fn selector_from_map(selector: rhai::Map) -> Result<Selector, String> {
+    if selector.contains_key("matchLabels") || selector.contains_key("matchExpressions") {
+        let selector: LabelSelector = rhai::serde::from_dynamic(&selector.into())
+            .map_err(|e| format!("invalid label selector: {e}"))?;
+
+        return selector
+            .try_into()
+            .map_err(|e| format!("invalid label selector: {e}"));
+    }
+
+    selector
+        .into_iter()
+        .map(|(key, value)| {
+            value
+                .into_string()
+                .map(|value| (key.to_string(), value))
+                .map_err(|_| format!("label selector: value of `{key}` must be a string"))
+        })
+        .collect::<Result<Vec<_>, _>>()
+        .map(Selector::from_iter)
+}
+
+/// Helpers for common questions about Kubernetes objects, so that scripts do not have to reimplement them.
+#[export_module]
+pub mod k8s_rhai {
+    use rhai::{Dynamic, EvalAltResult};
+
+    /// The conditions in `status.conditions`, or an empty array.
+    pub fn conditions(obj: Dynamic) -> rhai::Array {
+        super::conditions_of(&obj)
+    }
+
+    /// The status (`"True"`, `"False"` or `"Unknown"`) of the condition with the given type, or `()`.
+    pub fn condition_status(obj: Dynamic, kind: &str) -> Dynamic {
+        super::condition_status_of(&obj, kind)
+            .map(Dynamic::from)
+            .unwrap_or(Dynamic::UNIT)
+    }
+
+    /// A `ResourceRef` to the controller of the object (or its first owner), or `()`.
+    pub fn owner(obj: Dynamic) -> Dynamic {
+        super::owner_of(&obj)
+            .map(Dynamic::from)
+            .unwrap_or(Dynamic::UNIT)
+    }
+
+    /// The status of a Pod as shown by `kubectl get pods`, e.g. `CrashLoopBackOff` or `Init:0/2`.
+    #[rhai_fn(return_raw)]
+    pub fn pod_phase_reason(obj: Dynamic) -> Result<String, Box<EvalAltResult>> {
+        let pod = rhai::serde::from_dynamic(&obj)
+            .map_err(|e| format!("k8s::pod_phase_reason: not a Pod: {e}"))?;
+
+        Ok(super::phase_reason_of(&pod))
+    }
+
+    /// Whether the labels of the object match a selector like `app=web,env in (prod,staging)`.
+    #[rhai_fn(name = "labels_match", return_raw)]
+    pub fn labels_match(obj: Dynamic, selector: &str) -> Result<bool, Box<EvalAltResult>> {
+        let selector = super::parse_selector(selector)?;
+
+        Ok(selector.matches(&super::labels_of(&obj)))
+    }
+
+    /// Whether the labels of the object match a `LabelSelector` (e.g. `spec.selector`) or a map of labels.
+    #[rhai_fn(name = "labels_match", return_raw)]
+    pub fn labels_match_map(obj: Dynamic, selector: rhai::Map) -> Result<bool, Box<EvalAltResult>> {
+        let selector = super::selector_from_map(selector)?;
+
+        Ok(selector.matches(&super::labels_of(&obj)))
+    }
+}
+
+/// Builds the Rhai `k8s` module, `k8s::age` is measured against the given clock.
+pub fn build_module(clock: Arc<dyn Clock>) -> Module {
+    let mut k8s_module = exported_module!(k8s_rhai);
+
+    // Seconds since the object was created, or `()` if it has no creation timestamp.
+    FuncRegistration::new("age").set_into_module(&mut k8s_module, move |obj: Dynamic| -> Dynamic {
+        age_at(&obj, clock.now())
+            .map(Dynamic::from)
+            .unwrap_or(Dynamic::UNIT)
+    });
+
+    k8s_module
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::{json, Value};
+
+    use super::*;
+    use crate::scripting::modules::time::FixedClock;
+
+    fn obj(value: Value) -> Dynamic {
+        rhai::serde::to_dynamic(value).unwrap()
+    }
+
+    fn pod(status: Value) -> Pod {
+        serde_json::from_value(json!({
+            "metadata": { "name": "web", "namespace": "default" },
+            "spec": { "containers": [], "initContainers": [{ "name": "a" }, { "name": "b" }] },
+            "status": status
+        }))
+        .unwrap()
+    }
+
+    fn engine() -> rhai::Engine {
+        let now = "2026-01-01T01:00:00Z".parse().unwrap();
+
+        let mut engine = rhai::Engine::new();
+        engine.build_type::<ResourceRef>();
+        engine.register_static_module("k8s", build_module(Arc::new(FixedClock(now))).into());
+        engine
+    }
+
+    #[test]
+    pub fn test_conditions() {
+        let deployment = obj(json!({
+            "status": { "conditions": [
+                { "type": "Available", "status": "True" },
+                { "type": "Progressing", "status": "False" }
+            ]}
+        }));
+
+        assert_eq!(2, conditions_of(&deployment).len());
+        assert_eq!(
+            Some("False"),
+            condition_status_of(&deployment, "Progressing").as_deref()
+        );
+        assert_eq!(None, condition_status_of(&deployment, "Ready"));
+        assert!(conditions_of(&obj(json!({ "metadata": {} }))).is_empty());
+    }
+
+    #[test]
+    pub fn test_owner_prefers_controller() {
+        let pod = obj(json!({
+            "metadata": {
+                "namespace": "default",
+                "ownerReferences": [
+                    { "apiVersion": "v1", "kind": "ConfigMap", "name": "cm" },
+                    { "apiVersion": "apps/v1", "kind": "ReplicaSet", "name": "web-123", "controller": true }
+                ]
+            }
+        }));
+
+        let owner = owner_of(&pod).unwrap();
+
+        assert_eq!("ReplicaSet", owner.kind);
+        assert_eq!("web-123", owner.name);
+        assert_eq!(Some("default"), owner.namespace.as_deref());
+        assert!(owner_of(&obj(json!({ "metadata": {} }))).is_none());
+    }
+
+    #[test]
+    pub fn test_pod_phase_reason() {
+        let running = pod(json!({
+            "phase": "Running",
+            "initContainerStatuses": [
+                { "name": "a", "image": "", "imageID": "", "ready": true, "restartCount": 0, "state": { "terminated": { "exitCode": 0 } } },
+                { "name": "b", "image": "", "imageID": "", "ready": true, "restartCount": 0, "state": { "terminated": { "exitCode": 0 } } }
+            ],
+            "containerStatuses": [
+                { "name": "c", "image": "", "imageID": "", "ready": true, "restartCount": 0, "state": { "running": {} } }
+            ]
+        }));
+        let crashing = pod(json!({
+            "phase": "Running",
+            "containerStatuses": [
+                { "name": "c", "image": "", "imageID": "", "ready": false, "restartCount": 4, "state": { "waiting": { "reason": "CrashLoopBackOff" } } }
+            ]
+        }));
+        let initializing = pod(json!({
+            "phase": "Pending",
+            "initContainerStatuses": [
+                { "name": "a", "image": "", "imageID": "", "ready": false, "restartCount": 0, "state": { "running": {} } }
+            ]
+        }));
+        let init_failed = pod(json!({
+            "phase": "Pending",
+            "initContainerStatuses": [
+                { "name": "a", "image": "", "imageID": "", "ready": false, "restartCount": 0, "state": { "terminated": { "exitCode": 3 } } }
+            ]
+        }));
+        let evicted = pod(json!({ "phase": "Failed", "reason": "Evicted" }));
+        let mut terminating = running.clone();
+        terminating.metadata.deletion_timestamp =
+            Some(serde_json::from_value(json!("2026-01-01T00:00:00Z")).unwrap());
+
+        assert_eq!("Running", phase_reason_of(&running));
+        assert_eq!("CrashLoopBackOff", phase_reason_of(&crashing));
+        assert_eq!("Init:0/2", phase_reason_of(&initializing));
+        assert_eq!("Init:ExitCode:3", phase_reason_of(&init_failed));
+        assert_eq!("Evicted", phase_reason_of(&evicted));
+        assert_eq!("Terminating", phase_reason_of(&terminating));
+    }
+
+    #[test]
+    pub fn test_age() {
+        let now = "2026-01-01T01:00:00Z".parse().unwrap();
+
+        assert_eq!(
+            Some(3600),
+            age_at(
+                &obj(json!({ "metadata": { "creationTimestamp": "2026-01-01T00:00:00Z" } })),
+                now
+            )
+        );
+        assert_eq!(None, age_at(&obj(json!({ "metadata": {} })), now));
+        assert_eq!(
+            60,
+            engine()
+                .eval::<i64>(
+                    r#"k8s::age(#{ metadata: #{ creationTimestamp: "2026-01-01T00:59:00Z" } })"#
+                )
+                .unwrap()
+        );
+    }
+
+    #[test]
+    pub fn test_selector_syntax() {
+        let labels = BTreeMap::from([
+            ("app".to_owned(), "web".to_owned()),
+            ("env".to_owned(), "prod".to_owned()),
+        ]);
+        let matches = |selector: &str| parse_selector(selector).unwrap().matches(&labels);
+
+        assert!(matches(""));
+        assert!(matches("app=web"));
+        assert!(matches("app==web, env in (prod, staging)"));
+        assert!(matches("app,!legacy"));
+        assert!(matches("tier notin (cache),env!=dev"));
+        assert!(!matches("app=web,env=dev"));
+        assert!(!matches("env in (dev,staging)"));
+        assert!(!matches("!app"));
+        assert!(parse_selector("app in prod").is_err());
+        assert!(parse_selector("=web").is_err());
+    }
+
+    #[test]
+    pub fn test_labels_match_from_script() {
+        let engine = engine();
+        let mut scope = rhai::Scope::new();
+        scope.push_dynamic(
+            "pod",
+            obj(json!({ "metadata": { "labels": { "app": "web", "tier": "frontend" } } })),
+        );
+
+        let result = engine
+            .eval_with_scope::<rhai::Array>(
+                &mut scope,
+                r#"[
+                    k8s::labels_match(pod, "app=web"),
+                    k8s::labels_match(pod, #{ app: "web", tier: "backend" }),
+                    k8s::labels_match(pod, #{ matchLabels: #{ app: "web" }, matchExpressions: [#{ key: "tier", operator: "In", values: ["frontend"] }] }),
+                ]"#,
+            )
+            .unwrap();
+
+        assert_eq!(
+            vec![true, false, true],
+            result
+                .into_iter()
+                .map(|value| value.as_bool().unwrap())
+                .collect::<Vec<_>>()
+        );
+        assert!(engine
+            .eval_with_scope::<bool>(&mut scope, r#"k8s::labels_match(pod, "app in web")"#)
+            .is_err());
+    }
+
+    #[test]
+    pub fn test_owner_from_script() {
+        let engine = engine();
+        let mut scope = rhai::Scope::new();
+        scope.push_dynamic(
+            "pod",
+            obj(json!({ "metadata": { "ownerReferences": [{ "apiVersion": "batch/v1", "kind": "Job", "name": "backup" }] } })),
+        );
+
+        let kind = engine
+            .eval_with_scope::<String>(&mut scope, "k8s::owner(pod).kind")
+            .unwrap();
+        let unowned = engine
+            .eval_with_scope::<Dynamic>(&mut scope, "k8s::owner(#{ metadata: #{} })")
+            .unwrap();
+
+        assert_eq!("Job", kind);
+        assert!(unowned.is_unit());
+    }
+}
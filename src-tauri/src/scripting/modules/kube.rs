@@ -2,31 +2,56 @@ use std::sync::Arc;
 
 use k8s_openapi::api::apps::v1::{DaemonSet, Deployment, StatefulSet};
 use kube::{
-    api::{ApiResource, DeleteParams, DynamicObject, Patch, PatchParams},
+    api::{
+        ApiResource, DeleteParams, DynamicObject, ListParams, Patch, PatchParams, PostParams,
+        TypeMeta,
+    },
     core::{gvk::ParseGroupVersionError, GroupVersion},
+    discovery::{ApiCapabilities, Scope},
     Api, Discovery,
 };
 use rhai::{FuncRegistration, Module};
+use serde::Serialize;
 
-use crate::scripting::types::ResourceRef;
+use crate::scripting::{
+    capabilities::{self, Capabilities, Capability},
+    types::ResourceRef,
+};
 
 type LazyDiscovery = Option<Arc<kube::Discovery>>;
 type EvalResult<T> = Result<T, Box<rhai::EvalAltResult>>;
 
-/// Builds the Rhai `kube` module (`kube.get` / `delete` / `patch_merge` / `rollout_restart`).
+/// Builds the Rhai `kube` module.
+///
+/// Reading: `get`, `list`, `get_subresource`. Writing: `create`, `apply`, `patch_merge`, `patch_json`,
+/// `patch_subresource`, `scale`, `rollout_restart`. Deleting: `delete`. Objects are returned as maps.
+///
+/// Whether a kind is namespaced is looked up in discovery: the namespace argument is ignored for
+/// cluster-scoped kinds such as Nodes, and `list` with an empty namespace lists all namespaces.
 ///
 /// Every function blocks the calling thread for a full kube round-trip, so they are meant
 /// to be called only from context-menu **action callbacks** — never from matchers or `items`
 /// functions, which run once per resource. The owning engine must be invoked from a
 /// `spawn_blocking` context (see the `resource_menu` commands) so the block lands on Tokio's
-/// blocking pool instead of a core runtime worker driving UI watchers.
-pub fn build_module<F>(client: kube::Client, discovery_supplier: F) -> Module
+/// blocking pool instead of a core runtime worker driving UI watchers. Presentations read other
+/// objects through the non-blocking `related` module instead.
+///
+/// Functions that need a capability missing from `capabilities` are registered nonetheless, but always fail.
+pub fn build_module<F>(
+    client: kube::Client,
+    discovery_supplier: F,
+    capabilities: &Capabilities,
+) -> Module
 where
     F: Fn() -> LazyDiscovery + Send + Sync + 'static,
 {
     let mut kube_module = Module::new();
     let discovery = Arc::new(discovery_supplier);
 
+    let can_read = capabilities.contains(&Capability::KubeRead);
+    let can_write = capabilities.contains(&Capability::KubeWrite);
+    let can_delete = capabilities.contains(&Capability::KubeDelete);
+
     {
         let client = client.clone();
         let discovery = Arc::clone(&discovery);
@@ -38,13 +63,14 @@ where
                   namespace: &str,
                   name: &str|
                   -> EvalResult<rhai::Map> {
+                capabilities::require(can_read, "kube::get", Capability::KubeRead)?;
+
                 let discovery = discovery();
                 let discovery = &get_cache(discovery)?;
 
                 block_on(async {
-                    let ar = api_resource_for(api_version, kind, discovery)?;
-                    let api: Api<DynamicObject> =
-                        Api::namespaced_with(client.clone(), namespace, &ar);
+                    let resource = api_resource_for(api_version, kind, discovery)?;
+                    let api = object_api(&client, &resource, namespace)?;
                     let resource = api.get(name).await.map_err(|e| e.to_string())?;
 
                     Ok(rhai::serde::to_dynamic(resource)?.cast::<rhai::Map>())
@@ -60,16 +86,14 @@ where
         FuncRegistration::new("delete").set_into_module(
             &mut kube_module,
             move |api_version: &str, kind: &str, namespace: &str, name: &str| -> EvalResult<()> {
-                let client = client.clone();
+                capabilities::require(can_delete, "kube::delete", Capability::KubeDelete)?;
+
                 let discovery = discovery();
                 let discovery = &get_cache(discovery)?;
 
                 block_on(async {
-                    let ar = api_resource_for(api_version, kind, discovery)?;
-                    let api: Api<DynamicObject> = match namespace {
-                        "" => Api::all_with(client, &ar),
-                        namespace => Api::namespaced_with(client, namespace, &ar),
-                    };
+                    let resource = api_resource_for(api_version, kind, discovery)?;
+                    let api = object_api(&client, &resource, namespace)?;
 
                     api.delete(name, &DeleteParams::default())
                         .await
@@ -93,13 +117,14 @@ where
                   name: &str,
                   patch: rhai::Map|
                   -> EvalResult<()> {
+                capabilities::require(can_write, "kube::patch_merge", Capability::KubeWrite)?;
+
                 let discovery = discovery();
                 let discovery = &get_cache(discovery)?;
 
                 block_on(async {
-                    let ar = api_resource_for(api_version, kind, discovery)?;
-                    let api: Api<DynamicObject> =
-                        Api::namespaced_with(client.clone(), namespace, &ar);
+                    let resource = api_resource_for(api_version, kind, discovery)?;
+                    let api = object_api(&client, &resource, namespace)?;
 
                     api.patch(name, &PatchParams::default(), &Patch::Merge(patch))
                         .await
@@ -111,12 +136,266 @@ where
         );
     }
 
+    {
+        let client = client.clone();
+        let discovery = Arc::clone(&discovery);
+
+        FuncRegistration::new("list").set_into_module(
+            &mut kube_module,
+            move |api_version: &str,
+                  kind: &str,
+                  namespace: &str,
+                  selector: &str|
+                  -> EvalResult<rhai::Array> {
+                capabilities::require(can_read, "kube::list", Capability::KubeRead)?;
+
+                let discovery = discovery();
+                let discovery = &get_cache(discovery)?;
+
+                block_on(async {
+                    let resource = api_resource_for(api_version, kind, discovery)?;
+                    let api = collection_api(&client, &resource, namespace);
+                    let (ar, _) = &resource;
+
+                    let mut params = ListParams::default();
+                    if !selector.is_empty() {
+                        params = params.labels(selector);
+                    }
+
+                    let list = api.list(&params).await.map_err(|e| e.to_string())?;
+
+                    list.items
+                        .into_iter()
+                        .map(|mut object| {
+                            // Items of a list do not repeat their type
+                            object.types.get_or_insert_with(|| TypeMeta {
+                                api_version: ar.api_version.clone(),
+                                kind: ar.kind.clone(),
+                            });
+
+                            to_map(object).map(rhai::Dynamic::from)
+                        })
+                        .collect()
+                })
+            },
+        );
+    }
+
+    {
+        let client = client.clone();
+        let discovery = Arc::clone(&discovery);
+
+        FuncRegistration::new("create").set_into_module(
+            &mut kube_module,
+            move |obj: rhai::Map| -> EvalResult<rhai::Map> {
+                capabilities::require(can_write, "kube::create", Capability::KubeWrite)?;
+
+                let discovery = discovery();
+                let discovery = &get_cache(discovery)?;
+
+                block_on(async {
+                    let (object, resource) = parse_object(obj, discovery)?;
+                    let api = object_api(&client, &resource, &namespace_of(&object))?;
+
+                    let created = api
+                        .create(&PostParams::default(), &object)
+                        .await
+                        .map_err(|e| e.to_string())?;
+
+                    to_map(created)
+                })
+            },
+        );
+    }
+
+    {
+        let client = client.clone();
+        let discovery = Arc::clone(&discovery);
+
+        FuncRegistration::new("apply").set_into_module(
+            &mut kube_module,
+            move |obj: rhai::Map, field_manager: &str| -> EvalResult<rhai::Map> {
+                capabilities::require(can_write, "kube::apply", Capability::KubeWrite)?;
+
+                let discovery = discovery();
+                let discovery = &get_cache(discovery)?;
+
+                block_on(async {
+                    let (object, resource) = parse_object(obj, discovery)?;
+                    let api = object_api(&client, &resource, &namespace_of(&object))?;
+                    let name = object
+                        .metadata
+                        .name
+                        .clone()
+                        .ok_or("kube::apply: missing `metadata.name`")?;
+
+                    let applied = api
+                        .patch(
+                            &name,
+                            &PatchParams::apply(field_manager),
+                            &Patch::Apply(&object),
+                        )
+                        .await
+                        .map_err(|e| e.to_string())?;
+
+                    to_map(applied)
+                })
+            },
+        );
+    }
+
+    {
+        let client = client.clone();
+        let discovery = Arc::clone(&discovery);
+
+        FuncRegistration::new("patch_json").set_into_module(
+            &mut kube_module,
+            move |api_version: &str,
+                  kind: &str,
+                  namespace: &str,
+                  name: &str,
+                  operations: rhai::Array|
+                  -> EvalResult<rhai::Map> {
+                capabilities::require(can_write, "kube::patch_json", Capability::KubeWrite)?;
+
+                let patch: json_patch::Patch =
+                    rhai::serde::from_dynamic(&rhai::Dynamic::from_array(operations))
+                        .map_err(|e| format!("kube::patch_json: invalid JSON patch: {e}"))?;
+
+                let discovery = discovery();
+                let discovery = &get_cache(discovery)?;
+
+                block_on(async {
+                    let resource = api_resource_for(api_version, kind, discovery)?;
+                    let api = object_api(&client, &resource, namespace)?;
+
+                    let patched = api
+                        .patch(name, &PatchParams::default(), &Patch::Json::<()>(patch))
+                        .await
+                        .map_err(|e| e.to_string())?;
+
+                    to_map(patched)
+                })
+            },
+        );
+    }
+
+    {
+        let client = client.clone();
+        let discovery = Arc::clone(&discovery);
+
+        FuncRegistration::new("scale").set_into_module(
+            &mut kube_module,
+            move |resource_ref: ResourceRef, replicas: i64| -> EvalResult<rhai::Map> {
+                capabilities::require(can_write, "kube::scale", Capability::KubeWrite)?;
+
+                let discovery = discovery();
+                let discovery = &get_cache(discovery)?;
+
+                block_on(async {
+                    let resource =
+                        api_resource_for(&resource_ref.api_version, &resource_ref.kind, discovery)?;
+                    let api = object_api(
+                        &client,
+                        &resource,
+                        resource_ref.namespace.as_deref().unwrap_or_default(),
+                    )?;
+
+                    let scale = api
+                        .patch_scale(
+                            &resource_ref.name,
+                            &PatchParams::default(),
+                            &Patch::Merge(serde_json::json!({ "spec": { "replicas": replicas } })),
+                        )
+                        .await
+                        .map_err(|e| e.to_string())?;
+
+                    to_map(scale)
+                })
+            },
+        );
+    }
+
+    {
+        let client = client.clone();
+        let discovery = Arc::clone(&discovery);
+
+        FuncRegistration::new("get_subresource").set_into_module(
+            &mut kube_module,
+            move |api_version: &str,
+                  kind: &str,
+                  namespace: &str,
+                  name: &str,
+                  subresource: &str|
+                  -> EvalResult<rhai::Map> {
+                capabilities::require(can_read, "kube::get_subresource", Capability::KubeRead)?;
+                check_subresource(subresource)?;
+
+                let discovery = discovery();
+                let discovery = &get_cache(discovery)?;
+
+                block_on(async {
+                    let resource = api_resource_for(api_version, kind, discovery)?;
+                    let api = object_api(&client, &resource, namespace)?;
+
+                    let object = api
+                        .get_subresource(subresource, name)
+                        .await
+                        .map_err(|e| e.to_string())?;
+
+                    to_map(object)
+                })
+            },
+        );
+    }
+
+    {
+        let client = client.clone();
+        let discovery = Arc::clone(&discovery);
+
+        FuncRegistration::new("patch_subresource").set_into_module(
+            &mut kube_module,
+            move |api_version: &str,
+                  kind: &str,
+                  namespace: &str,
+                  name: &str,
+                  subresource: &str,
+                  patch: rhai::Map|
+                  -> EvalResult<rhai::Map> {
+                capabilities::require(can_write, "kube::patch_subresource", Capability::KubeWrite)?;
+                check_subresource(subresource)?;
+
+                let discovery = discovery();
+                let discovery = &get_cache(discovery)?;
+
+                block_on(async {
+                    let resource = api_resource_for(api_version, kind, discovery)?;
+                    let api = object_api(&client, &resource, namespace)?;
+
+                    let object = api
+                        .patch_subresource(
+                            subresource,
+                            name,
+                            &PatchParams::default(),
+                            &Patch::Merge(patch),
+                        )
+                        .await
+                        .map_err(|e| e.to_string())?;
+
+                    to_map(object)
+                })
+            },
+        );
+    }
+
     {
         let client = client.clone();
 
         FuncRegistration::new("rollout_restart").set_into_module(
             &mut kube_module,
             move |resource_ref: ResourceRef| -> EvalResult<()> {
+                capabilities::require(can_write, "kube::rollout_restart", Capability::KubeWrite)?;
+
                 block_on(async {
                     match resource_ref.kind.as_str() {
                         "Deployment" => {
@@ -176,18 +455,85 @@ fn api_resource_for(
     api_version: &str,
     kind: &str,
     discovery: &Discovery,
-) -> Result<ApiResource, String> {
+) -> Result<(ApiResource, ApiCapabilities), String> {
     let gv: GroupVersion = api_version
         .parse()
         .map_err(|e: ParseGroupVersionError| e.to_string())?;
 
-    let (ar, _) = discovery
+    discovery
         .get(&gv.group)
         .ok_or(format!("ApiVersion not found: {}", api_version))?
         .recommended_kind(kind)
-        .ok_or(format!("Kind not found: {}", kind))?;
+        .ok_or(format!("Kind not found: {}", kind))
+}
+
+/// Subresources that scripts may access, others are either streaming (`log`, `exec`) or special-purpose
+const SUBRESOURCES: [&str; 2] = ["status", "scale"];
+
+fn check_subresource(subresource: &str) -> EvalResult<()> {
+    if SUBRESOURCES.contains(&subresource) {
+        return Ok(());
+    }
+
+    Err(format!(
+        "Subresource `{subresource}` is not supported, use one of: {}",
+        SUBRESOURCES.join(", ")
+    )
+    .into())
+}
+
+/// The Api for a single object. The namespace is ignored for cluster-scoped kinds, e.g. Nodes, and
+/// required for namespaced ones.
+fn object_api(
+    client: &kube::Client,
+    (ar, capabilities): &(ApiResource, ApiCapabilities),
+    namespace: &str,
+) -> Result<Api<DynamicObject>, String> {
+    match (&capabilities.scope, namespace) {
+        (Scope::Cluster, _) => Ok(Api::all_with(client.clone(), ar)),
+        (Scope::Namespaced, "") => Err(format!(
+            "{} is namespaced, a namespace is required",
+            ar.kind
+        )),
+        (Scope::Namespaced, namespace) => Ok(Api::namespaced_with(client.clone(), namespace, ar)),
+    }
+}
+
+/// The Api for listing objects, across all namespaces if `namespace` is empty.
+fn collection_api(
+    client: &kube::Client,
+    (ar, capabilities): &(ApiResource, ApiCapabilities),
+    namespace: &str,
+) -> Api<DynamicObject> {
+    match (&capabilities.scope, namespace) {
+        (Scope::Cluster, _) | (Scope::Namespaced, "") => Api::all_with(client.clone(), ar),
+        (Scope::Namespaced, namespace) => Api::namespaced_with(client.clone(), namespace, ar),
+    }
+}
+
+/// Reads a full object as passed to `create` or `apply`, along with its resource.
+fn parse_object(
+    obj: rhai::Map,
+    discovery: &Discovery,
+) -> Result<(DynamicObject, (ApiResource, ApiCapabilities)), String> {
+    let object: DynamicObject = rhai::serde::from_dynamic(&obj.into())
+        .map_err(|e| format!("Not a Kubernetes object: {e}"))?;
+
+    let types = object
+        .types
+        .as_ref()
+        .ok_or("Not a Kubernetes object: missing `apiVersion` or `kind`")?;
+    let resource = api_resource_for(&types.api_version, &types.kind, discovery)?;
+
+    Ok((object, resource))
+}
+
+fn namespace_of(object: &DynamicObject) -> String {
+    object.metadata.namespace.clone().unwrap_or_default()
+}
 
-    Ok(ar)
+fn to_map(value: impl Serialize) -> EvalResult<rhai::Map> {
+    Ok(rhai::serde::to_dynamic(value)?.cast::<rhai::Map>())
 }
 
 /// Drives an async kube operation to completion synchronously.
@@ -203,3 +549,465 @@ where
 {
     tokio::runtime::Handle::current().block_on(future)
 }
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+
+    use http::{Method, StatusCode};
+    use kube::client::Body;
+    use serde_json::{json, Value};
+
+    use super::*;
+
+    /// A request that reached the fake API server, discovery requests excluded
+    #[derive(Debug)]
+    struct Recorded {
+        method: Method,
+        uri: String,
+        content_type: String,
+        body: Value,
+    }
+
+    /// A `kube::Client` backed by a `tower-test` mock, which serves discovery for `apps/v1` Deployments
+    /// and core Pods and Nodes, and answers everything else with plausible objects.
+    struct FakeCluster {
+        runtime: tokio::runtime::Runtime,
+        client: kube::Client,
+        discovery: Arc<Discovery>,
+        requests: Arc<Mutex<Vec<Recorded>>>,
+    }
+
+    impl FakeCluster {
+        fn start() -> Self {
+            let runtime = tokio::runtime::Builder::new_multi_thread()
+                .worker_threads(1)
+                .enable_all()
+                .build()
+                .unwrap();
+
+            let (service, mut handle) =
+                tower_test::mock::pair::<http::Request<Body>, http::Response<Body>>();
+            let requests = Arc::new(Mutex::new(Vec::new()));
+
+            {
+                let requests = Arc::clone(&requests);
+
+                runtime.spawn(async move {
+                    while let Some((request, respond)) = handle.next_request().await {
+                        let (parts, body) = request.into_parts();
+                        let body = body.collect_bytes().await.unwrap();
+                        let body = serde_json::from_slice(&body).unwrap_or(Value::Null);
+
+                        let (status, response) = respond_to(&parts.method, parts.uri.path(), &body);
+
+                        if !is_discovery(parts.uri.path()) {
+                            requests.lock().unwrap().push(Recorded {
+                                method: parts.method,
+                                uri: parts.uri.to_string(),
+                                content_type: parts
+                                    .headers
+                                    .get(http::header::CONTENT_TYPE)
+                                    .map(|value| value.to_str().unwrap().to_owned())
+                                    .unwrap_or_default(),
+                                body,
+                            });
+                        }
+
+                        respond.send_response(
+                            http::Response::builder()
+                                .status(status)
+                                .body(Body::from(serde_json::to_vec(&response).unwrap()))
+                                .unwrap(),
+                        );
+                    }
+                });
+            }
+
+            let client = {
+                let _guard = runtime.enter();
+                kube::Client::new(service, "default")
+            };
+            let discovery = runtime
+                .block_on(Discovery::new(client.clone()).run())
+                .unwrap();
+
+            Self {
+                runtime,
+                client,
+                discovery: Arc::new(discovery),
+                requests,
+            }
+        }
+
+        fn run(&self, script: &str) -> EvalResult<rhai::Dynamic> {
+            let _guard = self.runtime.enter();
+            let discovery = Arc::clone(&self.discovery);
+
+            let mut engine = rhai::Engine::new();
+            engine.build_type::<ResourceRef>().register_static_module(
+                "kube",
+                build_module(
+                    self.client.clone(),
+                    move || Some(Arc::clone(&discovery)),
+                    &Capability::ALL.into_iter().collect(),
+                )
+                .into(),
+            );
+
+            engine.eval(script)
+        }
+
+        fn run_ok(&self, script: &str) {
+            if let Err(error) = self.run(script) {
+                panic!("{script} failed: {error}");
+            }
+        }
+
+        fn take_requests(&self) -> Vec<Recorded> {
+            std::mem::take(&mut *self.requests.lock().unwrap())
+        }
+    }
+
+    fn is_discovery(path: &str) -> bool {
+        matches!(path, "/api" | "/api/v1" | "/apis" | "/apis/apps/v1")
+    }
+
+    fn deployment(replicas: i64) -> Value {
+        json!({
+            "apiVersion": "apps/v1",
+            "kind": "Deployment",
+            "metadata": { "namespace": "default", "name": "web" },
+            "spec": { "replicas": replicas }
+        })
+    }
+
+    fn respond_to(method: &Method, path: &str, body: &Value) -> (StatusCode, Value) {
+        let response = match path {
+            "/apis" => json!({
+                "kind": "APIGroupList",
+                "apiVersion": "v1",
+                "groups": [{
+                    "name": "apps",
+                    "versions": [{ "groupVersion": "apps/v1", "version": "v1" }],
+                    "preferredVersion": { "groupVersion": "apps/v1", "version": "v1" }
+                }]
+            }),
+            "/apis/apps/v1" => json!({
+                "kind": "APIResourceList",
+                "apiVersion": "v1",
+                "groupVersion": "apps/v1",
+                "resources": [
+                    { "name": "deployments", "singularName": "deployment", "namespaced": true, "kind": "Deployment", "verbs": ["create", "delete", "get", "list", "patch", "update", "watch"] },
+                    { "name": "deployments/scale", "singularName": "", "namespaced": true, "group": "autoscaling", "version": "v1", "kind": "Scale", "verbs": ["get", "patch", "update"] },
+                    { "name": "deployments/status", "singularName": "", "namespaced": true, "kind": "Deployment", "verbs": ["get", "patch", "update"] }
+                ]
+            }),
+            "/api" => json!({
+                "kind": "APIVersions",
+                "versions": ["v1"],
+                "serverAddressByClientCIDRs": []
+            }),
+            "/api/v1" => json!({
+                "kind": "APIResourceList",
+                "groupVersion": "v1",
+                "resources": [
+                    { "name": "pods", "singularName": "pod", "namespaced": true, "kind": "Pod", "verbs": ["get", "list", "patch"] },
+                    { "name": "nodes", "singularName": "node", "namespaced": false, "kind": "Node", "verbs": ["get", "list", "patch"] }
+                ]
+            }),
+            path if path.ends_with("/scale") => json!({
+                "apiVersion": "autoscaling/v1",
+                "kind": "Scale",
+                "metadata": { "namespace": "default", "name": "web" },
+                "spec": { "replicas": body["spec"]["replicas"].as_i64().unwrap_or(2) },
+                "status": { "replicas": 2 }
+            }),
+            path if method == Method::GET
+                && ["/deployments", "/pods", "/nodes"]
+                    .iter()
+                    .any(|plural| path.ends_with(plural)) =>
+            {
+                json!({
+                "apiVersion": "apps/v1",
+                "kind": "DeploymentList",
+                "metadata": {},
+                "items": [{ "metadata": { "namespace": "default", "name": "web" }, "spec": { "replicas": 2 } }]
+                })
+            }
+            _ if body.get("metadata").is_some() => body.clone(),
+            _ => deployment(2),
+        };
+
+        (StatusCode::OK, response)
+    }
+
+    #[test]
+    pub fn test_cluster_scoped_kinds_ignore_namespace() {
+        let cluster = FakeCluster::start();
+
+        cluster.run_ok(r#"kube::get("v1", "Node", "", "node-1")"#);
+        cluster.run_ok(r#"kube::get("v1", "Node", "default", "node-1")"#);
+        cluster.run_ok(
+            r#"kube::patch_merge("v1", "Node", "", "node-1", #{ spec: #{ unschedulable: true } })"#,
+        );
+        cluster.run_ok(r#"kube::list("v1", "Node", "default", "")"#);
+
+        let requests = cluster.take_requests();
+
+        assert_eq!("/api/v1/nodes/node-1", requests[0].uri);
+        assert_eq!("/api/v1/nodes/node-1", requests[1].uri);
+        assert_eq!(Method::PATCH, requests[2].method);
+        assert!(
+            requests[2].uri.starts_with("/api/v1/nodes/node-1?"),
+            "{}",
+            requests[2].uri
+        );
+        assert!(
+            requests[3].uri.starts_with("/api/v1/nodes?"),
+            "{}",
+            requests[3].uri
+        );
+    }
+
+    #[test]
+    pub fn test_namespaced_kinds_require_namespace() {
+        let cluster = FakeCluster::start();
+
+        cluster.run_ok(r#"kube::get("v1", "Pod", "default", "web")"#);
+        cluster.run_ok(r#"kube::patch_merge("v1", "Pod", "default", "web", #{ metadata: #{ labels: #{ app: "web" } } })"#);
+        let get_error = cluster
+            .run(r#"kube::get("v1", "Pod", "", "web")"#)
+            .unwrap_err();
+        let patch_error = cluster
+            .run(r#"kube::patch_merge("v1", "Pod", "", "web", #{})"#)
+            .unwrap_err();
+
+        let requests = cluster.take_requests();
+
+        assert_eq!(2, requests.len());
+        assert_eq!("/api/v1/namespaces/default/pods/web", requests[0].uri);
+        assert!(requests[1]
+            .uri
+            .starts_with("/api/v1/namespaces/default/pods/web?"));
+        assert!(
+            get_error.to_string().contains("Pod is namespaced"),
+            "{get_error}"
+        );
+        assert!(
+            patch_error.to_string().contains("Pod is namespaced"),
+            "{patch_error}"
+        );
+    }
+
+    #[test]
+    pub fn test_list_with_selector() {
+        let cluster = FakeCluster::start();
+
+        let items = cluster
+            .run(r#"kube::list("apps/v1", "Deployment", "default", "app=web")"#)
+            .unwrap()
+            .cast::<rhai::Array>();
+        cluster.run_ok(r#"kube::list("apps/v1", "Deployment", "", "")"#);
+
+        let requests = cluster.take_requests();
+
+        assert_eq!(1, items.len());
+        let item = items[0].clone().cast::<rhai::Map>();
+        assert_eq!("Deployment", item["kind"].clone().into_string().unwrap());
+        assert_eq!("apps/v1", item["apiVersion"].clone().into_string().unwrap());
+
+        assert_eq!(Method::GET, requests[0].method);
+        assert!(
+            requests[0]
+                .uri
+                .starts_with("/apis/apps/v1/namespaces/default/deployments?"),
+            "{}",
+            requests[0].uri
+        );
+        assert!(requests[0].uri.contains("labelSelector=app%3Dweb"));
+        assert!(
+            requests[1].uri.starts_with("/apis/apps/v1/deployments"),
+            "{}",
+            requests[1].uri
+        );
+        assert!(!requests[1].uri.contains("labelSelector"));
+    }
+
+    #[test]
+    pub fn test_create_and_apply() {
+        let cluster = FakeCluster::start();
+
+        let created = cluster
+            .run(
+                r#"kube::create(#{ apiVersion: "apps/v1", kind: "Deployment", metadata: #{ namespace: "default", name: "web" } })"#,
+            )
+            .unwrap()
+            .cast::<rhai::Map>();
+        cluster.run_ok(
+                r#"kube::apply(#{ apiVersion: "apps/v1", kind: "Deployment", metadata: #{ namespace: "default", name: "web" }, spec: #{ replicas: 3 } }, "hyprkube-test")"#,
+            );
+        let error = cluster
+            .run(r#"kube::create(#{ metadata: #{ name: "web" } })"#)
+            .unwrap_err();
+
+        let requests = cluster.take_requests();
+
+        assert_eq!(
+            "web",
+            created["metadata"].clone().cast::<rhai::Map>()["name"].to_string()
+        );
+
+        assert_eq!(Method::POST, requests[0].method);
+        assert_eq!(
+            "/apis/apps/v1/namespaces/default/deployments?",
+            requests[0].uri
+        );
+
+        assert_eq!(Method::PATCH, requests[1].method);
+        assert!(requests[1]
+            .uri
+            .starts_with("/apis/apps/v1/namespaces/default/deployments/web?"));
+        assert!(requests[1].uri.contains("fieldManager=hyprkube-test"));
+        assert_eq!("application/apply-patch+yaml", requests[1].content_type);
+        assert_eq!(3, requests[1].body["spec"]["replicas"]);
+
+        assert!(
+            error.to_string().contains("Not a Kubernetes object"),
+            "{error}"
+        );
+    }
+
+    #[test]
+    pub fn test_patch_json() {
+        let cluster = FakeCluster::start();
+
+        cluster.run_ok(
+                r#"kube::patch_json("apps/v1", "Deployment", "default", "web", [#{ op: "replace", path: "/spec/replicas", value: 3 }])"#,
+            );
+        let error = cluster
+            .run(r#"kube::patch_json("apps/v1", "Deployment", "default", "web", [#{ op: "frobnicate" }])"#)
+            .unwrap_err();
+
+        let requests = cluster.take_requests();
+
+        assert_eq!(1, requests.len());
+        assert_eq!(Method::PATCH, requests[0].method);
+        assert_eq!("application/json-patch+json", requests[0].content_type);
+        assert_eq!(
+            json!([{ "op": "replace", "path": "/spec/replicas", "value": 3 }]),
+            requests[0].body
+        );
+        assert!(error.to_string().contains("invalid JSON patch"), "{error}");
+    }
+
+    #[test]
+    pub fn test_scale_and_subresources() {
+        let cluster = FakeCluster::start();
+
+        let scale = cluster
+            .run(
+                r#"kube::scale(ResourceRef(#{ api_version: "apps/v1", kind: "Deployment", namespace: "default", name: "web" }), 5)"#,
+            )
+            .unwrap()
+            .cast::<rhai::Map>();
+        cluster.run_ok(
+            r#"kube::get_subresource("apps/v1", "Deployment", "default", "web", "status")"#,
+        );
+        cluster.run_ok(
+                r#"kube::patch_subresource("apps/v1", "Deployment", "default", "web", "status", #{ status: #{ replicas: 1 } })"#,
+            );
+        let error = cluster
+            .run(r#"kube::get_subresource("v1", "Pod", "default", "web", "exec")"#)
+            .unwrap_err();
+
+        let requests = cluster.take_requests();
+
+        assert_eq!(
+            5,
+            scale["spec"].clone().cast::<rhai::Map>()["replicas"]
+                .as_int()
+                .unwrap()
+        );
+
+        assert_eq!(Method::PATCH, requests[0].method);
+        assert!(requests[0]
+            .uri
+            .starts_with("/apis/apps/v1/namespaces/default/deployments/web/scale?"));
+        assert_eq!(json!({ "spec": { "replicas": 5 } }), requests[0].body);
+
+        assert_eq!(Method::GET, requests[1].method);
+        assert_eq!(
+            "/apis/apps/v1/namespaces/default/deployments/web/status",
+            requests[1].uri
+        );
+
+        assert_eq!(Method::PATCH, requests[2].method);
+        assert!(requests[2]
+            .uri
+            .starts_with("/apis/apps/v1/namespaces/default/deployments/web/status?"));
+
+        assert_eq!(
+            3,
+            requests.len(),
+            "unsupported subresources never reach the cluster"
+        );
+        assert!(
+            error.to_string().contains("`exec` is not supported"),
+            "{error}"
+        );
+    }
+
+    /// Runs `script` against a `kube` module with the given grants. The client never connects anywhere, calls
+    /// that get past the capability check fail due to the missing discovery cache instead.
+    fn run(capabilities: &[Capability], script: &str) -> String {
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .unwrap();
+        let _guard = runtime.enter();
+
+        let config = kube::Config::new("http://127.0.0.1:1".parse().unwrap());
+        let client = kube::Client::try_from(config).unwrap();
+
+        let mut engine = rhai::Engine::new();
+        engine.register_static_module(
+            "kube",
+            build_module(client, || None, &capabilities.iter().copied().collect()).into(),
+        );
+
+        engine.run(script).unwrap_err().to_string()
+    }
+
+    #[test]
+    pub fn test_denied_calls_name_the_capability() {
+        let error = run(
+            &[Capability::KubeRead],
+            r#"kube::delete("v1", "Pod", "default", "web")"#,
+        );
+
+        assert!(error.contains("kube::delete is not permitted"), "{error}");
+        assert!(error.contains("`kube-delete`"), "{error}");
+
+        let error = run(
+            &[Capability::KubeRead, Capability::KubeDelete],
+            r#"kube::patch_merge("v1", "Pod", "default", "web", #{})"#,
+        );
+
+        assert!(error.contains("`kube-write`"), "{error}");
+
+        let error = run(&[Capability::KubeRead], r#"kube::apply(#{}, "hyprkube")"#);
+
+        assert!(error.contains("kube::apply is not permitted"), "{error}");
+    }
+
+    #[test]
+    pub fn test_granted_calls_pass_the_check() {
+        let error = run(
+            &[Capability::KubeRead],
+            r#"kube::get("v1", "Pod", "default", "web")"#,
+        );
+
+        assert!(error.contains("discovery cache"), "{error}");
+    }
+}
@@ -0,0 +1,292 @@
+use std::sync::Arc;
+
+use rhai::{Dynamic, FuncRegistration, Module};
+
+use crate::{
+    cluster_metrics::{ClusterMetrics, ResourceUsage},
+    internal::quantity,
+};
+
+type EvalResult<T> = Result<T, Box<rhai::EvalAltResult>>;
+
+/// Builds the Rhai `metrics` module which exposes the latest `metrics.k8s.io` samples of a cluster.
+///
+/// Usage maps look like `#{cpu: <millicores>, memory: <bytes>}`. Lookups never block: they only read
+/// the cache filled by the metrics poller and return `()` if there is no sample (yet), e.g. because
+/// metrics-server is not installed.
+pub fn build_module(metrics: Arc<ClusterMetrics>) -> Module {
+    let mut metrics_module = Module::new();
+
+    {
+        let metrics = Arc::clone(&metrics);
+
+        FuncRegistration::new("pod_usage").set_into_module(
+            &mut metrics_module,
+            move |obj: rhai::Map| -> Dynamic {
+                let (namespace, name) = namespace_and_name(&obj);
+
+                metrics
+                    .pod_usage(&namespace, &name)
+                    .map(|usage| {
+                        let mut map = usage_to_map(usage.total);
+
+                        map.insert(
+                            "containers".into(),
+                            usage
+                                .containers
+                                .into_iter()
+                                .map(|(name, usage)| (name.into(), usage_to_map(usage).into()))
+                                .collect::<rhai::Map>()
+                                .into(),
+                        );
+                        map.insert(
+                            "timestamp".into(),
+                            usage.timestamp.map(Dynamic::from).unwrap_or(Dynamic::UNIT),
+                        );
+
+                        map.into()
+                    })
+                    .unwrap_or(Dynamic::UNIT)
+            },
+        );
+    }
+
+    {
+        let metrics = Arc::clone(&metrics);
+
+        FuncRegistration::new("node_usage").set_into_module(
+            &mut metrics_module,
+            move |obj: rhai::Map| -> Dynamic {
+                let (_, name) = namespace_and_name(&obj);
+
+                metrics
+                    .node_usage(&name)
+                    .map(|usage| {
+                        let mut map = usage_to_map(usage.total);
+
+                        map.insert(
+                            "timestamp".into(),
+                            usage.timestamp.map(Dynamic::from).unwrap_or(Dynamic::UNIT),
+                        );
+
+                        map.into()
+                    })
+                    .unwrap_or(Dynamic::UNIT)
+            },
+        );
+    }
+
+    FuncRegistration::new("pod_requests").set_into_module(
+        &mut metrics_module,
+        |obj: rhai::Map| -> EvalResult<rhai::Map> {
+            Ok(usage_to_map(sum_container_resources(&obj, "requests")?.sum))
+        },
+    );
+
+    FuncRegistration::new("pod_limits").set_into_module(
+        &mut metrics_module,
+        |obj: rhai::Map| -> EvalResult<rhai::Map> {
+            let limits = sum_container_resources(&obj, "limits")?;
+            let total = |complete: bool, value: i64| {
+                if complete {
+                    Dynamic::from(value)
+                } else {
+                    Dynamic::UNIT
+                }
+            };
+
+            Ok(rhai::Map::from_iter([
+                (
+                    "cpu".into(),
+                    total(limits.cpu_complete, limits.sum.cpu_millis),
+                ),
+                (
+                    "memory".into(),
+                    total(limits.memory_complete, limits.sum.memory_bytes),
+                ),
+            ]))
+        },
+    );
+
+    FuncRegistration::new("parse_cpu").set_into_module(
+        &mut metrics_module,
+        |quantity: &str| -> EvalResult<i64> {
+            quantity::parse_cpu_millis(quantity).map_err(|e| e.to_string().into())
+        },
+    );
+
+    FuncRegistration::new("parse_memory").set_into_module(
+        &mut metrics_module,
+        |quantity: &str| -> EvalResult<i64> {
+            quantity::parse_memory_bytes(quantity).map_err(|e| e.to_string().into())
+        },
+    );
+
+    FuncRegistration::new("format_cpu")
+        .set_into_module(&mut metrics_module, quantity::format_cpu_millis);
+
+    FuncRegistration::new("format_memory")
+        .set_into_module(&mut metrics_module, quantity::format_memory_bytes);
+
+    metrics_module
+}
+
+fn usage_to_map(usage: ResourceUsage) -> rhai::Map {
+    rhai::Map::from_iter([
+        ("cpu".into(), usage.cpu_millis.into()),
+        ("memory".into(), usage.memory_bytes.into()),
+    ])
+}
+
+fn namespace_and_name(obj: &rhai::Map) -> (String, String) {
+    let metadata = obj
+        .get("metadata")
+        .and_then(|m| m.read_lock::<rhai::Map>().map(|m| m.clone()))
+        .unwrap_or_default();
+
+    let field = |key: &str| {
+        metadata
+            .get(key)
+            .and_then(|v| v.clone().into_string().ok())
+            .unwrap_or_default()
+    };
+
+    (field("namespace"), field("name"))
+}
+
+/// Sum of `spec.containers[*].resources.<kind>` of a Pod.
+struct ContainerResources {
+    /// Sum of all values that are set. Containers without a value count as zero.
+    sum: ResourceUsage,
+    /// Whether every container sets a CPU value.
+    cpu_complete: bool,
+    /// Whether every container sets a memory value.
+    memory_complete: bool,
+}
+
+/// Sums up `spec.containers[*].resources.<kind>` of a Pod.
+fn sum_container_resources(obj: &rhai::Map, kind: &str) -> EvalResult<ContainerResources> {
+    let containers: serde_json::Value = rhai::serde::from_dynamic(
+        &obj.get("spec")
+            .and_then(|spec| spec.read_lock::<rhai::Map>()?.get("containers").cloned())
+            .unwrap_or(Dynamic::from_array(rhai::Array::new())),
+    )?;
+
+    let mut result = ContainerResources {
+        sum: ResourceUsage::default(),
+        cpu_complete: true,
+        memory_complete: true,
+    };
+
+    for container in containers.as_array().into_iter().flatten() {
+        let resources = &container["resources"][kind];
+
+        match resources["cpu"].as_str() {
+            Some(cpu) => {
+                result.sum.cpu_millis +=
+                    quantity::parse_cpu_millis(cpu).map_err(|e| e.to_string())?
+            }
+            None => result.cpu_complete = false,
+        }
+
+        match resources["memory"].as_str() {
+            Some(memory) => {
+                result.sum.memory_bytes +=
+                    quantity::parse_memory_bytes(memory).map_err(|e| e.to_string())?
+            }
+            None => result.memory_complete = false,
+        }
+    }
+
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn pod() -> rhai::Map {
+        rhai::serde::to_dynamic(json!({
+            "apiVersion": "v1",
+            "kind": "Pod",
+            "metadata": { "namespace": "default", "name": "web" },
+            "spec": {
+                "containers": [
+                    { "name": "app", "resources": { "requests": { "cpu": "100m", "memory": "64Mi" }, "limits": { "cpu": "1" } } },
+                    { "name": "sidecar", "resources": { "requests": { "cpu": "50m" } } },
+                    { "name": "bare" }
+                ]
+            }
+        }))
+        .unwrap()
+        .cast::<rhai::Map>()
+    }
+
+    #[test]
+    fn test_sum_requests_and_limits() {
+        let requests = sum_container_resources(&pod(), "requests").unwrap();
+        let limits = sum_container_resources(&pod(), "limits").unwrap();
+
+        assert_eq!(150, requests.sum.cpu_millis);
+        assert_eq!(64 * 1024 * 1024, requests.sum.memory_bytes);
+        assert_eq!(1000, limits.sum.cpu_millis);
+        assert_eq!(0, limits.sum.memory_bytes);
+    }
+
+    #[test]
+    fn test_pod_limits_are_unlimited_if_a_container_has_no_limit() {
+        let mut engine = rhai::Engine::new();
+        engine.register_static_module("metrics", build_module(ClusterMetrics::new()).into());
+
+        let mut scope = rhai::Scope::new();
+        scope.push("pod", pod());
+
+        let limits: rhai::Map = engine
+            .eval_with_scope(&mut scope, "metrics::pod_limits(pod)")
+            .unwrap();
+
+        assert!(limits["cpu"].is_unit());
+        assert!(limits["memory"].is_unit());
+
+        let complete = rhai::serde::to_dynamic(serde_json::json!({
+            "spec": { "containers": [
+                { "resources": { "limits": { "cpu": "500m", "memory": "1Gi" } } },
+                { "resources": { "limits": { "cpu": "1", "memory": "1Gi" } } }
+            ] }
+        }))
+        .unwrap();
+        scope.push("complete", complete);
+
+        let limits: rhai::Map = engine
+            .eval_with_scope(&mut scope, "metrics::pod_limits(complete)")
+            .unwrap();
+
+        assert_eq!(1500, limits["cpu"].as_int().unwrap());
+        assert_eq!(2 * 1024 * 1024 * 1024, limits["memory"].as_int().unwrap());
+    }
+
+    #[test]
+    fn test_namespace_and_name() {
+        assert_eq!(
+            ("default".to_owned(), "web".to_owned()),
+            namespace_and_name(&pod())
+        );
+    }
+
+    #[test]
+    fn test_usage_unit_without_sample() {
+        let mut engine = rhai::Engine::new();
+        engine.register_static_module("metrics", build_module(ClusterMetrics::new()).into());
+
+        let mut scope = rhai::Scope::new();
+        scope.push("obj", pod());
+
+        let usage = engine
+            .eval_with_scope::<Dynamic>(&mut scope, "metrics::pod_usage(obj)")
+            .unwrap();
+
+        assert!(usage.is_unit());
+    }
+}
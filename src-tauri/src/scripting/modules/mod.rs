@@ -1,7 +1,15 @@
 mod base64;
 pub mod clipboard;
-mod frontend;
+pub mod frontend;
+mod json;
+pub mod k8s;
 pub mod kube;
+pub mod metrics;
+pub mod related;
+pub mod storage;
+pub mod time;
+mod yaml;
 
 pub use base64::*;
-pub use frontend::*;
+pub use json::*;
+pub use yaml::*;
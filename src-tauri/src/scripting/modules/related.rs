@@ -0,0 +1,260 @@
+use std::sync::Arc;
+
+use rhai::{Dynamic, FuncRegistration, Module};
+
+use crate::{
+    related_resources::{Lookup, RelatedKey, RelatedResources},
+    scripting::{
+        capabilities::{self, Capabilities, Capability},
+        types::ResourceRef,
+    },
+};
+
+type EvalResult<T> = Result<T, Box<rhai::EvalAltResult>>;
+
+/// Builds the Rhai `related` module, which gives presentations access to objects other than the
+/// rendered resource, e.g. the Deployment that owns a ReplicaSet.
+///
+/// Lookups never block: the first lookup of an object declares it and returns `()`, the object is
+/// then fetched in the background together with the lookups of other rows and the resource is
+/// rendered again once it arrived.
+///
+/// Like `kube::get`, all lookups need the `kube-read` capability and fail without it.
+pub fn build_module(related: Arc<RelatedResources>, capabilities: &Capabilities) -> Module {
+    let mut related_module = Module::new();
+
+    let can_read = capabilities.contains(&Capability::KubeRead);
+
+    {
+        let related = Arc::clone(&related);
+
+        FuncRegistration::new("get").set_into_module(
+            &mut related_module,
+            move |resource_ref: ResourceRef| -> EvalResult<Dynamic> {
+                capabilities::require(can_read, "related::get", Capability::KubeRead)?;
+
+                lookup_to_dynamic(related.lookup(&resource_ref.into()))
+            },
+        );
+    }
+
+    {
+        let related = Arc::clone(&related);
+
+        FuncRegistration::new("get").set_into_module(
+            &mut related_module,
+            move |api_version: &str,
+                  kind: &str,
+                  namespace: &str,
+                  name: &str|
+                  -> EvalResult<Dynamic> {
+                capabilities::require(can_read, "related::get", Capability::KubeRead)?;
+
+                lookup_to_dynamic(related.lookup(&RelatedKey::new(
+                    api_version,
+                    kind,
+                    namespace,
+                    name,
+                )))
+            },
+        );
+    }
+
+    {
+        let related = Arc::clone(&related);
+
+        FuncRegistration::new("owner").set_into_module(
+            &mut related_module,
+            move |obj: rhai::Map| -> EvalResult<Dynamic> {
+                capabilities::require(can_read, "related::owner", Capability::KubeRead)?;
+
+                match controller_of(&obj)? {
+                    Some(key) => lookup_to_dynamic(related.lookup(&key)),
+                    None => Ok(Dynamic::UNIT),
+                }
+            },
+        );
+    }
+
+    {
+        let related = Arc::clone(&related);
+
+        FuncRegistration::new("is_pending").set_into_module(
+            &mut related_module,
+            move |resource_ref: ResourceRef| -> EvalResult<bool> {
+                capabilities::require(can_read, "related::is_pending", Capability::KubeRead)?;
+
+                Ok(matches!(
+                    related.lookup(&resource_ref.into()),
+                    Lookup::Pending
+                ))
+            },
+        );
+    }
+
+    related_module
+}
+
+fn lookup_to_dynamic(lookup: Lookup) -> EvalResult<Dynamic> {
+    match lookup {
+        Lookup::Found(object) => rhai::serde::to_dynamic(&*object),
+        Lookup::Pending | Lookup::Missing => Ok(Dynamic::UNIT),
+        Lookup::Failed(message) => Err(message.into()),
+    }
+}
+
+/// The owner reference marked as `controller`, which lives in the namespace of `obj`.
+fn controller_of(obj: &rhai::Map) -> EvalResult<Option<RelatedKey>> {
+    let metadata: serde_json::Value = rhai::serde::from_dynamic(
+        obj.get("metadata")
+            .ok_or_else(|| "related::owner: missing `metadata`".to_owned())?,
+    )?;
+
+    let controller = metadata["ownerReferences"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .find(|owner| owner["controller"].as_bool() == Some(true));
+
+    Ok(controller.map(|owner| {
+        RelatedKey::new(
+            owner["apiVersion"].as_str().unwrap_or_default(),
+            owner["kind"].as_str().unwrap_or_default(),
+            metadata["namespace"].as_str().unwrap_or_default(),
+            owner["name"].as_str().unwrap_or_default(),
+        )
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn replica_set() -> rhai::Map {
+        rhai::serde::to_dynamic(json!({
+            "apiVersion": "apps/v1",
+            "kind": "ReplicaSet",
+            "metadata": {
+                "namespace": "default",
+                "name": "web-5d8f",
+                "ownerReferences": [
+                    { "apiVersion": "v1", "kind": "ConfigMap", "name": "unrelated" },
+                    { "apiVersion": "apps/v1", "kind": "Deployment", "name": "web", "controller": true }
+                ]
+            }
+        }))
+        .unwrap()
+        .cast::<rhai::Map>()
+    }
+
+    fn eval_with(
+        related: &Arc<RelatedResources>,
+        capabilities: &Capabilities,
+        script: &str,
+    ) -> Result<Dynamic, Box<rhai::EvalAltResult>> {
+        let mut engine = rhai::Engine::new();
+        engine.build_type::<ResourceRef>().register_static_module(
+            "related",
+            build_module(Arc::clone(related), capabilities).into(),
+        );
+
+        let mut scope = rhai::Scope::new();
+        scope.push("obj", replica_set());
+
+        engine.eval_with_scope(&mut scope, script)
+    }
+
+    fn eval(related: &Arc<RelatedResources>, script: &str) -> Dynamic {
+        eval_with(related, &Capabilities::from([Capability::KubeRead]), script).unwrap()
+    }
+
+    #[test]
+    pub fn test_owner_is_unit_until_resolved() {
+        let related = RelatedResources::new();
+        let owner = RelatedKey::new("apps/v1", "Deployment", "default", "web");
+
+        assert!(eval(&related, "related::owner(obj)").is_unit());
+        assert_eq!(
+            std::collections::BTreeSet::from([owner.clone()]),
+            related.take_queued()
+        );
+
+        related.complete([(
+            owner,
+            Lookup::Found(Arc::new(json!({ "status": { "readyReplicas": 3 } }))),
+        )]);
+
+        assert_eq!(
+            3,
+            eval(&related, "related::owner(obj).status.readyReplicas").cast::<i64>()
+        );
+    }
+
+    #[test]
+    pub fn test_get_by_ref_and_parts() {
+        let related = RelatedResources::new();
+
+        assert!(eval(
+            &related,
+            r#"related::is_pending(ResourceRef(#{api_version: "v1", kind: "Node", name: "node-1"}))"#
+        )
+        .cast::<bool>());
+
+        related.complete([(RelatedKey::new("v1", "Node", "", "node-1"), Lookup::Missing)]);
+
+        assert!(eval(&related, r#"related::get("v1", "Node", "", "node-1")"#).is_unit());
+        assert!(!eval(
+            &related,
+            r#"related::is_pending(ResourceRef(#{api_version: "v1", kind: "Node", name: "node-1"}))"#
+        )
+        .cast::<bool>());
+    }
+
+    #[test]
+    pub fn test_lookups_need_kube_read() {
+        let related = RelatedResources::new();
+
+        for script in [
+            "related::owner(obj)",
+            r#"related::get("apps/v1", "Deployment", "default", "web")"#,
+            r#"related::is_pending(ResourceRef(#{api_version: "v1", kind: "Node", name: "node-1"}))"#,
+        ] {
+            let error = eval_with(&related, &Capabilities::new(), script).unwrap_err();
+
+            assert!(error.to_string().contains("`kube-read`"), "{error}");
+        }
+
+        assert!(related.take_queued().is_empty());
+    }
+
+    #[test]
+    pub fn test_failed_lookups_raise_an_error() {
+        let related = RelatedResources::new();
+        let key = RelatedKey::new("apps/v1", "Deployment", "", "web");
+
+        assert!(eval(
+            &related,
+            r#"related::get("apps/v1", "Deployment", "", "web")"#
+        )
+        .is_unit());
+
+        related.complete([(
+            key,
+            Lookup::Failed("Deployment is namespaced, a namespace is required".into()),
+        )]);
+
+        let error = eval_with(
+            &related,
+            &Capabilities::from([Capability::KubeRead]),
+            r#"related::get("apps/v1", "Deployment", "", "web")"#,
+        )
+        .unwrap_err();
+
+        assert!(
+            error.to_string().contains("a namespace is required"),
+            "{error}"
+        );
+    }
+}
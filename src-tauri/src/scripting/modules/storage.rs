@@ -0,0 +1,598 @@
+use std::{
+    cell::RefCell,
+    collections::HashMap,
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex},
+};
+
+use rhai::{Dynamic, FuncRegistration, Module};
+use serde_json::{Map, Value};
+
+use crate::{
+    persistence::repository::{Context, Repository},
+    scripting::{
+        package_manifest::PackageManifest,
+        scripts_provider::{self, PackageSource},
+    },
+};
+
+type EvalResult<T> = Result<T, Box<rhai::EvalAltResult>>;
+
+/// How much the values of one package may take up per scope, measured as compact JSON
+pub const QUOTA_BYTES: usize = 64 * 1024;
+
+/// Whose values a [Storage] handle reads and writes. Packages never see each other's values.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum StorageScope {
+    /// Shared by all clusters
+    Package(String),
+    /// Separate for each cluster, identified by its kube context
+    Cluster { package: String, cluster: String },
+}
+
+/// Changes the values of a scope in place, see [ScriptStorage::update].
+pub type Update<'a> = dyn FnMut(&mut Map<String, Value>) -> Result<(), String> + 'a;
+
+/// Where the values of scripts are persisted, so that tests don't write to the app's stores.
+pub trait ScriptStorage: Send + Sync {
+    fn read(&self, scope: &StorageScope) -> Result<Map<String, Value>, String>;
+
+    /// Replaces the values of `scope` with what `update` made of them, nothing is written if it fails.
+    ///
+    /// No other update of the storage runs between reading and writing the values, no matter which engine or
+    /// cluster it comes from.
+    fn update(&self, scope: &StorageScope, update: &mut Update) -> Result<(), String>;
+}
+
+thread_local! {
+    /// The entry script that is being evaluated on this thread, see [evaluating]
+    static ENTRYPOINT: RefCell<Option<PathBuf>> = const { RefCell::new(None) };
+}
+
+/// Evaluates the top level of `entrypoint`. Storage handles created meanwhile belong to the script's package, also
+/// if they are created by a library that the script imported.
+pub fn evaluating<T>(entrypoint: &Path, eval: impl FnOnce() -> T) -> T {
+    /// Restores the outer entrypoint even if the evaluation panics
+    struct RestoreEntrypoint(Option<PathBuf>);
+
+    impl Drop for RestoreEntrypoint {
+        fn drop(&mut self) {
+            ENTRYPOINT.set(self.0.take());
+        }
+    }
+
+    let _restore = RestoreEntrypoint(ENTRYPOINT.replace(Some(entrypoint.to_owned())));
+
+    eval()
+}
+
+/// Keeps the values of scripts for as long as it lives.
+#[derive(Default)]
+pub struct MemoryStorage {
+    scopes: Mutex<HashMap<StorageScope, Map<String, Value>>>,
+}
+
+impl ScriptStorage for MemoryStorage {
+    fn read(&self, scope: &StorageScope) -> Result<Map<String, Value>, String> {
+        Ok(self
+            .scopes
+            .lock()
+            .unwrap()
+            .get(scope)
+            .cloned()
+            .unwrap_or_default())
+    }
+
+    fn update(&self, scope: &StorageScope, update: &mut Update) -> Result<(), String> {
+        let mut scopes = self.scopes.lock().unwrap();
+
+        let mut values = scopes.get(scope).cloned().unwrap_or_default();
+        update(&mut values)?;
+        scopes.insert(scope.clone(), values);
+
+        Ok(())
+    }
+}
+
+/// All values of a package are kept as one object, in the app-wide store or in the store of the cluster.
+impl ScriptStorage for Repository {
+    fn read(&self, scope: &StorageScope) -> Result<Map<String, Value>, String> {
+        let (context, key) = repository_location(scope);
+
+        match self.read_key(&context, &key).map_err(|e| e.to_string())? {
+            Some(Value::Object(values)) => Ok(values),
+            _ => Ok(Map::new()),
+        }
+    }
+
+    fn update(&self, scope: &StorageScope, update: &mut Update) -> Result<(), String> {
+        let _guard = self.script_storage_updates.lock().unwrap();
+
+        let mut values = self.read(scope)?;
+        update(&mut values)?;
+
+        let (context, key) = repository_location(scope);
+        self.set_key(&context, &key, Value::Object(values))
+            .map_err(|e| e.to_string())
+    }
+}
+
+fn repository_location(scope: &StorageScope) -> (Context, String) {
+    match scope {
+        StorageScope::Package(package) => (
+            Context::ApplicationWide,
+            format!("script_storage/{package}"),
+        ),
+        StorageScope::Cluster { package, cluster } => (
+            Context::PerCluster(cluster.clone()),
+            format!("script_storage/{package}"),
+        ),
+    }
+}
+
+/// A handle to the values of the package that created it, see [build_module].
+#[derive(Clone, Debug)]
+pub struct Storage {
+    scope: StorageScope,
+}
+
+/// Builds the Rhai `storage` module, which lets scripts remember values between invocations, e.g. the last image
+/// used for a debug container.
+///
+/// The package of a handle is the one of the script whose evaluation called `storage::all_clusters()` or
+/// `storage::cluster()`, see [evaluating]. So handles must be created while the script is evaluated, not within
+/// closures called later on:
+///
+/// ```rhai
+/// let store = storage::all_clusters();
+///
+/// ActionButton(#{ title: "Remember", action: |ctx| storage::set(store, "last", "value") })
+/// ```
+///
+/// Values must be JSON-compatible: `()`, booleans, numbers, strings, arrays and maps of them.
+pub fn build_module(backend: Arc<dyn ScriptStorage>, cluster: Option<String>) -> Module {
+    let mut storage_module = Module::new();
+    storage_module.set_custom_type::<Storage>("Storage");
+
+    FuncRegistration::new("all_clusters").set_into_module(
+        &mut storage_module,
+        || -> EvalResult<Storage> {
+            Ok(Storage {
+                scope: StorageScope::Package(calling_package("storage::all_clusters")?),
+            })
+        },
+    );
+
+    FuncRegistration::new("cluster").set_into_module(
+        &mut storage_module,
+        move || -> EvalResult<Storage> {
+            let cluster = cluster
+                .clone()
+                .ok_or("storage::cluster: scripts are not running for a cluster")?;
+
+            Ok(Storage {
+                scope: StorageScope::Cluster {
+                    package: calling_package("storage::cluster")?,
+                    cluster,
+                },
+            })
+        },
+    );
+
+    {
+        let backend = Arc::clone(&backend);
+
+        FuncRegistration::new("get").set_into_module(
+            &mut storage_module,
+            move |storage: &mut Storage, key: &str| -> EvalResult<Dynamic> {
+                get(&*backend, storage, key, Dynamic::UNIT)
+            },
+        );
+    }
+
+    {
+        let backend = Arc::clone(&backend);
+
+        FuncRegistration::new("get").set_into_module(
+            &mut storage_module,
+            move |storage: &mut Storage, key: &str, default: Dynamic| -> EvalResult<Dynamic> {
+                get(&*backend, storage, key, default)
+            },
+        );
+    }
+
+    {
+        let backend = Arc::clone(&backend);
+
+        FuncRegistration::new("set").set_into_module(
+            &mut storage_module,
+            move |storage: &mut Storage, key: &str, value: Dynamic| -> EvalResult<()> {
+                if key.is_empty() {
+                    return Err("storage::set: the key must not be empty".into());
+                }
+
+                let value = to_json(&value.flatten_clone(), "storage::set")?;
+
+                Ok(backend.update(&storage.scope, &mut |values| {
+                    values.insert(key.to_owned(), value.clone());
+
+                    let size = serde_json::to_vec(&values).map(|v| v.len()).unwrap_or(0);
+                    if size > QUOTA_BYTES {
+                        return Err(format!(
+                            "storage::set: `{key}` would grow the storage to {size} bytes, the quota is {QUOTA_BYTES} bytes"
+                        ));
+                    }
+
+                    Ok(())
+                })?)
+            },
+        );
+    }
+
+    {
+        let backend = Arc::clone(&backend);
+
+        FuncRegistration::new("remove").set_into_module(
+            &mut storage_module,
+            move |storage: &mut Storage, key: &str| -> EvalResult<Dynamic> {
+                let mut removed = None;
+
+                backend.update(&storage.scope, &mut |values| {
+                    removed = values.remove(key);
+                    Ok(())
+                })?;
+
+                match removed {
+                    Some(removed) => rhai::serde::to_dynamic(removed),
+                    None => Ok(Dynamic::UNIT),
+                }
+            },
+        );
+    }
+
+    {
+        let backend = Arc::clone(&backend);
+
+        FuncRegistration::new("keys").set_into_module(
+            &mut storage_module,
+            move |storage: &mut Storage| -> EvalResult<rhai::Array> {
+                Ok(backend
+                    .read(&storage.scope)?
+                    .keys()
+                    .map(|key| Dynamic::from(key.clone()))
+                    .collect())
+            },
+        );
+    }
+
+    {
+        let backend = Arc::clone(&backend);
+
+        FuncRegistration::new("clear").set_into_module(
+            &mut storage_module,
+            move |storage: &mut Storage| -> EvalResult<()> {
+                Ok(backend.update(&storage.scope, &mut |values| {
+                    values.clear();
+                    Ok(())
+                })?)
+            },
+        );
+    }
+
+    storage_module
+}
+
+fn get(
+    backend: &dyn ScriptStorage,
+    storage: &Storage,
+    key: &str,
+    default: Dynamic,
+) -> EvalResult<Dynamic> {
+    match backend.read(&storage.scope)?.remove(key) {
+        Some(value) => Ok(rhai::serde::to_dynamic(value)?),
+        None => Ok(default),
+    }
+}
+
+/// The name of the package whose script is being evaluated, as declared in its manifest.
+fn calling_package(function: &str) -> EvalResult<String> {
+    let entrypoint = ENTRYPOINT.with_borrow(Clone::clone);
+    let package = entrypoint
+        .as_deref()
+        .and_then(scripts_provider::package_dir_of)
+        .ok_or_else(|| {
+            format!("{function} must be called at the top level of a script, e.g. `let store = {function}();`")
+        })?;
+
+    // The name doesn't depend on the source, only the capabilities of packages without manifest do
+    PackageManifest::read(package, PackageSource::Extension)
+        .map(|manifest| manifest.name)
+        .map_err(|e| format!("{function}: {e}").into())
+}
+
+fn to_json(value: &Dynamic, function: &str) -> EvalResult<Value> {
+    check_json_compatible(value)
+        .map_err(|type_name| format!("{function}: cannot store a value of type {type_name}"))?;
+
+    rhai::serde::from_dynamic(value)
+}
+
+/// Fails with the name of the first type that has no JSON equivalent.
+fn check_json_compatible(value: &Dynamic) -> Result<(), &'static str> {
+    if value.is_array() {
+        return value
+            .read_lock::<rhai::Array>()
+            .unwrap()
+            .iter()
+            .try_for_each(check_json_compatible);
+    }
+
+    if value.is_map() {
+        return value
+            .read_lock::<rhai::Map>()
+            .unwrap()
+            .values()
+            .try_for_each(check_json_compatible);
+    }
+
+    if value.is_unit()
+        || value.is_bool()
+        || value.is_int()
+        || value.is_float()
+        || value.is_string()
+        || value.is_char()
+    {
+        return Ok(());
+    }
+
+    Err(value.type_name())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scripting::{module_resolver::LibraryResolver, test_fixtures::TempPackages};
+
+    fn engine(packages: &TempPackages, storage: &Arc<MemoryStorage>) -> rhai::Engine {
+        let mut engine = rhai::Engine::new();
+        engine
+            .set_module_resolver(LibraryResolver::new(Some(packages.root().join("core/lib"))))
+            .register_static_module(
+                "storage",
+                build_module(storage.clone(), Some("kind-dev".to_owned())).into(),
+            );
+        engine
+    }
+
+    fn run(
+        engine: &rhai::Engine,
+        packages: &TempPackages,
+        path: &str,
+        script: &str,
+    ) -> Result<Dynamic, String> {
+        let path = packages.write(path, script);
+
+        evaluating(&path, || {
+            engine
+                .compile_file(path.clone())
+                .and_then(|ast| engine.eval_ast::<Dynamic>(&ast))
+        })
+        .map_err(|e| e.to_string())
+    }
+
+    #[test]
+    pub fn test_scopes_are_separate() {
+        let packages = TempPackages::new();
+        let storage = Arc::new(MemoryStorage::default());
+        let engine = engine(&packages, &storage);
+
+        assert!(run(
+            &engine,
+            &packages,
+            "fleet/menus/a.rhai",
+            r#"
+            storage::set(storage::all_clusters(), "image", "busybox");
+            storage::set(storage::cluster(), "namespaces", ["default", "kube-system"]);
+            "#,
+        )
+        .unwrap()
+        .is_unit());
+
+        let other = run(
+            &engine,
+            &packages,
+            "other/menus/a.rhai",
+            r#"storage::get(storage::all_clusters(), "image", "none")"#,
+        )
+        .unwrap();
+        let same = run(
+            &engine,
+            &packages,
+            "fleet/presentations/a.rhai",
+            r#"`${storage::get(storage::all_clusters(), "image")} ${storage::get(storage::cluster(), "namespaces")}`"#,
+        )
+        .unwrap();
+
+        assert_eq!("none", other.into_string().unwrap());
+        assert_eq!(
+            r#"busybox ["default", "kube-system"]"#,
+            same.into_string().unwrap()
+        );
+        assert_eq!(
+            Some(&serde_json::json!(["default", "kube-system"])),
+            storage
+                .read(&StorageScope::Cluster {
+                    package: "fleet".to_owned(),
+                    cluster: "kind-dev".to_owned()
+                })
+                .unwrap()
+                .get("namespaces")
+        );
+    }
+
+    #[test]
+    pub fn test_remove_keys_and_clear() {
+        let packages = TempPackages::new();
+        let storage = Arc::new(MemoryStorage::default());
+        let engine = engine(&packages, &storage);
+
+        let result = run(
+            &engine,
+            &packages,
+            "fleet/menus/a.rhai",
+            r#"
+            let store = storage::all_clusters();
+            storage::set(store, "a", #{ nested: [1, 2.5, true, ()] });
+            storage::set(store, "b", 'c');
+
+            let removed = storage::remove(store, "a");
+            let keys = storage::keys(store);
+            storage::clear(store);
+
+            [removed.nested[1], keys, storage::keys(store), storage::remove(store, "a")]
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!("[2.5, [\"b\"], [], ()]", result.to_string());
+    }
+
+    #[test]
+    pub fn test_invalid_values_and_quota() {
+        let packages = TempPackages::new();
+        let storage = Arc::new(MemoryStorage::default());
+        let engine = engine(&packages, &storage);
+
+        let function = run(
+            &engine,
+            &packages,
+            "fleet/menus/a.rhai",
+            r#"storage::set(storage::all_clusters(), "f", #{ f: || 1 })"#,
+        );
+        let quota = run(
+            &engine,
+            &packages,
+            "fleet/menus/b.rhai",
+            r#"
+            let store = storage::all_clusters();
+            storage::set(store, "a", "small");
+            let large = "";
+            large.pad(70000, 'x');
+            storage::set(store, "b", large);
+            "#,
+        );
+
+        assert!(function
+            .unwrap_err()
+            .contains("cannot store a value of type Fn"));
+        assert!(quota.unwrap_err().contains("the quota is 65536 bytes"));
+        assert_eq!(
+            vec!["a"],
+            storage
+                .read(&StorageScope::Package("fleet".to_owned()))
+                .unwrap()
+                .keys()
+                .collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    pub fn test_handles_outlive_the_evaluation() {
+        let packages = TempPackages::new();
+        let storage = Arc::new(MemoryStorage::default());
+        let engine = engine(&packages, &storage);
+
+        let late = run(
+            &engine,
+            &packages,
+            "fleet/menus/a.rhai",
+            r#"
+            let store = storage::all_clusters();
+            [|| storage::set(store, "k", 1), || storage::all_clusters()]
+            "#,
+        )
+        .unwrap()
+        .into_array()
+        .unwrap();
+
+        let ast = rhai::AST::empty();
+        let call = |f: &Dynamic| {
+            f.clone_cast::<rhai::FnPtr>()
+                .call::<Dynamic>(&engine, &ast, ())
+                .map_err(|e| e.to_string())
+        };
+
+        assert!(call(&late[0]).is_ok());
+        assert!(call(&late[1])
+            .unwrap_err()
+            .contains("must be called at the top level"));
+    }
+
+    #[test]
+    pub fn test_handles_of_libraries_belong_to_the_importing_package() {
+        let packages = TempPackages::new();
+        let storage = Arc::new(MemoryStorage::default());
+        let engine = engine(&packages, &storage);
+        packages.write(
+            "core/lib/remember.rhai",
+            r#"fn last_image() { storage::get(storage::all_clusters(), "image", "none") }"#,
+        );
+
+        let image = run(
+            &engine,
+            &packages,
+            "fleet/menus/a.rhai",
+            r#"
+            import "remember" as r;
+
+            storage::set(storage::all_clusters(), "image", "busybox");
+            r::last_image()
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!("busybox", image.into_string().unwrap());
+        assert!(storage
+            .read(&StorageScope::Package("core".to_owned()))
+            .unwrap()
+            .is_empty());
+    }
+
+    #[test]
+    pub fn test_concurrent_updates_of_different_engines() {
+        let packages = TempPackages::new();
+        let storage = Arc::new(MemoryStorage::default());
+        let script = |prefix: &str| {
+            format!(
+                r#"
+                let store = storage::all_clusters();
+                for i in 0..200 {{ storage::set(store, `{prefix}${{i}}`, i); }}
+                "#
+            )
+        };
+        let menu = packages.write("fleet/menus/a.rhai", &script("menu"));
+        let presentation = packages.write("fleet/presentations/a.rhai", &script("presentation"));
+
+        std::thread::scope(|threads| {
+            for entrypoint in [&menu, &presentation] {
+                let storage = Arc::clone(&storage);
+
+                // Like the facades, which build an engine per kind of script and set of capabilities
+                threads.spawn(move || {
+                    let mut engine = rhai::Engine::new();
+                    engine.register_static_module("storage", build_module(storage, None).into());
+
+                    evaluating(entrypoint, || engine.run_file(entrypoint.clone())).unwrap();
+                });
+            }
+        });
+
+        assert_eq!(
+            400,
+            storage
+                .read(&StorageScope::Package("fleet".to_owned()))
+                .unwrap()
+                .len()
+        );
+    }
+}
@@ -0,0 +1,250 @@
+use std::sync::Arc;
+
+use chrono::{DateTime, Utc};
+use rhai::{Dynamic, FuncRegistration, Module};
+
+type EvalResult<T> = Result<T, Box<rhai::EvalAltResult>>;
+
+/// The source of the current time of the `time` module, so that tests don't depend on the wall clock.
+pub trait Clock: Send + Sync {
+    fn now(&self) -> DateTime<Utc>;
+}
+
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> DateTime<Utc> {
+        Utc::now()
+    }
+}
+
+/// A clock that is stuck at the given time.
+#[cfg(any(test, feature = "script-test"))]
+pub struct FixedClock(pub DateTime<Utc>);
+
+#[cfg(any(test, feature = "script-test"))]
+impl Clock for FixedClock {
+    fn now(&self) -> DateTime<Utc> {
+        self.0
+    }
+}
+
+/// Builds the Rhai `time` module.
+///
+/// Points in time are either RFC3339 strings, as found in Kubernetes objects, or Unix timestamps in seconds. Durations
+/// are always whole seconds, so that they can be compared and calculated with like any other number:
+/// `time::since(obj.metadata.creationTimestamp) < time::minutes(10)`.
+pub fn build_module(clock: Arc<dyn Clock>) -> Module {
+    let mut time_module = Module::new();
+
+    {
+        let clock = Arc::clone(&clock);
+
+        FuncRegistration::new("now")
+            .set_into_module(&mut time_module, move || -> i64 { clock.now().timestamp() });
+    }
+
+    {
+        let clock = Arc::clone(&clock);
+
+        FuncRegistration::new("since").set_into_module(
+            &mut time_module,
+            move |time: Dynamic| -> EvalResult<i64> {
+                Ok((clock.now() - to_datetime(&time, "time::since")?).num_seconds())
+            },
+        );
+    }
+
+    {
+        let clock = Arc::clone(&clock);
+
+        FuncRegistration::new("remaining").set_into_module(
+            &mut time_module,
+            move |time: Dynamic| -> EvalResult<i64> {
+                Ok((to_datetime(&time, "time::remaining")? - clock.now()).num_seconds())
+            },
+        );
+    }
+
+    FuncRegistration::new("parse")
+        .set_into_module(&mut time_module, |text: &str| -> EvalResult<i64> {
+            Ok(parse_rfc3339(text, "time::parse")?.timestamp())
+        });
+
+    FuncRegistration::new("diff").set_into_module(
+        &mut time_module,
+        |from: Dynamic, to: Dynamic| -> EvalResult<i64> {
+            Ok((to_datetime(&to, "time::diff")? - to_datetime(&from, "time::diff")?).num_seconds())
+        },
+    );
+
+    FuncRegistration::new("to_rfc3339").set_into_module(
+        &mut time_module,
+        |time: Dynamic| -> EvalResult<String> {
+            Ok(to_datetime(&time, "time::to_rfc3339")?
+                .to_rfc3339_opts(chrono::SecondsFormat::Secs, true))
+        },
+    );
+
+    FuncRegistration::new("format_duration")
+        .set_into_module(&mut time_module, |seconds: i64| -> String {
+            format_duration(seconds)
+        });
+
+    FuncRegistration::new("seconds").set_into_module(&mut time_module, |n: i64| -> i64 { n });
+    FuncRegistration::new("minutes")
+        .set_into_module(&mut time_module, |n: i64| -> EvalResult<i64> {
+            in_seconds(n, 60, "time::minutes")
+        });
+    FuncRegistration::new("hours").set_into_module(&mut time_module, |n: i64| -> EvalResult<i64> {
+        in_seconds(n, 60 * 60, "time::hours")
+    });
+    FuncRegistration::new("days").set_into_module(&mut time_module, |n: i64| -> EvalResult<i64> {
+        in_seconds(n, 24 * 60 * 60, "time::days")
+    });
+
+    time_module
+}
+
+/// `n` units of `unit` seconds each, an error instead of an overflow.
+fn in_seconds(n: i64, unit: i64, function: &str) -> EvalResult<i64> {
+    n.checked_mul(unit)
+        .ok_or_else(|| format!("{function}: {n} is out of range").into())
+}
+
+fn parse_rfc3339(text: &str, function: &str) -> EvalResult<DateTime<Utc>> {
+    text.parse::<DateTime<Utc>>()
+        .map_err(|e| format!("{function}: invalid RFC3339 timestamp `{text}`: {e}").into())
+}
+
+fn to_datetime(time: &Dynamic, function: &str) -> EvalResult<DateTime<Utc>> {
+    if let Ok(seconds) = time.as_int() {
+        return DateTime::from_timestamp(seconds, 0)
+            .ok_or_else(|| format!("{function}: timestamp {seconds} is out of range").into());
+    }
+
+    match time.read_lock::<rhai::ImmutableString>() {
+        Some(text) => parse_rfc3339(&text, function),
+        None => Err(format!(
+            "{function}: expected an RFC3339 string or Unix timestamp, got {}",
+            time.type_name()
+        )
+        .into()),
+    }
+}
+
+/// Formats a duration the way `kubectl` shows ages: the two most significant units, e.g. `45s`, `12m`, `3h20m` or
+/// `5d4h`. Negative durations are prefixed with `-`.
+pub fn format_duration(seconds: i64) -> String {
+    let sign = if seconds < 0 { "-" } else { "" };
+    let seconds = seconds.unsigned_abs();
+
+    let (days, hours, minutes) = (seconds / 86400, seconds / 3600 % 24, seconds / 60 % 60);
+
+    let formatted = match () {
+        _ if days > 0 && hours > 0 => format!("{days}d{hours}h"),
+        _ if days > 0 => format!("{days}d"),
+        _ if hours > 0 && minutes > 0 => format!("{hours}h{minutes}m"),
+        _ if hours > 0 => format!("{hours}h"),
+        _ if minutes > 0 => format!("{minutes}m"),
+        _ => format!("{seconds}s"),
+    };
+
+    format!("{sign}{formatted}")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn engine() -> rhai::Engine {
+        let now = "2026-03-01T12:00:00Z".parse().unwrap();
+
+        let mut engine = rhai::Engine::new();
+        engine.register_static_module("time", build_module(Arc::new(FixedClock(now))).into());
+        engine
+    }
+
+    #[test]
+    pub fn test_relative_to_clock() {
+        let engine = engine();
+
+        assert_eq!(
+            600,
+            engine
+                .eval::<i64>(r#"time::since("2026-03-01T11:50:00Z")"#)
+                .unwrap()
+        );
+        assert_eq!(
+            5 * 86400,
+            engine
+                .eval::<i64>(r#"time::remaining("2026-03-06T12:00:00+00:00")"#)
+                .unwrap()
+        );
+        assert!(engine
+            .eval::<bool>(r#"time::since("2026-03-01T11:55:00Z") < time::minutes(10)"#)
+            .unwrap());
+        assert_eq!(
+            "2026-03-01T12:00:00Z",
+            engine
+                .eval::<String>("time::to_rfc3339(time::now())")
+                .unwrap()
+        );
+    }
+
+    #[test]
+    pub fn test_parse_and_diff() {
+        let engine = engine();
+
+        assert_eq!(
+            0,
+            engine
+                .eval::<i64>(r#"time::parse("1970-01-01T00:00:00Z")"#)
+                .unwrap()
+        );
+        assert_eq!(
+            -3600,
+            engine
+                .eval::<i64>(r#"time::diff("2026-03-01T12:00:00Z", "2026-03-01T11:00:00Z")"#)
+                .unwrap()
+        );
+        assert_eq!(
+            90,
+            engine
+                .eval::<i64>(
+                    r#"time::diff(time::parse("2026-03-01T12:00:00Z"), "2026-03-01T12:01:30Z")"#
+                )
+                .unwrap()
+        );
+    }
+
+    #[test]
+    pub fn test_invalid_input() {
+        let engine = engine();
+
+        let error = engine
+            .eval::<i64>(r#"time::parse("yesterday")"#)
+            .unwrap_err();
+
+        assert!(error.to_string().contains("time::parse"));
+        assert!(engine.eval::<i64>("time::since(())").is_err());
+
+        let error = engine
+            .eval::<i64>("time::days(9223372036854775807)")
+            .unwrap_err();
+
+        assert!(error.to_string().contains("time::days"));
+        assert_eq!(-120, engine.eval::<i64>("time::minutes(-2)").unwrap());
+    }
+
+    #[test]
+    pub fn test_format_duration() {
+        assert_eq!("45s", format_duration(45));
+        assert_eq!("12m", format_duration(12 * 60 + 5));
+        assert_eq!("3h20m", format_duration(3 * 3600 + 20 * 60));
+        assert_eq!("2h", format_duration(2 * 3600 + 30));
+        assert_eq!("5d4h", format_duration(5 * 86400 + 4 * 3600 + 59));
+        assert_eq!("-1m", format_duration(-60));
+        assert_eq!("0s", format_duration(0));
+    }
+}
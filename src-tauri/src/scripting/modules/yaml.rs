@@ -0,0 +1,82 @@
+use rhai::plugin::*;
+
+#[export_module]
+pub mod yaml_rhai {
+    use rhai::{Dynamic, EvalAltResult};
+
+    use crate::scripting::limits;
+
+    /// Parses a single YAML document into maps, arrays and primitive values. Keys must be strings.
+    #[rhai_fn(return_raw)]
+    pub fn parse(text: &str) -> Result<Dynamic, Box<EvalAltResult>> {
+        limits::check_document_size("yaml::parse", text.len())?;
+
+        // Going through JSON values rejects what scripts could not represent anyway, e.g. non-string keys.
+        let value: serde_json::Value =
+            serde_yaml::from_str(text).map_err(|e| format!("yaml::parse: {e}"))?;
+
+        rhai::serde::to_dynamic(value)
+    }
+
+    #[rhai_fn(name = "to_string", return_raw)]
+    pub fn to_string(value: Dynamic) -> Result<String, Box<EvalAltResult>> {
+        limits::serialize_document("yaml::to_string", |writer| {
+            serde_yaml::to_writer(writer, &value)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rhai::{exported_module, Dynamic};
+
+    use super::yaml_rhai;
+
+    fn engine() -> rhai::Engine {
+        let mut engine = rhai::Engine::new();
+        engine.register_static_module("yaml", exported_module!(yaml_rhai).into());
+        engine
+    }
+
+    #[test]
+    pub fn test_roundtrip() {
+        let engine = engine();
+
+        assert_eq!(
+            "debug",
+            engine
+                .eval::<String>(
+                    r#"yaml::parse("logging:\n  level: debug\nports: [80, 443]\n").logging.level"#
+                )
+                .unwrap()
+        );
+        assert_eq!(
+            "name: web\nports:\n- 80\n",
+            engine
+                .eval::<String>(r#"yaml::to_string(#{ name: "web", ports: [80] })"#)
+                .unwrap()
+        );
+    }
+
+    #[test]
+    pub fn test_invalid_document() {
+        let error = yaml_rhai::parse("a: [unclosed").unwrap_err();
+        let non_string_keys = yaml_rhai::parse("{ [1, 2]: x }").unwrap_err();
+
+        assert!(error.to_string().contains("yaml::parse"), "{error}");
+        assert!(non_string_keys.to_string().contains("yaml::parse"));
+    }
+
+    #[test]
+    pub fn test_size_limit() {
+        let text = "x".repeat(crate::scripting::limits::MAX_DOCUMENT_SIZE + 1);
+        let error = yaml_rhai::parse(&text).unwrap_err();
+
+        assert!(error.to_string().contains("exceeds the limit"), "{error}");
+
+        let error = yaml_rhai::to_string(Dynamic::from(text)).unwrap_err();
+
+        assert!(error.to_string().contains("yaml::to_string"), "{error}");
+        assert!(error.to_string().contains("exceeds the limit"), "{error}");
+    }
+}
@@ -0,0 +1,541 @@
+use std::{
+    fs::{self, File},
+    io,
+    path::{Path, PathBuf},
+};
+
+use crate::scripting::{
+    limits,
+    package_manifest::{self, PackageManifest, MANIFEST_FILE_NAME},
+    scripts_provider::{self, PackageSource},
+};
+
+/// Installs, upgrades and removes extension packages in the `extensions` directory.
+///
+/// Packages are unpacked into a hidden staging directory next to the installed packages first. Only after the
+/// manifest has been validated and all scripts compile, the staging directory is moved into place. Hidden
+/// directories are ignored by the [ScriptsProvider](super::scripts_provider::ScriptsProvider), so running
+/// clusters never load a half-installed package.
+pub struct PackageInstaller {
+    extensions_dir: PathBuf,
+}
+
+impl PackageInstaller {
+    pub fn new(extensions_dir: PathBuf) -> Self {
+        Self { extensions_dir }
+    }
+
+    /// Installs a new package from a directory, a `.zip` or a `.tar.gz` archive.
+    pub fn install(&self, source: &Path) -> Result<PackageManifest, Error> {
+        let staged = self.stage(source)?;
+
+        if let Some((_, installed)) = self.find_installed(&staged.manifest.name)? {
+            staged.discard();
+            return Err(Error::AlreadyInstalled(
+                installed.name,
+                installed.version.to_string(),
+            ));
+        }
+
+        let target = self.extensions_dir.join(&staged.manifest.name);
+        staged.activate(&target)
+    }
+
+    /// Replaces an installed package with a newer version of it.
+    pub fn upgrade(&self, source: &Path) -> Result<PackageManifest, Error> {
+        let staged = self.stage(source)?;
+
+        let Some((installed_dir, installed)) = self.find_installed(&staged.manifest.name)? else {
+            staged.discard();
+            return Err(Error::NotInstalled(staged.manifest.name.clone()));
+        };
+
+        if staged.manifest.version < installed.version {
+            staged.discard();
+            return Err(Error::Downgrade(
+                installed.version.to_string(),
+                staged.manifest.version.to_string(),
+            ));
+        }
+
+        // Keep the previous version around until the new one is in place, so a failed move can be rolled back
+        let backup = self.hidden_dir("backup");
+        fs::rename(&installed_dir, &backup)?;
+
+        match staged.activate(&installed_dir) {
+            Ok(manifest) => {
+                if let Err(e) = fs::remove_dir_all(&backup) {
+                    tracing::warn!("Failed to remove {}: {e}", backup.display());
+                }
+                Ok(manifest)
+            }
+            Err(e) => {
+                fs::rename(&backup, &installed_dir)?;
+                Err(e)
+            }
+        }
+    }
+
+    pub fn uninstall(&self, name: &str) -> Result<(), Error> {
+        let (installed_dir, _) = self
+            .find_installed(name)?
+            .ok_or_else(|| Error::NotInstalled(name.to_owned()))?;
+
+        fs::remove_dir_all(installed_dir)?;
+
+        Ok(())
+    }
+
+    /// Finds the directory of an installed package by the name in its manifest.
+    ///
+    /// The directory name is not relevant, so packages that have been copied in manually are found as well.
+    fn find_installed(&self, name: &str) -> Result<Option<(PathBuf, PackageManifest)>, Error> {
+        let entries = match fs::read_dir(&self.extensions_dir) {
+            Ok(entries) => entries,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(e.into()),
+        };
+
+        for entry in entries {
+            let path = entry?.path();
+
+            if !path.is_dir() || is_hidden(&path) {
+                continue;
+            }
+
+            // Broken packages can't be matched by name, they have to be removed by hand
+            if let Ok(manifest) = PackageManifest::read(&path, PackageSource::Extension) {
+                if manifest.name == name {
+                    return Ok(Some((path, manifest)));
+                }
+            }
+        }
+
+        Ok(None)
+    }
+
+    fn hidden_dir(&self, purpose: &str) -> PathBuf {
+        self.extensions_dir
+            .join(format!(".{purpose}-{}", uuid::Uuid::new_v4()))
+    }
+
+    fn stage(&self, source: &Path) -> Result<StagedPackage, Error> {
+        fs::create_dir_all(&self.extensions_dir)?;
+
+        let staging_dir = self.hidden_dir("install");
+        let staged = StagedPackage::unpack(source, staging_dir.clone()).and_then(|root| {
+            let manifest = validate(&root)?;
+
+            Ok(StagedPackage {
+                staging_dir: staging_dir.clone(),
+                root,
+                manifest,
+            })
+        });
+
+        if staged.is_err() {
+            let _ = fs::remove_dir_all(&staging_dir);
+        }
+
+        staged
+    }
+}
+
+/// A package that has been unpacked and validated, but is not visible to the scripts provider yet.
+struct StagedPackage {
+    staging_dir: PathBuf,
+    /// The directory containing `package.toml`, either the staging directory itself or its only child
+    root: PathBuf,
+    manifest: PackageManifest,
+}
+
+impl StagedPackage {
+    fn unpack(source: &Path, staging_dir: PathBuf) -> Result<PathBuf, Error> {
+        let file_name = source
+            .file_name()
+            .map(|name| name.to_string_lossy().to_lowercase())
+            .unwrap_or_default();
+
+        if source.is_dir() {
+            copy_dir(source, &staging_dir)?;
+        } else if file_name.ends_with(".zip") {
+            zip::ZipArchive::new(File::open(source)?)?.extract(&staging_dir)?;
+        } else if file_name.ends_with(".tar.gz") || file_name.ends_with(".tgz") {
+            let decoder = flate2::read::GzDecoder::new(File::open(source)?);
+            tar::Archive::new(decoder).unpack(&staging_dir)?;
+        } else {
+            return Err(Error::UnsupportedSource(source.to_path_buf()));
+        }
+
+        if staging_dir.join(MANIFEST_FILE_NAME).is_file() {
+            return Ok(staging_dir);
+        }
+
+        // Archives commonly wrap their contents in a single directory, e.g. `fleet-1.2.0/package.toml`
+        let children = fs::read_dir(&staging_dir)?
+            .map(|entry| entry.map(|e| e.path()))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        match children.as_slice() {
+            [child] if child.join(MANIFEST_FILE_NAME).is_file() => Ok(child.clone()),
+            _ => Err(Error::MissingManifest),
+        }
+    }
+
+    fn activate(self, target: &Path) -> Result<PackageManifest, Error> {
+        let result = fs::rename(&self.root, target);
+        self.discard();
+
+        result?;
+
+        Ok(self.manifest)
+    }
+
+    fn discard(&self) {
+        if let Err(e) = fs::remove_dir_all(&self.staging_dir) {
+            if e.kind() != io::ErrorKind::NotFound {
+                tracing::warn!("Failed to remove {}: {e}", self.staging_dir.display());
+            }
+        }
+    }
+}
+
+/// Checks that a package can be installed: its manifest must be valid and all of its scripts must compile.
+pub fn validate(package_dir: &Path) -> Result<PackageManifest, Error> {
+    let manifest =
+        PackageManifest::parse(&fs::read_to_string(package_dir.join(MANIFEST_FILE_NAME))?)?;
+
+    // The name becomes the directory name
+    let valid_name = manifest
+        .name
+        .chars()
+        .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'))
+        && !manifest.name.starts_with('.');
+
+    if !valid_name {
+        return Err(Error::InvalidName(manifest.name));
+    }
+
+    let hyprkube_version = package_manifest::hyprkube_version();
+    if !manifest.is_compatible_with(&hyprkube_version) {
+        return Err(Error::Incompatible(
+            manifest
+                .min_hyprkube_version
+                .unwrap_or(hyprkube_version)
+                .to_string(),
+        ));
+    }
+
+    // Compile with the same limits as when the scripts are loaded, a script that is nested too deeply fails here
+    let mut engine = rhai::Engine::new();
+    limits::apply(&mut engine);
+
+    let relative = |path: &Path| path.strip_prefix(package_dir).unwrap_or(path).to_path_buf();
+
+    let scripts = rhai_scripts_in(package_dir).map_err(|e| match e {
+        Error::SymbolicLink(path) => Error::SymbolicLink(relative(&path)),
+        e => e,
+    })?;
+
+    for script in scripts {
+        engine
+            .compile_file(script.clone())
+            .map_err(|error| Error::Compilation {
+                script: relative(&script),
+                error,
+            })?;
+    }
+
+    Ok(manifest)
+}
+
+/// All scripts of a package. Symbolic links are rejected, so that a package can't pull in files from elsewhere.
+fn rhai_scripts_in(dir: &Path) -> Result<Vec<PathBuf>, Error> {
+    let mut scripts = Vec::new();
+
+    for entry in fs::read_dir(dir)? {
+        let entry = entry?;
+        let path = entry.path();
+        let file_type = entry.file_type()?;
+
+        if file_type.is_symlink() {
+            return Err(Error::SymbolicLink(path));
+        } else if file_type.is_dir() {
+            scripts.append(&mut rhai_scripts_in(&path)?);
+        } else if path.extension().is_some_and(|ext| ext == "rhai") {
+            scripts.push(path);
+        }
+    }
+
+    scripts.sort();
+
+    Ok(scripts)
+}
+
+fn copy_dir(source: &Path, target: &Path) -> Result<(), Error> {
+    fs::create_dir_all(target)?;
+
+    for entry in fs::read_dir(source)? {
+        let entry = entry?;
+        let target = target.join(entry.file_name());
+        let file_type = entry.file_type()?;
+
+        if file_type.is_symlink() {
+            return Err(Error::SymbolicLink(entry.path()));
+        } else if file_type.is_dir() {
+            copy_dir(&entry.path(), &target)?;
+        } else {
+            fs::copy(entry.path(), target)?;
+        }
+    }
+
+    Ok(())
+}
+
+fn is_hidden(path: &Path) -> bool {
+    path.file_name()
+        .is_some_and(|name| name.to_string_lossy().starts_with('.'))
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum Error {
+    #[error(transparent)]
+    Io(#[from] io::Error),
+
+    #[error(transparent)]
+    Zip(#[from] zip::result::ZipError),
+
+    #[error(transparent)]
+    Manifest(#[from] package_manifest::Error),
+
+    #[error(transparent)]
+    ExtensionsDir(#[from] scripts_provider::Error),
+
+    #[error("Packages can only be installed from a directory, a .zip or a .tar.gz archive: {0}")]
+    UnsupportedSource(PathBuf),
+
+    #[error("The package does not contain a {MANIFEST_FILE_NAME}")]
+    MissingManifest,
+
+    #[error("Packages must not contain symbolic links: {}", .0.display())]
+    SymbolicLink(PathBuf),
+
+    #[error("Invalid package name `{0}`, only letters, digits, `-`, `_` and `.` are allowed")]
+    InvalidName(String),
+
+    #[error("The package requires Hyprkube {0} or newer")]
+    Incompatible(String),
+
+    #[error("{}: {error}", script.display())]
+    Compilation {
+        script: PathBuf,
+        error: Box<rhai::EvalAltResult>,
+    },
+
+    #[error("Package {0} {1} is already installed")]
+    AlreadyInstalled(String, String),
+
+    #[error("Package {0} is not installed")]
+    NotInstalled(String),
+
+    #[error("Refusing to replace version {0} with older version {1}")]
+    Downgrade(String, String),
+}
+
+impl serde::Serialize for Error {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::ser::Serializer,
+    {
+        serializer.serialize_str(self.to_string().as_ref())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Write as _;
+
+    use super::*;
+    use crate::scripting::test_fixtures::TempPackages;
+
+    fn write_package(dir: &Path, version: &str, script: &str) {
+        fs::create_dir_all(dir.join("menus")).unwrap();
+        fs::write(
+            dir.join(MANIFEST_FILE_NAME),
+            format!("name = \"fleet\"\nversion = \"{version}\""),
+        )
+        .unwrap();
+        fs::write(dir.join("menus/gitrepo.rhai"), script).unwrap();
+    }
+
+    fn installed_files(extensions: &Path) -> Vec<String> {
+        let mut files: Vec<String> = rhai_scripts_in(extensions)
+            .unwrap()
+            .iter()
+            .map(|p| p.strip_prefix(extensions).unwrap().display().to_string())
+            .collect();
+        files.sort();
+        files
+    }
+
+    #[test]
+    pub fn test_install_from_directory() {
+        let tmp = TempPackages::new();
+        let source = tmp.root().join("source");
+        let extensions = tmp.root().join("extensions");
+        write_package(&source, "1.0.0", "let x = 1;");
+
+        let installer = PackageInstaller::new(extensions.clone());
+        let manifest = installer.install(&source).unwrap();
+
+        assert_eq!("fleet", manifest.name);
+        assert_eq!(
+            vec!["fleet/menus/gitrepo.rhai"],
+            installed_files(&extensions)
+        );
+        assert!(matches!(
+            installer.install(&source),
+            Err(Error::AlreadyInstalled(..))
+        ));
+    }
+
+    #[test]
+    pub fn test_install_from_tar_gz_with_wrapping_directory() {
+        let tmp = TempPackages::new();
+        let source = tmp.root().join("source");
+        let extensions = tmp.root().join("extensions");
+        write_package(&source, "1.0.0", "let x = 1;");
+
+        let archive = tmp.root().join("fleet-1.0.0.tar.gz");
+        let encoder =
+            flate2::write::GzEncoder::new(File::create(&archive).unwrap(), Default::default());
+        let mut builder = tar::Builder::new(encoder);
+        builder.append_dir_all("fleet-1.0.0", &source).unwrap();
+        builder.into_inner().unwrap().finish().unwrap();
+
+        PackageInstaller::new(extensions.clone())
+            .install(&archive)
+            .unwrap();
+
+        assert_eq!(
+            vec!["fleet/menus/gitrepo.rhai"],
+            installed_files(&extensions)
+        );
+    }
+
+    #[test]
+    pub fn test_install_from_zip() {
+        let tmp = TempPackages::new();
+        let extensions = tmp.root().join("extensions");
+
+        let archive = tmp.root().join("fleet.zip");
+        let mut writer = zip::ZipWriter::new(File::create(&archive).unwrap());
+        let options = zip::write::SimpleFileOptions::default()
+            .compression_method(zip::CompressionMethod::Stored);
+        writer.start_file(MANIFEST_FILE_NAME, options).unwrap();
+        writer
+            .write_all(b"name = \"fleet\"\nversion = \"1.0.0\"")
+            .unwrap();
+        writer.start_file("menus/gitrepo.rhai", options).unwrap();
+        writer.write_all(b"let x = 1;").unwrap();
+        writer.finish().unwrap();
+
+        PackageInstaller::new(extensions.clone())
+            .install(&archive)
+            .unwrap();
+
+        assert_eq!(
+            vec!["fleet/menus/gitrepo.rhai"],
+            installed_files(&extensions)
+        );
+    }
+
+    #[test]
+    pub fn test_validate_with_the_limits_of_loaded_scripts() {
+        let tmp = TempPackages::new();
+        let source = tmp.root().join("source");
+        let extensions = tmp.root().join("extensions");
+        // Deeper than the default expression depth of debug builds, but within the limits of loaded scripts
+        let nested = format!("let x = {}1{};", "(".repeat(20), ")".repeat(20));
+        write_package(&source, "1.0.0", &nested);
+
+        PackageInstaller::new(extensions).install(&source).unwrap();
+    }
+
+    #[test]
+    #[cfg(unix)]
+    pub fn test_reject_symbolic_links() {
+        let tmp = TempPackages::new();
+        let source = tmp.root().join("source");
+        let extensions = tmp.root().join("extensions");
+        let secret = tmp.write("secret.txt", "not part of the package");
+        write_package(&source, "1.0.0", "let x = 1;");
+        std::os::unix::fs::symlink(secret, source.join("menus/secret.txt")).unwrap();
+
+        let result = PackageInstaller::new(extensions.clone()).install(&source);
+
+        assert!(
+            matches!(result, Err(Error::SymbolicLink(ref link)) if link.ends_with("menus/secret.txt"))
+        );
+        assert_eq!(0, fs::read_dir(&extensions).unwrap().count());
+    }
+
+    #[test]
+    pub fn test_reject_package_that_does_not_compile() {
+        let tmp = TempPackages::new();
+        let source = tmp.root().join("source");
+        let extensions = tmp.root().join("extensions");
+        write_package(&source, "1.0.0", "let x = ;");
+
+        let result = PackageInstaller::new(extensions.clone()).install(&source);
+
+        assert!(
+            matches!(result, Err(Error::Compilation { ref script, .. }) if script == Path::new("menus/gitrepo.rhai"))
+        );
+        // Neither the package nor its staging directory are left behind
+        assert_eq!(0, fs::read_dir(&extensions).unwrap().count());
+    }
+
+    #[test]
+    pub fn test_upgrade_and_uninstall() {
+        let tmp = TempPackages::new();
+        let v1 = tmp.root().join("v1");
+        let v2 = tmp.root().join("v2");
+        let v3 = tmp.root().join("v3");
+        let broken = tmp.root().join("broken");
+        let extensions = tmp.root().join("extensions");
+        write_package(&v1, "1.0.0", "let x = 1;");
+        write_package(&v2, "2.0.0", "let x = 2;");
+        write_package(&v3, "3.0.0", "let x = 3;");
+        write_package(&broken, "3.0.0", "let x = ;");
+
+        let installer = PackageInstaller::new(extensions.clone());
+
+        assert!(matches!(
+            installer.upgrade(&v1),
+            Err(Error::NotInstalled(_))
+        ));
+
+        installer.install(&v2).unwrap();
+        assert!(matches!(installer.upgrade(&v1), Err(Error::Downgrade(..))));
+        assert!(installer.upgrade(&broken).is_err());
+
+        // Failed upgrades keep the installed version
+        assert_eq!(
+            "let x = 2;",
+            fs::read_to_string(extensions.join("fleet/menus/gitrepo.rhai")).unwrap()
+        );
+
+        assert_eq!(
+            semver::Version::new(3, 0, 0),
+            installer.upgrade(&v3).unwrap().version
+        );
+        assert_eq!(
+            "let x = 3;",
+            fs::read_to_string(extensions.join("fleet/menus/gitrepo.rhai")).unwrap()
+        );
+
+        installer.uninstall("fleet").unwrap();
+        assert_eq!(0, fs::read_dir(&extensions).unwrap().count());
+    }
+}
@@ -0,0 +1,183 @@
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use crate::scripting::scripts_provider::PackageSource;
+
+pub const MANIFEST_FILE_NAME: &str = "package.toml";
+
+/// A permission that a package needs for its scripts, e.g. to modify resources in the cluster.
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[serde(rename_all = "kebab-case")]
+pub enum Capability {
+    /// Read resources via the `kube` module
+    KubeRead,
+    /// Create and modify resources via the `kube` module
+    KubeWrite,
+    /// Delete resources via the `kube` module
+    KubeDelete,
+    /// Write to the clipboard
+    Clipboard,
+    /// Open tabs and dialogs in the frontend
+    Frontend,
+}
+
+/// The contents of a `package.toml` in the root directory of a script package.
+///
+/// ```toml
+/// name = "fleet"
+/// version = "1.2.0"
+/// description = "Menus and presentations for Rancher Fleet"
+/// min-hyprkube-version = "0.1.0"
+/// capabilities = ["kube-read", "clipboard"]
+/// ```
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all(serialize = "camelCase", deserialize = "kebab-case"))]
+#[serde(deny_unknown_fields)]
+pub struct PackageManifest {
+    pub name: String,
+    pub version: semver::Version,
+    #[serde(default)]
+    pub description: Option<String>,
+    #[serde(default)]
+    pub min_hyprkube_version: Option<semver::Version>,
+    #[serde(default)]
+    pub capabilities: Vec<Capability>,
+}
+
+impl PackageManifest {
+    pub fn parse(content: &str) -> Result<Self, Error> {
+        let manifest: Self = toml::from_str(content)?;
+
+        if manifest.name.trim().is_empty() {
+            return Err(Error::EmptyName);
+        }
+
+        Ok(manifest)
+    }
+
+    /// Reads the manifest of the package in `package_dir`.
+    ///
+    /// Packages from before manifests existed are still supported: without a `package.toml`, the package
+    /// is named after its directory and has version `0.0.0`, see [Self::unversioned] for its capabilities.
+    pub fn read(package_dir: &Path, source: PackageSource) -> Result<Self, Error> {
+        match std::fs::read_to_string(package_dir.join(MANIFEST_FILE_NAME)) {
+            Ok(content) => Self::parse(&content),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+                Ok(Self::unversioned(package_dir, source))
+            }
+            Err(e) => Err(Error::Io(e)),
+        }
+    }
+
+    /// A manifest for a package without `package.toml`, named after its directory.
+    ///
+    /// Builtin packages keep all capabilities they always had. Extensions get none, they have to declare
+    /// what they need in a manifest.
+    pub fn unversioned(package_dir: &Path, source: PackageSource) -> Self {
+        let capabilities = match source {
+            PackageSource::Builtin => Capability::ALL.to_vec(),
+            PackageSource::Extension => Vec::new(),
+        };
+
+        Self {
+            name: package_dir
+                .file_name()
+                .map(|name| name.to_string_lossy().to_string())
+                .unwrap_or_default(),
+            version: semver::Version::new(0, 0, 0),
+            description: None,
+            min_hyprkube_version: None,
+            capabilities,
+        }
+    }
+
+    /// Whether this package can run on the given version of Hyprkube.
+    pub fn is_compatible_with(&self, hyprkube_version: &semver::Version) -> bool {
+        self.min_hyprkube_version
+            .as_ref()
+            .is_none_or(|min| hyprkube_version >= min)
+    }
+}
+
+/// The version of this build of Hyprkube, as checked against `min-hyprkube-version`.
+pub fn hyprkube_version() -> semver::Version {
+    semver::Version::parse(env!("CARGO_PKG_VERSION")).expect("crate version is valid semver")
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum Error {
+    #[error("Failed to read {MANIFEST_FILE_NAME}: {0}")]
+    Io(#[from] std::io::Error),
+
+    #[error("Invalid {MANIFEST_FILE_NAME}: {0}")]
+    Parse(#[from] toml::de::Error),
+
+    #[error("Invalid {MANIFEST_FILE_NAME}: `name` must not be empty")]
+    EmptyName,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scripting::test_fixtures::TempPackages;
+
+    #[test]
+    pub fn test_parse_full_manifest() {
+        let manifest = PackageManifest::parse(
+            r#"
+name = "fleet"
+version = "1.2.0"
+description = "Rancher Fleet"
+min-hyprkube-version = "0.2.0"
+capabilities = ["kube-read", "clipboard"]
+"#,
+        )
+        .unwrap();
+
+        assert_eq!("fleet", manifest.name);
+        assert_eq!(semver::Version::new(1, 2, 0), manifest.version);
+        assert_eq!(
+            vec![Capability::KubeRead, Capability::Clipboard],
+            manifest.capabilities
+        );
+        assert!(!manifest.is_compatible_with(&semver::Version::new(0, 1, 9)));
+        assert!(manifest.is_compatible_with(&semver::Version::new(0, 2, 0)));
+    }
+
+    #[test]
+    pub fn test_parse_minimal_manifest() {
+        let manifest = PackageManifest::parse("name = \"fleet\"\nversion = \"0.1.0\"").unwrap();
+
+        assert!(manifest.capabilities.is_empty());
+        assert!(manifest.is_compatible_with(&semver::Version::new(0, 0, 1)));
+    }
+
+    #[test]
+    pub fn test_err_on_invalid_manifest() {
+        assert!(PackageManifest::parse("name = \"fleet\"").is_err());
+        assert!(PackageManifest::parse("name = \"\"\nversion = \"0.1.0\"").is_err());
+        assert!(PackageManifest::parse("name = \"a\"\nversion = \"latest\"").is_err());
+        assert!(PackageManifest::parse(
+            "name = \"a\"\nversion = \"0.1.0\"\ncapabilities = [\"root\"]"
+        )
+        .is_err());
+        assert!(
+            PackageManifest::parse("name = \"a\"\nversion = \"0.1.0\"\nauthor = \"me\"").is_err()
+        );
+    }
+
+    #[test]
+    pub fn test_missing_manifest_uses_directory_name() {
+        let packages = TempPackages::new();
+        let dir = packages.root();
+
+        let extension = PackageManifest::read(dir, PackageSource::Extension).unwrap();
+        let builtin = PackageManifest::read(dir, PackageSource::Builtin).unwrap();
+
+        assert_eq!(dir.file_name().unwrap().to_str().unwrap(), extension.name);
+        assert_eq!(semver::Version::new(0, 0, 0), extension.version);
+        assert!(extension.capabilities.is_empty());
+        assert_eq!(Capability::ALL.to_vec(), builtin.capabilities);
+    }
+}
@@ -0,0 +1,319 @@
+use std::{collections::HashSet, sync::mpsc::Receiver, time::Duration};
+
+use rhai::{Dynamic, EvalAltResult};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tauri::Emitter as _;
+
+use crate::{
+    app_state::{ManagerExt as _, MenuPrompts, PromptAnswer, PromptId},
+    scripting::limits,
+};
+
+type EvalResult<T> = Result<T, Box<EvalAltResult>>;
+
+/// A dialog that a menu action shows to ask the user for input.
+///
+/// ```rhai
+/// frontend::prompt(ctx, #{
+///     title: "Scale deployment",
+///     fields: [#{ name: "replicas", type: "number", label: "Replicas", value: 3, required: true }]
+/// })
+/// ```
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct PromptDefinition {
+    pub title: String,
+    #[serde(default)]
+    pub message: Option<String>,
+    pub fields: Vec<PromptField>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct PromptField {
+    pub name: String,
+    #[serde(rename = "type")]
+    pub kind: PromptFieldKind,
+    #[serde(default)]
+    pub label: Option<String>,
+    /// The initial value
+    #[serde(default)]
+    pub value: Option<Value>,
+    /// The choices of a `select` field
+    #[serde(default)]
+    pub options: Vec<String>,
+    #[serde(default)]
+    pub required: bool,
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum PromptFieldKind {
+    Text,
+    Number,
+    Select,
+    Checkbox,
+}
+
+#[derive(Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+struct FrontendPrompt<'a> {
+    prompt_id: PromptId,
+    tab_id: &'a str,
+    #[serde(flatten)]
+    definition: &'a PromptDefinition,
+}
+
+#[derive(Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+struct FrontendPromptExpired<'a> {
+    prompt_id: PromptId,
+    tab_id: &'a str,
+}
+
+impl PromptDefinition {
+    pub fn parse(definition: rhai::Map) -> EvalResult<Self> {
+        let definition: Self = rhai::serde::from_dynamic(&definition.into())
+            .map_err(|e| format!("frontend::prompt: invalid prompt: {e}"))?;
+
+        definition.validate()?;
+
+        Ok(definition)
+    }
+
+    fn validate(&self) -> EvalResult<()> {
+        if self.fields.is_empty() {
+            return Err("frontend::prompt: a prompt needs at least one field".into());
+        }
+
+        let mut names = HashSet::new();
+
+        for field in &self.fields {
+            if !names.insert(&field.name) {
+                return Err(format!("frontend::prompt: duplicate field `{}`", field.name).into());
+            }
+
+            if field.kind == PromptFieldKind::Select && field.options.is_empty() {
+                return Err(format!(
+                    "frontend::prompt: select field `{}` needs `options`",
+                    field.name
+                )
+                .into());
+            }
+
+            if let Some(value) = &field.value {
+                field.check(value)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Checks the values the user submitted and turns them into the map the script receives.
+    ///
+    /// Optional fields the user left empty are `()`.
+    pub fn read_answer(&self, mut values: serde_json::Map<String, Value>) -> EvalResult<rhai::Map> {
+        self.fields
+            .iter()
+            .map(|field| {
+                let value = match values.remove(&field.name) {
+                    None | Some(Value::Null) if field.required => {
+                        return Err(
+                            format!("frontend::prompt: `{}` is required", field.name).into()
+                        );
+                    }
+                    None | Some(Value::Null) => Dynamic::UNIT,
+                    Some(value) => {
+                        field.check(&value)?;
+                        rhai::serde::to_dynamic(value)?
+                    }
+                };
+
+                Ok((field.name.as_str().into(), value))
+            })
+            .collect()
+    }
+}
+
+impl PromptField {
+    fn check(&self, value: &Value) -> EvalResult<()> {
+        let valid = match self.kind {
+            PromptFieldKind::Text => value.is_string(),
+            PromptFieldKind::Number => value.is_number(),
+            PromptFieldKind::Checkbox => value.is_boolean(),
+            PromptFieldKind::Select => value
+                .as_str()
+                .is_some_and(|value| self.options.iter().any(|option| option == value)),
+        };
+
+        if valid {
+            return Ok(());
+        }
+
+        Err(format!(
+            "frontend::prompt: invalid value for {:?} field `{}`: {value}",
+            self.kind, self.name
+        )
+        .into())
+    }
+}
+
+/// Shows a prompt in the tab that opened the menu and blocks until the user answered it.
+///
+/// Returns the submitted values, or `()` if the user cancelled the prompt.
+pub fn ask(
+    app: &tauri::AppHandle,
+    tab_id: &str,
+    definition: &PromptDefinition,
+) -> EvalResult<Dynamic> {
+    let prompts = app.state::<MenuPrompts>();
+    let (prompt_id, answers) = prompts.register();
+
+    app.emit(
+        "hyprkube:menu:prompt",
+        FrontendPrompt {
+            prompt_id,
+            tab_id,
+            definition,
+        },
+    )
+    .map_err(|e| e.to_string())?;
+
+    match wait_for_answer(&answers, limits::PROMPT_TIME_LIMIT) {
+        Some(PromptAnswer::Submitted(values)) => definition.read_answer(values).map(Dynamic::from),
+        Some(PromptAnswer::Cancelled) => Ok(Dynamic::UNIT),
+        None => {
+            prompts.forget(&prompt_id);
+
+            let _ = app.emit(
+                "hyprkube:menu:prompt_expired",
+                FrontendPromptExpired { prompt_id, tab_id },
+            );
+
+            Err(format!(
+                "frontend::prompt: no answer within {}s",
+                limits::PROMPT_TIME_LIMIT.as_secs()
+            )
+            .into())
+        }
+    }
+}
+
+/// Waits for the answer without counting the time against the limit of the menu action.
+fn wait_for_answer(answers: &Receiver<PromptAnswer>, timeout: Duration) -> Option<PromptAnswer> {
+    limits::paused(|| answers.recv_timeout(timeout).ok())
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn scale_prompt() -> PromptDefinition {
+        let definition = rhai::Engine::new()
+            .eval::<rhai::Map>(
+                r#"#{
+                    title: "Scale",
+                    fields: [
+                        #{ name: "replicas", type: "number", value: 3, required: true },
+                        #{ name: "reason", type: "text" },
+                        #{ name: "strategy", type: "select", options: ["Recreate", "RollingUpdate"] },
+                        #{ name: "wait", type: "checkbox", value: false }
+                    ]
+                }"#,
+            )
+            .unwrap();
+
+        PromptDefinition::parse(definition).unwrap()
+    }
+
+    fn values(value: Value) -> serde_json::Map<String, Value> {
+        value.as_object().unwrap().clone()
+    }
+
+    #[test]
+    pub fn test_read_answer() {
+        let answer = scale_prompt()
+            .read_answer(values(
+                json!({ "replicas": 5, "strategy": "Recreate", "wait": true }),
+            ))
+            .unwrap();
+
+        assert_eq!(5, answer["replicas"].as_int().unwrap());
+        assert!(answer["reason"].is_unit());
+        assert_eq!("Recreate", answer["strategy"].to_string());
+        assert!(answer["wait"].as_bool().unwrap());
+    }
+
+    #[test]
+    pub fn test_err_on_invalid_answer() {
+        let prompt = scale_prompt();
+
+        let missing = prompt.read_answer(values(json!({ "reason": "x" })));
+        let wrong_type = prompt.read_answer(values(json!({ "replicas": "five" })));
+        let unknown_option =
+            prompt.read_answer(values(json!({ "replicas": 1, "strategy": "Blue" })));
+
+        assert!(missing
+            .unwrap_err()
+            .to_string()
+            .contains("`replicas` is required"));
+        assert!(wrong_type
+            .unwrap_err()
+            .to_string()
+            .contains("invalid value"));
+        assert!(unknown_option
+            .unwrap_err()
+            .to_string()
+            .contains("`strategy`"));
+    }
+
+    #[test]
+    pub fn test_err_on_invalid_definition() {
+        let engine = rhai::Engine::new();
+        let parse = |script: &str| PromptDefinition::parse(engine.eval(script).unwrap());
+
+        assert!(parse(r#"#{ title: "t", fields: [] }"#).is_err());
+        assert!(parse(r#"#{ title: "t", fields: [#{ name: "a", type: "slider" }] }"#).is_err());
+        assert!(parse(r#"#{ title: "t", fields: [#{ name: "a", type: "select" }] }"#).is_err());
+        assert!(parse(
+            r#"#{ title: "t", fields: [#{ name: "a", type: "text" }, #{ name: "a", type: "text" }] }"#
+        )
+        .is_err());
+        assert!(
+            parse(r#"#{ title: "t", fields: [#{ name: "a", type: "number", value: "x" }] }"#)
+                .is_err()
+        );
+    }
+
+    #[test]
+    pub fn test_answer_round_trip() {
+        let prompts = std::sync::Arc::new(MenuPrompts::default());
+        let (prompt_id, answers) = prompts.register();
+
+        let frontend = {
+            let prompts = std::sync::Arc::clone(&prompts);
+            std::thread::spawn(move || prompts.answer(&prompt_id, PromptAnswer::Cancelled))
+        };
+
+        assert_eq!(
+            Some(PromptAnswer::Cancelled),
+            wait_for_answer(&answers, Duration::from_secs(5))
+        );
+        assert!(frontend.join().unwrap().is_ok());
+        assert!(
+            prompts.answer(&prompt_id, PromptAnswer::Cancelled).is_err(),
+            "answered only once"
+        );
+    }
+
+    #[test]
+    pub fn test_unanswered_prompt_times_out() {
+        let prompts = MenuPrompts::default();
+        let (_, answers) = prompts.register();
+
+        assert_eq!(None, wait_for_answer(&answers, Duration::from_millis(10)));
+    }
+}
@@ -1,14 +1,12 @@
-use std::sync::Arc;
-
 use kube::api::GroupVersionKind;
 
-use crate::scripting::types::resource_context_menus::MenuItem;
+use crate::scripting::{limits, types::resource_context_menus::MenuItem};
 
 pub struct ContextMenuSection {
     pub title: Option<String>,
     pub matcher: Option<rhai::FnPtr>,
-    pub items: rhai::FnPtr,
-    pub ast: Arc<rhai::AST>,
+    pub items: Option<rhai::FnPtr>,
+    pub multi_items: Option<rhai::FnPtr>,
 }
 
 impl ContextMenuSection {
@@ -22,7 +20,11 @@ impl ContextMenuSection {
     ) -> Result<bool, Box<rhai::EvalAltResult>> {
         self.matcher
             .as_ref()
-            .map(|matcher| matcher.call::<bool>(engine, ast, (gvk.group, gvk.version, gvk.kind)))
+            .map(|matcher| {
+                limits::with_time_limit(limits::MATCHER_TIME_LIMIT, || {
+                    matcher.call::<bool>(engine, ast, (gvk.group, gvk.version, gvk.kind))
+                })
+            })
             .unwrap_or(Ok(true))
     }
 
@@ -35,9 +37,38 @@ impl ContextMenuSection {
         ast: &rhai::AST,
         obj: rhai::Dynamic,
     ) -> Result<Vec<MenuItem>, Box<rhai::EvalAltResult>> {
-        Ok(self
-            .items
-            .call::<rhai::Array>(engine, ast, (obj.clone(),))?
+        let Some(items) = &self.items else {
+            return Ok(Vec::new());
+        };
+
+        let items = limits::with_time_limit(limits::ITEMS_TIME_LIMIT, || {
+            items.call::<rhai::Array>(engine, ast, (obj.clone(),))
+        })?;
+
+        Ok(Self::into_menu_items(items))
+    }
+
+    /// Calls the `multi_items` function defined in the script with all selected Kubernetes objects. The actions of
+    /// these items are called once per object, which is passed as their second argument.
+    pub fn render_multi_items_for(
+        &self,
+        engine: &rhai::Engine,
+        ast: &rhai::AST,
+        objs: rhai::Array,
+    ) -> Result<Vec<MenuItem>, Box<rhai::EvalAltResult>> {
+        let Some(multi_items) = &self.multi_items else {
+            return Ok(Vec::new());
+        };
+
+        let items = limits::with_time_limit(limits::ITEMS_TIME_LIMIT, || {
+            multi_items.call::<rhai::Array>(engine, ast, (objs.clone(),))
+        })?;
+
+        Ok(Self::into_menu_items(items))
+    }
+
+    fn into_menu_items(items: rhai::Array) -> Vec<MenuItem> {
+        items
             .into_iter()
             .filter(|i| !i.is_unit())
             .flat_map(|dynamic| {
@@ -51,6 +82,6 @@ impl ContextMenuSection {
 
                 something
             })
-            .collect())
+            .collect()
     }
 }
@@ -2,6 +2,8 @@ use std::collections::HashMap;
 
 use serde::Serialize;
 
+use crate::scripting::diagnostics::ScriptDiagnostic;
+
 #[derive(Debug, Clone, Serialize)]
 #[allow(unused)]
 pub enum FrontendMenuItemKind {
@@ -53,3 +55,115 @@ impl MenuBlueprint {
         Self { id, items }
     }
 }
+
+/// How a menu action ended, so that the frontend can tell the user.
+#[derive(Debug, Clone, Serialize, PartialEq)]
+#[serde(rename_all = "camelCase", tag = "status")]
+pub enum ActionOutcome {
+    Ok,
+    #[serde(rename_all = "camelCase")]
+    Error {
+        message: String,
+        script: Option<String>,
+        line: Option<usize>,
+        column: Option<usize>,
+    },
+    /// An action of a multi-selection menu, which has been called once per selected resource
+    Batch {
+        results: Vec<BatchItemOutcome>,
+    },
+}
+
+/// How a batch action ended for one of the selected resources.
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct BatchItemOutcome {
+    pub namespace: Option<String>,
+    pub name: String,
+    #[serde(flatten)]
+    pub outcome: ActionOutcome,
+}
+
+impl From<&ScriptDiagnostic> for ActionOutcome {
+    fn from(diagnostic: &ScriptDiagnostic) -> Self {
+        Self::Error {
+            message: diagnostic.message.clone(),
+            script: diagnostic.script.clone(),
+            line: diagnostic.line,
+            column: diagnostic.column,
+        }
+    }
+}
+
+/// Sent to the tab that opened the menu once an action returned.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FrontendActionFinished<'a> {
+    pub tab_id: &'a str,
+    pub outcome: &'a ActionOutcome,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scripting::diagnostics::DiagnosticKind;
+
+    #[test]
+    pub fn test_error_outcome_points_at_script() {
+        let engine = rhai::Engine::new();
+        let error = engine
+            .run(
+                r#"let x = 1;
+throw "boom";"#,
+            )
+            .unwrap_err();
+
+        let diagnostic =
+            ScriptDiagnostic::from_eval_error(DiagnosticKind::Action, &error, Some("restart.rhai"));
+
+        let outcome = serde_json::to_value(ActionOutcome::from(&diagnostic)).unwrap();
+
+        assert_eq!("error", outcome["status"]);
+        assert!(outcome["message"].as_str().unwrap().contains("boom"));
+        assert_eq!("restart.rhai", outcome["script"]);
+        assert_eq!(2, outcome["line"]);
+        assert_eq!(1, outcome["column"]);
+        assert_eq!(
+            serde_json::json!({ "status": "ok" }),
+            serde_json::to_value(ActionOutcome::Ok).unwrap()
+        );
+    }
+
+    #[test]
+    pub fn test_batch_outcome_lists_every_resource() {
+        let outcome = ActionOutcome::Batch {
+            results: vec![
+                BatchItemOutcome {
+                    namespace: Some("default".into()),
+                    name: "web".into(),
+                    outcome: ActionOutcome::Ok,
+                },
+                BatchItemOutcome {
+                    namespace: None,
+                    name: "node-1".into(),
+                    outcome: ActionOutcome::Error {
+                        message: "forbidden".into(),
+                        script: None,
+                        line: None,
+                        column: None,
+                    },
+                },
+            ],
+        };
+
+        assert_eq!(
+            serde_json::json!({
+                "status": "batch",
+                "results": [
+                    { "namespace": "default", "name": "web", "status": "ok" },
+                    { "namespace": null, "name": "node-1", "status": "error", "message": "forbidden", "script": null, "line": null, "column": null },
+                ]
+            }),
+            serde_json::to_value(outcome).unwrap()
+        );
+    }
+}
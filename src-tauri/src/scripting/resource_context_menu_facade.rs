@@ -1,20 +1,29 @@
 use std::{
     collections::HashMap,
-    path::PathBuf,
+    path::Path,
     sync::{Arc, RwLock, Weak},
 };
 
-use kube::core::gvk::ParseGroupVersionError;
+use kube::{api::GroupVersionKind, core::gvk::ParseGroupVersionError};
 use rhai::exported_module;
 
 use crate::{
+    cluster_profiles::ClusterProfileId,
     internal::{gvk_extraction::GvkExtraction, mini_id::random_id},
     scripting::{
-        modules,
-        resource_context_menu::{ContextMenuSection, FrontendMenuSection, MenuBlueprint},
-        scripts_provider::{self, ScriptType, ScriptsProvider},
+        capabilities::Capabilities,
+        diagnostics::{DiagnosticKind, ScriptDiagnostic, ScriptDiagnostics},
+        environment::ScriptEnvironment,
+        host::{self, ScriptHost},
+        limits, modules,
+        resource_context_menu::{
+            ActionOutcome, BatchItemOutcome, ContextMenuSection, FrontendActionFinished,
+            FrontendMenuSection, MenuBlueprint,
+        },
+        script_registry::{self, Registered, ScriptRegistry},
+        scripts_provider::{ScriptType, ScriptsProvider},
         types::{
-            commons::{CallbackContext, ContentScript, FnPtrWithAst},
+            commons::{CallbackContext, FnPtrWithAst},
             resource_context_menus::{
                 ActionButton, MenuItem, MenuSection, ResourceSubMenu, SubMenu,
             },
@@ -24,23 +33,38 @@ use crate::{
 };
 
 pub struct ResourceContextMenuFacade {
-    app: tauri::AppHandle,
-    engine: rhai::Engine,
-    registered_sections: RwLock<Vec<ContextMenuSection>>,
-    scripts: RwLock<HashMap<PathBuf, ContentScript>>,
+    host: Arc<dyn ScriptHost>,
+    registry: ScriptRegistry<ContextMenuSection>,
     menu_stacks: RwLock<HashMap<String, MenuStack>>,
     // RwLock (not OnceLock) so a later `set_discovery` — e.g. on reconnect/refresh — replaces
     // the cache instead of being silently dropped.
     kube_discovery: RwLock<Option<Arc<kube::Discovery>>>,
+    diagnostics: Arc<ScriptDiagnostics>,
+}
+
+#[derive(Clone)]
+struct MenuAction {
+    callback: FnPtrWithAst,
+    engine: Arc<rhai::Engine>,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Clone)]
 struct MenuStack {
     context: Arc<CallbackContext>,
-    actions: HashMap<String, FnPtrWithAst>,
+    actions: HashMap<String, MenuAction>,
 
     /// List of children menu IDs to drop together with this stack
     children: Vec<String>,
+
+    /// The selected resources if this is the menu of a multi-selection, its actions are called once per resource
+    batch: Option<Arc<Vec<BatchItem>>>,
+}
+
+struct BatchItem {
+    namespace: Option<String>,
+    name: String,
+    uid: Option<String>,
+    obj: rhai::Dynamic,
 }
 
 impl MenuStack {
@@ -49,19 +73,42 @@ impl MenuStack {
             context: Arc::new(context),
             actions: HashMap::new(),
             children: Vec::new(),
+            batch: None,
         }
     }
 }
 
 impl ResourceContextMenuFacade {
-    pub fn new(app: tauri::AppHandle, client: kube::Client) -> Arc<Self> {
+    pub fn new(
+        host: Arc<dyn ScriptHost>,
+        client: kube::Client,
+        diagnostics: Arc<ScriptDiagnostics>,
+        environment: ScriptEnvironment,
+    ) -> Arc<Self> {
         Arc::new_cyclic(|weak| Self {
-            app: app.clone(),
-            engine: Self::make_resource_contextmenu_engine(weak.clone(), app, client),
-            registered_sections: RwLock::new(Vec::new()),
-            scripts: RwLock::new(HashMap::new()),
+            host,
+            registry: ScriptRegistry::new(
+                ScriptType::Menu,
+                environment.clone(),
+                Arc::clone(&diagnostics),
+                {
+                    let facade = weak.clone();
+                    let diagnostics = Arc::clone(&diagnostics);
+
+                    move |capabilities| {
+                        Self::make_resource_contextmenu_engine(
+                            facade.clone(),
+                            client.clone(),
+                            &diagnostics,
+                            &environment,
+                            capabilities,
+                        )
+                    }
+                },
+            ),
             menu_stacks: RwLock::new(HashMap::new()),
             kube_discovery: RwLock::new(None),
+            diagnostics,
         })
     }
 
@@ -69,12 +116,17 @@ impl ResourceContextMenuFacade {
         *self.kube_discovery.write().unwrap() = Some(discovery);
     }
 
+    /// Builds an engine that only permits what `capabilities` grants. The modules of all capabilities are
+    /// registered regardless, but the functions that have not been granted fail with an explanation.
     fn make_resource_contextmenu_engine(
         facade: Weak<Self>,
-        app: tauri::AppHandle,
         client: kube::Client,
+        diagnostics: &Arc<ScriptDiagnostics>,
+        environment: &ScriptEnvironment,
+        capabilities: &Capabilities,
     ) -> rhai::Engine {
         let mut engine = rhai::Engine::new();
+        diagnostics.capture_output(&mut engine);
 
         engine.build_type::<ResourceRef>();
         engine.build_type::<ResourceKind>();
@@ -86,106 +138,159 @@ impl ResourceContextMenuFacade {
         let kube_facade = facade.clone();
         engine.register_static_module(
             "kube",
-            modules::kube::build_module(client, move || {
-                kube_facade
-                    .upgrade()
-                    .expect("facade dropped")
-                    .kube_discovery
-                    .read()
-                    .unwrap()
-                    .clone()
-            })
+            modules::kube::build_module(
+                client,
+                move || {
+                    kube_facade
+                        .upgrade()
+                        .expect("facade dropped")
+                        .kube_discovery
+                        .read()
+                        .unwrap()
+                        .clone()
+                },
+                capabilities,
+            )
             .into(),
         );
-        engine.register_static_module("clipboard", modules::clipboard::build_module(app).into());
+        engine.register_static_module(
+            "clipboard",
+            modules::clipboard::build_module(capabilities).into(),
+        );
         engine.register_static_module("base64", exported_module!(modules::base64_rhai).into());
-        engine.register_static_module("frontend", exported_module!(modules::frontend_rhai).into());
+        environment.apply(&mut engine);
+        engine.register_static_module(
+            "frontend",
+            modules::frontend::build_module(capabilities).into(),
+        );
 
         {
             engine.register_fn(
                 "register_resource_contextmenu_section",
-                move |ctx: rhai::NativeCallContext,
-                      definition: MenuSection|
-                      -> Result<(), Box<rhai::EvalAltResult>> {
+                move |section: MenuSection| {
                     let facade = facade.upgrade().expect("facade dropped");
-                    let script = ctx
-                        .call_source()
-                        .ok_or("only file-based scripts supported")?;
 
-                    facade
-                        .register_resource_contextmenu_section(definition, script)
-                        .map_err(|e| e.to_string().into())
+                    facade.registry.stage(ContextMenuSection {
+                        title: section.title,
+                        matcher: section.matcher,
+                        items: section.items,
+                        multi_items: section.multi_items,
+                    });
                 },
             );
         }
 
-        engine.set_max_expr_depths(64, 32);
+        limits::apply(&mut engine);
 
         engine
     }
 
-    fn register_resource_contextmenu_section(
+    pub fn create_resource_menustack(
         &self,
-        section: MenuSection,
-        script: &str,
-    ) -> Result<(), ResourceContextMenuError> {
-        let script: PathBuf = script.into();
-
-        let ast = self.scripts.read().unwrap();
-        let ast = ast
-            .get(&script)
-            .unwrap()
-            .ast
-            .as_ref()
-            .ok_or(ResourceContextMenuError::PendingCompilation)?
-            .as_ref()
-            .map_err(|_| ResourceContextMenuError::CompilationError)?;
-
-        let mut sections = self.registered_sections.write().unwrap();
-
-        sections.push(ContextMenuSection {
-            title: section.title,
-            matcher: section.matcher,
-            items: section.items,
-            ast: Arc::clone(ast),
-        });
+        parent_menu: Option<&str>,
+        obj: kube::api::DynamicObject,
+        tab_id: &str,
+    ) -> Result<MenuBlueprint, ResourceContextMenuError> {
+        let gvk = Self::gvk_of(&obj)?;
+        let obj = rhai::serde::to_dynamic(obj)?;
 
-        Ok(())
+        let menu_stack = MenuStack::new(CallbackContext::new(
+            Arc::clone(&self.host),
+            tab_id.to_owned(),
+        ));
+
+        Ok(
+            self.build_menustack(parent_menu, &gvk, menu_stack, |section| {
+                section
+                    .definition
+                    .render_items_for(&section.engine, &section.ast, obj.clone())
+            }),
+        )
     }
 
-    pub fn create_resource_menustack(
+    /// Builds the menu of a selection of resources, which must all be of the same kind. Only sections with
+    /// `multi_items` contribute to it.
+    pub fn create_multi_resource_menustack(
         &self,
-        parent_menu: Option<&str>,
-        obj: kube::api::DynamicObject,
+        objs: Vec<kube::api::DynamicObject>,
         tab_id: &str,
     ) -> Result<MenuBlueprint, ResourceContextMenuError> {
-        let gvk = obj
-            .types
+        let gvk = Self::gvk_of(
+            objs.first()
+                .ok_or(ResourceContextMenuError::EmptySelection)?,
+        )?;
+
+        let mut batch = Vec::with_capacity(objs.len());
+        for obj in objs {
+            if Self::gvk_of(&obj)? != gvk {
+                return Err(ResourceContextMenuError::MixedSelection);
+            }
+
+            batch.push(BatchItem {
+                namespace: obj.metadata.namespace.clone(),
+                name: obj.metadata.name.clone().unwrap_or_default(),
+                uid: obj.metadata.uid.clone(),
+                obj: rhai::serde::to_dynamic(obj)?,
+            });
+        }
+
+        let objs: rhai::Array = batch.iter().map(|item| item.obj.clone()).collect();
+
+        let mut menu_stack = MenuStack::new(CallbackContext::new(
+            Arc::clone(&self.host),
+            tab_id.to_owned(),
+        ));
+        menu_stack.batch = Some(Arc::new(batch));
+
+        Ok(self.build_menustack(None, &gvk, menu_stack, |section| {
+            section
+                .definition
+                .render_multi_items_for(&section.engine, &section.ast, objs.clone())
+        }))
+    }
+
+    fn gvk_of(
+        obj: &kube::api::DynamicObject,
+    ) -> Result<GroupVersionKind, ResourceContextMenuError> {
+        obj.types
             .as_ref()
             .unwrap()
             .try_gvk()
-            .map_err(ResourceContextMenuError::InvalidKubeApiVersion)?;
-        let obj = rhai::serde::to_dynamic(obj)?;
-
-        let mut menu_stack =
-            MenuStack::new(CallbackContext::new(self.app.clone(), tab_id.to_owned()));
+            .map_err(ResourceContextMenuError::InvalidKubeApiVersion)
+    }
 
+    /// Renders the items of all sections that match `gvk` and stores the menu stack, so that its actions can be
+    /// called. Sections that fail are left out.
+    fn build_menustack(
+        &self,
+        parent_menu: Option<&str>,
+        gvk: &GroupVersionKind,
+        mut menu_stack: MenuStack,
+        render_items: impl Fn(
+            &Registered<ContextMenuSection>,
+        ) -> Result<Vec<MenuItem>, Box<rhai::EvalAltResult>>,
+    ) -> MenuBlueprint {
         let frontend_sections: Vec<Result<FrontendMenuSection, ResourceContextMenuError>> = {
-            let section_templates = self.registered_sections.read().unwrap();
+            let section_templates = self.registry.registered();
             section_templates
                 .iter()
                 .map(|section_template| {
                     let matches = section_template
-                        .matches_gvk(&self.engine, &section_template.ast, gvk.clone())
-                        .map_err(ResourceContextMenuError::Matcher)?;
+                        .definition
+                        .matches_gvk(&section_template.engine, &section_template.ast, gvk.clone())
+                        .map_err(|e| {
+                            self.report(DiagnosticKind::Matcher, &e, &section_template.ast);
+                            ResourceContextMenuError::Matcher(e)
+                        })?;
 
                     if !matches {
                         return Ok(None);
                     }
 
-                    let items: Vec<MenuItem> = section_template
-                        .render_items_for(&self.engine, &section_template.ast, obj.clone())
-                        .map_err(ResourceContextMenuError::Items)?;
+                    let items: Vec<MenuItem> = render_items(section_template).map_err(|e| {
+                        self.report(DiagnosticKind::Evaluation, &e, &section_template.ast);
+                        ResourceContextMenuError::Items(e)
+                    })?;
 
                     let frontend_items = items
                         .into_iter()
@@ -193,17 +298,26 @@ impl ResourceContextMenuFacade {
                             let (item, actions) = MenuItem::transform_for_frontend(item);
 
                             for (action_id, action) in actions {
-                                menu_stack.actions.entry(action_id).insert_entry(
-                                    FnPtrWithAst::new(action, Arc::clone(&section_template.ast)),
-                                );
+                                menu_stack
+                                    .actions
+                                    .entry(action_id)
+                                    .insert_entry(MenuAction {
+                                        callback: FnPtrWithAst::new(
+                                            action,
+                                            Arc::clone(&section_template.ast),
+                                        ),
+                                        engine: Arc::clone(&section_template.engine),
+                                    });
                             }
 
                             item
                         })
                         .collect();
 
-                    let section_items =
-                        FrontendMenuSection::new(section_template.title.clone(), frontend_items);
+                    let section_items = FrontendMenuSection::new(
+                        section_template.definition.title.clone(),
+                        frontend_items,
+                    );
 
                     Ok(Some(section_items))
                 })
@@ -241,10 +355,7 @@ impl ResourceContextMenuFacade {
             tracing::warn!("Error building MenuBlueprint: {err}");
         }
 
-        Ok(MenuBlueprint::new(
-            menu_stack_id,
-            oks.into_iter().map(Result::unwrap).collect(),
-        ))
+        MenuBlueprint::new(menu_stack_id, oks.into_iter().map(Result::unwrap).collect())
     }
 
     pub fn drop_resource_menustack(&self, id: &str) -> Result<(), ResourceContextMenuError> {
@@ -263,57 +374,121 @@ impl ResourceContextMenuFacade {
         &self,
         menu_id: &str,
         action_ref: &str,
-    ) -> Result<(), ResourceContextMenuError> {
-        let menus = self.menu_stacks.read().unwrap();
+    ) -> Result<ActionOutcome, ResourceContextMenuError> {
+        // The action may open or drop menus itself, e.g. through `frontend`, so the menus must not stay locked
+        // while it runs.
+        let (ctx, action, batch) = {
+            let menus = self.menu_stacks.read().unwrap();
+
+            let menu = menus
+                .get(menu_id)
+                .ok_or_else(|| ResourceContextMenuError::NoSuchMenuStack(menu_id.to_owned()))?;
+
+            let action = menu
+                .actions
+                .get(action_ref)
+                .ok_or_else(|| ResourceContextMenuError::NoSuchMenuAction(action_ref.to_owned()))?;
+
+            (
+                Arc::clone(&menu.context),
+                action.clone(),
+                menu.batch.clone(),
+            )
+        };
+        let frontend_ctx = Arc::clone(&ctx);
 
-        let menu = menus
-            .get(menu_id)
-            .ok_or_else(|| ResourceContextMenuError::NoSuchMenuStack(menu_id.to_owned()))?;
+        let FnPtrWithAst { fnptr, ast } = &action.callback;
 
-        let action = menu
-            .actions
-            .get(action_ref)
-            .ok_or_else(|| ResourceContextMenuError::NoSuchMenuAction(action_ref.to_owned()))?;
+        let outcome = match &batch {
+            None => {
+                let result = limits::with_time_limit(limits::ACTION_TIME_LIMIT, || {
+                    fnptr.call::<()>(&action.engine, ast, (ctx,))
+                });
 
-        let ctx = Arc::clone(&menu.context);
+                self.outcome_of(result, ast, None)
+            }
+            // One failing resource does not stop the others, each one ends up in the outcome
+            Some(batch) => ActionOutcome::Batch {
+                results: batch
+                    .iter()
+                    .map(|item| {
+                        let result = limits::with_time_limit(limits::ACTION_TIME_LIMIT, || {
+                            fnptr.call::<()>(
+                                &action.engine,
+                                ast,
+                                (Arc::clone(&ctx), item.obj.clone()),
+                            )
+                        });
+
+                        BatchItemOutcome {
+                            namespace: item.namespace.clone(),
+                            name: item.name.clone(),
+                            outcome: self.outcome_of(result, ast, item.uid.clone()),
+                        }
+                    })
+                    .collect(),
+            },
+        };
 
-        action.fnptr.call::<()>(&self.engine, &action.ast, (ctx,))?;
+        // Lets the tab hide the progress of the action and show how it ended
+        let _ = host::emit(
+            &*self.host,
+            "hyprkube:menu:action_finished",
+            FrontendActionFinished {
+                tab_id: &frontend_ctx.frontend_tab,
+                outcome: &outcome,
+            },
+        );
 
-        Ok(())
+        Ok(outcome)
     }
 
-    pub fn evaluate(
+    /// A failing script is an outcome for the user to see, not a failure of the call.
+    fn outcome_of(
         &self,
-        scripts_provider: &ScriptsProvider,
-    ) -> Result<(), ResourceContextMenuError> {
-        let menu_scripts = scripts_provider
-            .get_scripts_for_type(ScriptType::Menu)
-            .unwrap();
-
-        for entrypoint in &menu_scripts {
-            tracing::info!("Evaluating {}", entrypoint.to_string_lossy());
-
-            let ast_arc = {
-                let mut scripts = self.scripts.write().unwrap();
-
-                let script = scripts
-                    .entry(entrypoint.to_owned())
-                    .or_insert(ContentScript::new());
+        result: Result<(), Box<rhai::EvalAltResult>>,
+        ast: &rhai::AST,
+        resource_uid: Option<String>,
+    ) -> ActionOutcome {
+        match result {
+            Ok(()) => ActionOutcome::Ok,
+            Err(e) => {
+                let diagnostic =
+                    ScriptDiagnostic::from_eval_error(DiagnosticKind::Action, &e, ast.source())
+                        .with_resource_uid(resource_uid);
+                let outcome = ActionOutcome::from(&diagnostic);
+
+                self.diagnostics.report(diagnostic);
+
+                outcome
+            }
+        }
+    }
 
-                let ast_result = script.ast.get_or_insert_with(|| {
-                    self.engine.compile_file(entrypoint.clone()).map(Arc::new)
-                });
+    fn report(&self, kind: DiagnosticKind, error: &rhai::EvalAltResult, ast: &rhai::AST) {
+        self.diagnostics
+            .report(ScriptDiagnostic::from_eval_error(kind, error, ast.source()));
+    }
 
-                ast_result
-                    .as_ref()
-                    .map_err(|_| ResourceContextMenuError::CompilationError)?
-                    .clone()
-            };
+    pub fn evaluate(
+        &self,
+        scripts_provider: &ScriptsProvider,
+        profile: &ClusterProfileId,
+    ) -> Result<(), script_registry::Error> {
+        self.registry.evaluate(scripts_provider, profile)
+    }
 
-            self.engine.eval_ast::<()>(&ast_arc)?;
-        }
+    /// (Re-)compiles and evaluates a single script and replaces all sections it had registered before.
+    ///
+    /// If the script fails to compile or to evaluate, its previous sections stay in place. Menus that are
+    /// already open keep calling the actions of the version they were created from.
+    pub fn load_script(&self, entrypoint: &Path) -> Result<(), script_registry::Error> {
+        self.registry.load_script(entrypoint)
+    }
 
-        Ok(())
+    /// Removes all sections of a script that no longer exists.
+    pub fn unload_script(&self, entrypoint: &Path) {
+        self.registry.unload_script(entrypoint)
     }
 }
 
@@ -331,18 +506,232 @@ pub enum ResourceContextMenuError {
     #[error("Error evaluating script: {0}")]
     EvaluationResult(#[from] Box<rhai::EvalAltResult>),
 
-    #[error("The script has not yet been compiled")]
-    PendingCompilation,
-
-    #[error("The script has a compilation error")]
-    CompilationError,
-
-    #[error(transparent)]
-    ScriptDirectoryResolution(#[from] scripts_provider::Error),
-
     #[error("Call to matcher failed: {0}")]
     Matcher(Box<rhai::EvalAltResult>),
 
     #[error("Call to items failed: {0}")]
     Items(Box<rhai::EvalAltResult>),
+
+    #[error("No resources selected")]
+    EmptySelection,
+
+    #[error("The selected resources must all be of the same kind")]
+    MixedSelection,
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{sync::OnceLock, time::Duration};
+
+    use rhai::{Dynamic, EvalAltResult};
+    use serde_json::{json, Value};
+
+    use super::*;
+    use crate::scripting::{
+        harness::{FakeCluster, RecordingHost},
+        prompts::PromptDefinition,
+        test_fixtures::TempPackages,
+    };
+
+    struct LoadedMenus {
+        facade: Arc<ResourceContextMenuFacade>,
+        _packages: TempPackages,
+        _runtime: tokio::runtime::Runtime,
+    }
+
+    /// Loads `script` as a menu script of the builtin core package, which is granted all capabilities.
+    fn load(host: Arc<dyn ScriptHost>, script: &str) -> LoadedMenus {
+        let runtime = tokio::runtime::Runtime::new().unwrap();
+        let packages = TempPackages::new();
+        let entrypoint = packages.write("core/menus/test.rhai", script);
+
+        let client = {
+            let _guard = runtime.enter();
+            FakeCluster::new(Vec::new()).client()
+        };
+
+        let facade = ResourceContextMenuFacade::new(
+            host,
+            client,
+            ScriptDiagnostics::new(),
+            ScriptEnvironment {
+                builtin_packages: Some(packages.root().to_owned()),
+                ..ScriptEnvironment::default()
+            },
+        );
+        facade.load_script(&entrypoint).unwrap();
+
+        LoadedMenus {
+            facade,
+            _packages: packages,
+            _runtime: runtime,
+        }
+    }
+
+    /// The ID of an opened menu and the action ref of its first button.
+    fn first_action(blueprint: MenuBlueprint) -> (String, String) {
+        let blueprint = serde_json::to_value(blueprint).unwrap();
+
+        (
+            blueprint["id"].as_str().unwrap().to_owned(),
+            blueprint["items"][0]["items"][0]["data"]["actionRef"]
+                .as_str()
+                .unwrap()
+                .to_owned(),
+        )
+    }
+
+    fn pod(name: &str) -> kube::api::DynamicObject {
+        serde_json::from_value(json!({
+            "apiVersion": "v1",
+            "kind": "Pod",
+            "metadata": { "namespace": "default", "name": name, "uid": format!("uid-{name}") }
+        }))
+        .unwrap()
+    }
+
+    /// Closes the open menu while a prompt is shown, like a user who clicks elsewhere.
+    #[derive(Default)]
+    struct ClosingHost {
+        facade: OnceLock<Weak<ResourceContextMenuFacade>>,
+        menu_id: OnceLock<String>,
+    }
+
+    impl ScriptHost for ClosingHost {
+        fn emit(&self, _event: &str, _payload: Value) -> Result<(), String> {
+            Ok(())
+        }
+
+        fn write_clipboard(&self, _text: &str) -> Result<(), String> {
+            Ok(())
+        }
+
+        fn prompt(
+            &self,
+            _tab_id: &str,
+            _definition: &PromptDefinition,
+        ) -> Result<Dynamic, Box<EvalAltResult>> {
+            let facade = self.facade.get().unwrap().upgrade().unwrap();
+            let menu_id = self.menu_id.get().unwrap().clone();
+            let (dropped, drop_finished) = std::sync::mpsc::channel();
+
+            std::thread::spawn(move || {
+                facade.drop_resource_menustack(&menu_id).unwrap();
+                let _ = dropped.send(());
+            });
+
+            drop_finished
+                .recv_timeout(Duration::from_secs(5))
+                .map_err(|_| "the menus stayed locked while the action was running")?;
+
+            Ok(Dynamic::UNIT)
+        }
+    }
+
+    #[test]
+    pub fn test_menus_can_be_dropped_while_an_action_runs() {
+        let host = Arc::new(ClosingHost::default());
+        let menus = load(
+            Arc::clone(&host) as _,
+            r#"
+            register_resource_contextmenu_section(MenuSection(#{
+                items: |obj| [
+                    ActionButton(#{
+                        title: "Scale",
+                        action: |ctx| frontend::prompt(ctx, #{ title: "Scale", fields: [#{ name: "replicas", type: "number" }] })
+                    })
+                ]
+            }));
+            "#,
+        );
+        let _ = host.facade.set(Arc::downgrade(&menus.facade));
+
+        let (menu_id, action_ref) = first_action(
+            menus
+                .facade
+                .create_resource_menustack(None, pod("web"), "tab")
+                .unwrap(),
+        );
+        let _ = host.menu_id.set(menu_id.clone());
+
+        assert_eq!(
+            ActionOutcome::Ok,
+            menus
+                .facade
+                .call_menustack_action(&menu_id, &action_ref)
+                .unwrap()
+        );
+        assert!(matches!(
+            menus.facade.call_menustack_action(&menu_id, &action_ref),
+            Err(ResourceContextMenuError::NoSuchMenuStack(_))
+        ));
+    }
+
+    #[test]
+    pub fn test_batch_action_continues_after_a_failing_item() {
+        let host = Arc::new(RecordingHost::default());
+        let menus = load(
+            Arc::clone(&host) as _,
+            r#"
+            register_resource_contextmenu_section(MenuSection(#{
+                multi_items: |objs| [
+                    ActionButton(#{
+                        title: "Touch",
+                        action: |ctx, obj| {
+                            frontend::notify(ctx, "info", obj.metadata.name);
+
+                            if obj.metadata.name == "b" {
+                                throw "cannot touch b";
+                            }
+                        }
+                    })
+                ]
+            }));
+            "#,
+        );
+
+        let (menu_id, action_ref) = first_action(
+            menus
+                .facade
+                .create_multi_resource_menustack(vec![pod("a"), pod("b"), pod("c")], "tab")
+                .unwrap(),
+        );
+
+        let ActionOutcome::Batch { results } = menus
+            .facade
+            .call_menustack_action(&menu_id, &action_ref)
+            .unwrap()
+        else {
+            panic!("a multi-selection action must have a batch outcome");
+        };
+
+        let events = host.take_events();
+        let notified: Vec<_> = events
+            .iter()
+            .filter(|e| e.event == "hyprkube:menu:notify")
+            .map(|e| e.payload["message"].as_str().unwrap())
+            .collect();
+
+        assert_eq!(vec!["a", "b", "c"], notified);
+        assert_eq!(
+            1,
+            events
+                .iter()
+                .filter(|e| e.event == "hyprkube:menu:action_finished")
+                .count()
+        );
+
+        assert_eq!(
+            vec!["a", "b", "c"],
+            results.iter().map(|r| r.name.as_str()).collect::<Vec<_>>()
+        );
+        assert!(results
+            .iter()
+            .all(|r| r.namespace.as_deref() == Some("default")));
+        assert_eq!(ActionOutcome::Ok, results[0].outcome);
+        assert!(
+            matches!(&results[1].outcome, ActionOutcome::Error { message, .. } if message.contains("cannot touch b"))
+        );
+        assert_eq!(ActionOutcome::Ok, results[2].outcome);
+    }
 }
@@ -0,0 +1,391 @@
+use std::{
+    path::Path,
+    sync::{Arc, Weak},
+};
+
+use kube::api::{DynamicObject, GroupVersionKind};
+use serde::Serialize;
+
+use crate::{
+    cluster_metrics::ClusterMetrics,
+    cluster_profiles::ClusterProfileId,
+    related_resources::RelatedResources,
+    scripting::{
+        capabilities::Capabilities,
+        diagnostics::{DiagnosticKind, ScriptDiagnostic, ScriptDiagnostics},
+        environment::ScriptEnvironment,
+        limits, modules,
+        script_registry::{self, Registered, ScriptRegistry},
+        scripts_provider::{ScriptType, ScriptsProvider},
+        types::{
+            resource_details::{self, DetailSection, ResourceDetails, SectionComponent},
+            resource_presentations, ResourceRef,
+        },
+    },
+};
+
+/// The sections of one registered details panel, rendered for a single resource.
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RenderedDetails {
+    pub title: String,
+    pub sections: Vec<RenderedSection>,
+}
+
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RenderedSection {
+    pub title: String,
+    pub component: Option<SectionComponent>,
+    /// Why the section could not be rendered, the details are in the scripting console
+    pub error: Option<String>,
+}
+
+pub struct ResourceDetailsFacade {
+    registry: ScriptRegistry<ResourceDetails>,
+    diagnostics: Arc<ScriptDiagnostics>,
+}
+
+impl ResourceDetailsFacade {
+    pub fn new(
+        metrics: Arc<ClusterMetrics>,
+        related: Arc<RelatedResources>,
+        diagnostics: Arc<ScriptDiagnostics>,
+        environment: ScriptEnvironment,
+    ) -> Arc<Self> {
+        Arc::new_cyclic(|weak| Self {
+            registry: ScriptRegistry::new(
+                ScriptType::Details,
+                environment.clone(),
+                Arc::clone(&diagnostics),
+                {
+                    let facade = weak.clone();
+                    let diagnostics = Arc::clone(&diagnostics);
+
+                    move |capabilities| {
+                        Self::make_engine(
+                            facade.clone(),
+                            Arc::clone(&metrics),
+                            Arc::clone(&related),
+                            &diagnostics,
+                            &environment,
+                            capabilities,
+                        )
+                    }
+                },
+            ),
+            diagnostics,
+        })
+    }
+
+    fn make_engine(
+        facade: Weak<Self>,
+        metrics: Arc<ClusterMetrics>,
+        related: Arc<RelatedResources>,
+        diagnostics: &Arc<ScriptDiagnostics>,
+        environment: &ScriptEnvironment,
+        capabilities: &Capabilities,
+    ) -> rhai::Engine {
+        let mut engine = rhai::Engine::new();
+        diagnostics.capture_output(&mut engine);
+
+        engine.build_type::<ResourceRef>();
+        resource_presentations::register_components(&mut engine);
+        resource_details::register_types(&mut engine);
+
+        engine.register_static_module("metrics", modules::metrics::build_module(metrics).into());
+        environment.apply(&mut engine);
+        engine.register_static_module(
+            "related",
+            modules::related::build_module(related, capabilities).into(),
+        );
+
+        {
+            engine.register_fn(
+                "register_resource_details",
+                move |definition: ResourceDetails| {
+                    let facade = facade.upgrade().expect("facade dropped");
+
+                    facade.registry.stage(definition);
+                },
+            );
+        }
+
+        limits::apply(&mut engine);
+
+        engine
+    }
+
+    /// Renders all details panels that match the GVK of `obj`, in the order they were registered.
+    ///
+    /// A failing section does not affect the others, it is returned with an error instead.
+    pub fn render(
+        &self,
+        gvk: &GroupVersionKind,
+        obj: &DynamicObject,
+    ) -> Result<Vec<RenderedDetails>, script_registry::Error> {
+        let uid = obj.metadata.uid.clone();
+        let obj = rhai::serde::to_dynamic(obj)?;
+
+        let registered_details = self.registry.registered();
+
+        Ok(registered_details
+            .iter()
+            .filter(|details| self.matches(details, gvk))
+            .map(|details| RenderedDetails {
+                title: details.definition.title.clone(),
+                sections: details
+                    .definition
+                    .sections
+                    .iter()
+                    .map(|section| self.render_section(details, section, &obj, uid.clone()))
+                    .collect(),
+            })
+            .collect())
+    }
+
+    fn matches(&self, details: &Registered<ResourceDetails>, gvk: &GroupVersionKind) -> bool {
+        let Some(matcher) = &details.definition.matcher else {
+            return true;
+        };
+
+        let gvk = gvk.clone();
+
+        limits::with_time_limit(limits::MATCHER_TIME_LIMIT, || {
+            matcher.call::<bool>(
+                &details.engine,
+                &details.ast,
+                (gvk.group, gvk.version, gvk.kind),
+            )
+        })
+        .unwrap_or_else(|e| {
+            self.diagnostics.report(
+                ScriptDiagnostic::from_eval_error(
+                    DiagnosticKind::Matcher,
+                    &e,
+                    details.ast.source(),
+                )
+                .with_presentation(&details.definition.title),
+            );
+            false
+        })
+    }
+
+    fn render_section(
+        &self,
+        details: &Registered<ResourceDetails>,
+        section: &DetailSection,
+        obj: &rhai::Dynamic,
+        uid: Option<String>,
+    ) -> RenderedSection {
+        let result = limits::with_time_limit(limits::RENDER_TIME_LIMIT, || {
+            section
+                .render
+                .call::<rhai::Dynamic>(&details.engine, &details.ast, (obj.clone(),))
+        });
+
+        let diagnostic = match result.map(SectionComponent::from_dynamic) {
+            Ok(Ok(component)) => {
+                return RenderedSection {
+                    title: section.title.clone(),
+                    component: Some(component),
+                    error: None,
+                };
+            }
+            Ok(Err(message)) => ScriptDiagnostic {
+                script: details.ast.source().map(ToOwned::to_owned),
+                ..ScriptDiagnostic::new(DiagnosticKind::Render, message)
+            },
+            Err(e) => {
+                ScriptDiagnostic::from_eval_error(DiagnosticKind::Render, &e, details.ast.source())
+            }
+        }
+        .with_presentation(&details.definition.title)
+        .with_column_title(&section.title)
+        .with_resource_uid(uid);
+
+        let message = diagnostic.to_string();
+        self.diagnostics.report(diagnostic);
+
+        RenderedSection {
+            title: section.title.clone(),
+            component: None,
+            error: Some(message),
+        }
+    }
+
+    pub fn evaluate(
+        &self,
+        scripts_provider: &ScriptsProvider,
+        profile: &ClusterProfileId,
+    ) -> Result<(), script_registry::Error> {
+        self.registry.evaluate(scripts_provider, profile)
+    }
+
+    /// (Re-)compiles and evaluates a single script and replaces all details it had registered before.
+    ///
+    /// If the script fails to compile or to evaluate, its previous details stay in place.
+    pub fn load_script(&self, entrypoint: &Path) -> Result<(), script_registry::Error> {
+        self.registry.load_script(entrypoint)
+    }
+
+    /// Removes all details of a script that no longer exists.
+    pub fn unload_script(&self, entrypoint: &Path) {
+        self.registry.unload_script(entrypoint)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::scripting::test_fixtures::TempPackages;
+
+    fn facade_with_script(script: &str) -> Arc<ResourceDetailsFacade> {
+        let packages = TempPackages::new();
+        let entrypoint = packages.write("core/details/deployments.rhai", script);
+
+        let facade = ResourceDetailsFacade::new(
+            ClusterMetrics::new(),
+            RelatedResources::new(),
+            ScriptDiagnostics::new(),
+            ScriptEnvironment::default(),
+        );
+        facade.load_script(&entrypoint).unwrap();
+
+        facade
+    }
+
+    fn deployment() -> DynamicObject {
+        serde_json::from_value(json!({
+            "apiVersion": "apps/v1",
+            "kind": "Deployment",
+            "metadata": { "name": "web", "namespace": "default", "uid": "1234" },
+            "spec": { "replicas": 3 },
+            "status": {
+                "conditions": [{ "type": "Available", "status": "True" }]
+            }
+        }))
+        .unwrap()
+    }
+
+    fn gvk(kind: &str) -> GroupVersionKind {
+        GroupVersionKind::gvk("apps", "v1", kind)
+    }
+
+    #[test]
+    pub fn test_render_matching_details() {
+        let facade = facade_with_script(
+            r#"
+            register_resource_details(ResourceDetails(#{
+                title: "Deployment",
+                matcher: |group, version, kind| kind == "Deployment",
+                sections: [
+                    DetailSection(#{ title: "Overview", render: |obj| #{ Replicas: obj.spec.replicas, Paused: obj.spec.paused } }),
+                    DetailSection(#{ title: "Conditions", render: |obj| ConditionTable(obj.status?.conditions) }),
+                    DetailSection(#{ title: "Events", render: |obj| EventList(#{ max: 5 }) }),
+                ]
+            }));
+            "#,
+        );
+
+        let rendered = facade.render(&gvk("Deployment"), &deployment()).unwrap();
+
+        assert_eq!(1, rendered.len());
+        assert_eq!(
+            json!([
+                {
+                    "title": "Overview",
+                    "component": {
+                        "kind": "KeyValueGrid",
+                        "args": { "rows": [{ "key": "Replicas", "value": { "kind": "Text", "args": { "content": "3" }, "properties": null, "sortableValue": "3" } }] }
+                    },
+                    "error": null
+                },
+                {
+                    "title": "Conditions",
+                    "component": {
+                        "kind": "ConditionTable",
+                        "args": { "conditions": [{ "type": "Available", "status": "True", "reason": null, "message": null, "lastTransitionTime": null }] }
+                    },
+                    "error": null
+                },
+                {
+                    "title": "Events",
+                    "component": { "kind": "EventList", "args": { "max": 5, "events": [] } },
+                    "error": null
+                }
+            ]),
+            serde_json::to_value(&rendered[0].sections).unwrap()
+        );
+        assert!(facade
+            .render(&gvk("StatefulSet"), &deployment())
+            .unwrap()
+            .is_empty());
+    }
+
+    #[test]
+    pub fn test_failing_section_does_not_affect_others() {
+        let facade = facade_with_script(
+            r#"
+            register_resource_details(ResourceDetails(#{
+                title: "Deployment",
+                sections: [
+                    DetailSection(#{ title: "Broken", render: |obj| obj.spec.replicas.missing() }),
+                    DetailSection(#{ title: "Wrong type", render: |obj| 42 }),
+                    DetailSection(#{ title: "Overview", render: |obj| KeyValueGrid([["Name", obj.metadata.name]]) }),
+                ]
+            }));
+            "#,
+        );
+
+        let rendered = facade.render(&gvk("Deployment"), &deployment()).unwrap();
+        let sections = &rendered[0].sections;
+
+        assert!(sections[0].component.is_none());
+        assert!(sections[0].error.as_ref().unwrap().contains("missing"));
+        assert!(sections[1]
+            .error
+            .as_ref()
+            .unwrap()
+            .contains("must render a KeyValueGrid"));
+        assert!(sections[2].component.is_some());
+    }
+
+    #[test]
+    pub fn test_related_needs_kube_read() {
+        let packages = TempPackages::new();
+        let script = r#"
+            register_resource_details(ResourceDetails(#{
+                title: "Owner",
+                sections: [
+                    DetailSection(#{ title: "Owner", render: |obj| KeyValueGrid([["Owner", related::owner(obj)]]) }),
+                ]
+            }));
+            "#;
+        let builtin = packages.write("builtin/core/details/owner.rhai", script);
+        let extension = packages.write("extensions/fleet/details/owner.rhai", script);
+
+        let facade = ResourceDetailsFacade::new(
+            ClusterMetrics::new(),
+            RelatedResources::new(),
+            ScriptDiagnostics::new(),
+            ScriptEnvironment {
+                builtin_packages: Some(packages.root().join("builtin")),
+                ..ScriptEnvironment::default()
+            },
+        );
+        facade.load_script(&builtin).unwrap();
+        facade.load_script(&extension).unwrap();
+
+        let rendered = facade.render(&gvk("Deployment"), &deployment()).unwrap();
+
+        assert!(rendered[0].sections[0].component.is_some());
+        assert!(rendered[1].sections[0]
+            .error
+            .as_ref()
+            .unwrap()
+            .contains("`kube-read`"));
+    }
+}
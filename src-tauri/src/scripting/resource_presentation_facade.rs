@@ -1,162 +1,154 @@
 use std::{
-    collections::HashMap,
-    path::PathBuf,
-    sync::{Arc, RwLock, Weak},
+    path::Path,
+    sync::{Arc, Weak},
 };
 
 use k8s_openapi::apiextensions_apiserver::pkg::apis::apiextensions::v1::CustomResourceDefinition;
 use kube::api::GroupVersionKind;
+use tokio::sync::broadcast;
 
 use crate::{
-    app_state::{ClusterStateRegistry, ManagerExt as _},
-    cluster_discovery::ClusterDiscovery,
-    frontend_commands::KubeContextSource,
+    cluster_metrics::ClusterMetrics,
+    cluster_profiles::ClusterProfileId,
+    related_resources::RelatedResources,
     resource_rendering::{
         CrdRenderer, FallbackRenderer, ResourceColumnDefinition, ResourceRenderer,
     },
     scripting::{
-        scripts_provider::{self, ScriptType, ScriptsProvider},
+        capabilities::Capabilities,
+        diagnostics::{DiagnosticKind, ScriptDiagnostic, ScriptDiagnostics},
+        environment::ScriptEnvironment,
+        limits, modules,
+        script_registry::{self, ScriptRegistry},
+        scripts_provider::{ScriptType, ScriptsProvider},
         types::{
-            commons::ContentScript,
             resource_context_menus::ColumnTemplate,
-            resource_presentations::{
-                ColoredBox, ColoredBoxes, Hyperlink, RelativeTime, ResourcePresentation,
-                ResourcePresentationField, Text,
-            },
+            resource_presentations::{self, ResourcePresentation, ResourcePresentationField},
             ResourceRef,
         },
     },
 };
 
-struct ResourcePresentationDefinition {
-    title: String,
-    matcher: Option<rhai::FnPtr>,
-    columns: Vec<ColumnTemplate>,
-    ast: Arc<rhai::AST>,
-}
-
 pub struct ResourcePresentationFacade {
-    app: tauri::AppHandle,
-    scripts: RwLock<HashMap<PathBuf, ContentScript>>,
-    engine: Arc<rhai::Engine>,
-    registered_presentations: RwLock<Vec<ResourcePresentationDefinition>>,
+    registry: ScriptRegistry<ResourcePresentation>,
+    diagnostics: Arc<ScriptDiagnostics>,
+    reloads: broadcast::Sender<()>,
 }
 
 impl ResourcePresentationFacade {
-    pub fn new(app: tauri::AppHandle) -> Arc<Self> {
+    pub fn new(
+        metrics: Arc<ClusterMetrics>,
+        related: Arc<RelatedResources>,
+        diagnostics: Arc<ScriptDiagnostics>,
+        environment: ScriptEnvironment,
+    ) -> Arc<Self> {
         Arc::new_cyclic(|weak| Self {
-            app,
-            engine: Self::make_engine(weak.clone()),
-            scripts: RwLock::new(HashMap::new()),
-            registered_presentations: RwLock::new(Vec::new()),
+            registry: ScriptRegistry::new(
+                ScriptType::Presentation,
+                environment.clone(),
+                Arc::clone(&diagnostics),
+                {
+                    let facade = weak.clone();
+                    let diagnostics = Arc::clone(&diagnostics);
+
+                    move |capabilities| {
+                        Self::make_engine(
+                            facade.clone(),
+                            Arc::clone(&metrics),
+                            Arc::clone(&related),
+                            &diagnostics,
+                            &environment,
+                            capabilities,
+                        )
+                    }
+                },
+            ),
+            diagnostics,
+            reloads: broadcast::channel(4).0,
         })
     }
 
-    fn make_engine(facade: Weak<Self>) -> Arc<rhai::Engine> {
+    fn make_engine(
+        facade: Weak<Self>,
+        metrics: Arc<ClusterMetrics>,
+        related: Arc<RelatedResources>,
+        diagnostics: &Arc<ScriptDiagnostics>,
+        environment: &ScriptEnvironment,
+        capabilities: &Capabilities,
+    ) -> rhai::Engine {
         let mut engine = rhai::Engine::new();
+        diagnostics.capture_output(&mut engine);
 
         engine
             .build_type::<ResourceRef>()
             .build_type::<ResourcePresentation>()
-            .build_type::<ColumnTemplate>()
-            .build_type::<Text>()
-            .build_type::<Hyperlink>()
-            .build_type::<RelativeTime>()
-            .build_type::<ColoredBox>()
-            .build_type::<ColoredBoxes>();
+            .build_type::<ColumnTemplate>();
+        resource_presentations::register_components(&mut engine);
+
+        engine.register_static_module("metrics", modules::metrics::build_module(metrics).into());
+        environment.apply(&mut engine);
+        engine.register_static_module(
+            "related",
+            modules::related::build_module(related, capabilities).into(),
+        );
 
         {
             engine.register_fn(
                 "register_resource_presentation",
-                move |ctx: rhai::NativeCallContext,
-                      definition: ResourcePresentation|
-                      -> Result<(), Box<rhai::EvalAltResult>> {
+                move |definition: ResourcePresentation| {
                     let facade = facade.upgrade().expect("facade dropped");
-                    let script = ctx
-                        .call_source()
-                        .ok_or("only file-based scripts supported")?;
 
-                    facade
-                        .register_resource_presentation(definition, script)
-                        .map_err(|e| e.to_string().into())
+                    facade.registry.stage(definition);
                 },
             );
         }
 
-        engine.set_max_expr_depths(64, 32);
+        limits::apply(&mut engine);
 
-        Arc::new(engine)
-    }
-
-    fn register_resource_presentation(
-        &self,
-        presentation: ResourcePresentation,
-        script: &str,
-    ) -> Result<(), ResourcePresentationError> {
-        let script: PathBuf = script.into();
-
-        let ast = self.scripts.read().unwrap();
-        let ast = ast
-            .get(&script)
-            .unwrap()
-            .ast
-            .as_ref()
-            .ok_or(ResourcePresentationError::PendingCompilation)?
-            .as_ref()
-            .map_err(|_| ResourcePresentationError::CompilationError)?;
-
-        let mut presentations = self.registered_presentations.write().unwrap();
-
-        presentations.push(ResourcePresentationDefinition {
-            title: presentation.title,
-            matcher: presentation.matcher,
-            columns: presentation.columns,
-            ast: Arc::clone(ast),
-        });
-
-        Ok(())
+        engine
     }
 
-    /// Returns the names of all available renderers for the given GVK
+    /// Returns the names of all available renderers for the given GVK, `is_crd` adds the default renderer of
+    /// custom resources.
     pub fn get_renderers(
         &self,
-        context_source: &KubeContextSource,
         gvk: &GroupVersionKind,
-    ) -> Result<Vec<String>, ResourcePresentationError> {
-        let clusters = self.app.state::<ClusterStateRegistry>();
-        let discovery = clusters.discovery_for(context_source).unwrap();
-
-        let registered_presentations = self.registered_presentations.read().unwrap();
-
-        let crds: Vec<GroupVersionKind> = match &*discovery {
-            ClusterDiscovery::Inflight(_) => Vec::new(),
-            ClusterDiscovery::Completed(resources) => resources.crds.keys().cloned().collect(),
-        };
+        is_crd: bool,
+    ) -> Result<Vec<String>, script_registry::Error> {
+        let registered_presentations = self.registry.registered();
 
         let renderers = registered_presentations
             .iter()
             .filter(|&presentation| {
                 presentation
+                    .definition
                     .matcher
                     .as_ref()
                     .map(|matcher| {
                         let gvk = gvk.clone();
-                        matcher.call::<bool>(
-                            &self.engine,
-                            &Arc::clone(&presentation.ast),
-                            (gvk.group, gvk.version, gvk.kind),
-                        )
+                        limits::with_time_limit(limits::MATCHER_TIME_LIMIT, || {
+                            matcher.call::<bool>(
+                                &presentation.engine,
+                                &presentation.ast,
+                                (gvk.group, gvk.version, gvk.kind),
+                            )
+                        })
                     })
                     .unwrap_or(Ok(true))
-                    .map_err(ResourcePresentationError::Matcher)
-                    .expect("handle me")
-            })
-            .map(|presentation| presentation.title.clone())
-            .chain({
-                crds.contains(gvk)
-                    .then(|| "Custom resource default".to_owned())
-                    .into_iter()
+                    .unwrap_or_else(|e| {
+                        self.diagnostics.report(
+                            ScriptDiagnostic::from_eval_error(
+                                DiagnosticKind::Matcher,
+                                &e,
+                                presentation.ast.source(),
+                            )
+                            .with_presentation(&presentation.definition.title),
+                        );
+                        false
+                    })
             })
+            .map(|presentation| presentation.definition.title.clone())
+            .chain(is_crd.then(|| "Custom resource default".to_owned()))
             .chain(std::iter::once("Simple list".to_owned()))
             .collect();
 
@@ -177,11 +169,11 @@ impl ResourcePresentationFacade {
             return Box::new(crd_renderer) as Box<dyn ResourceRenderer>;
         }
 
-        let registered_presentations = self.registered_presentations.read().unwrap();
+        let registered_presentations = self.registry.registered();
 
         let presentation = registered_presentations
             .iter()
-            .find(|p| p.title == presentation);
+            .find(|p| p.definition.title == presentation);
 
         if presentation.is_none() {
             return Box::new(generic_renderer) as Box<dyn ResourceRenderer>;
@@ -190,64 +182,43 @@ impl ResourcePresentationFacade {
         let presentation = presentation.unwrap();
 
         Box::new(ScriptedRenderer {
-            title: presentation.title.clone(),
-            templates: presentation.columns.clone(),
-            engine: Arc::clone(&self.engine),
+            title: presentation.definition.title.clone(),
+            templates: presentation.definition.columns.clone(),
+            engine: Arc::clone(&presentation.engine),
             ast: Arc::clone(&presentation.ast),
+            diagnostics: Arc::clone(&self.diagnostics),
         }) as Box<dyn ResourceRenderer>
     }
 
     pub fn evaluate(
         &self,
         scripts_provider: &ScriptsProvider,
-    ) -> Result<(), ResourcePresentationError> {
-        let menu_scripts = scripts_provider
-            .get_scripts_for_type(ScriptType::Presentation)
-            .unwrap();
-
-        for entrypoint in &menu_scripts {
-            tracing::info!("Evaluating {}", entrypoint.to_string_lossy());
-
-            let ast_arc = {
-                let mut scripts = self.scripts.write().unwrap();
-
-                let script = scripts
-                    .entry(entrypoint.to_owned())
-                    .or_insert(ContentScript::new());
-
-                let ast_result = script.ast.get_or_insert_with(|| {
-                    self.engine.compile_file(entrypoint.clone()).map(Arc::new)
-                });
-
-                ast_result
-                    .as_ref()
-                    .map_err(|_| ResourcePresentationError::CompilationError)?
-                    .clone()
-            };
-
-            self.engine.eval_ast::<()>(&ast_arc)?;
-        }
-
-        Ok(())
+        profile: &ClusterProfileId,
+    ) -> Result<(), script_registry::Error> {
+        self.registry.evaluate(scripts_provider, profile)
     }
-}
-
-#[derive(thiserror::Error, Debug)]
-pub enum ResourcePresentationError {
-    #[error("Error evaluating script: {0}")]
-    EvaluationResult(#[from] Box<rhai::EvalAltResult>),
 
-    #[error("The script has not yet been compiled")]
-    PendingCompilation,
+    /// (Re-)compiles and evaluates a single script and replaces all presentations it had registered before.
+    ///
+    /// If the script fails to compile or to evaluate, its previous presentations stay in place.
+    pub fn load_script(&self, entrypoint: &Path) -> Result<(), script_registry::Error> {
+        self.registry.load_script(entrypoint)
+    }
 
-    #[error("The script has a compilation error")]
-    CompilationError,
+    /// Removes all presentations of a script that no longer exists.
+    pub fn unload_script(&self, entrypoint: &std::path::Path) {
+        self.registry.unload_script(entrypoint)
+    }
 
-    #[error(transparent)]
-    ScriptDirectoryResolution(#[from] scripts_provider::Error),
+    /// Returns a receiver that yields once after every batch of reloaded scripts.
+    pub fn subscribe(&self) -> broadcast::Receiver<()> {
+        self.reloads.subscribe()
+    }
 
-    #[error("Call to matcher failed: {0}")]
-    Matcher(Box<rhai::EvalAltResult>),
+    pub fn notify_reloaded(&self) {
+        // Nobody listening is fine, views subscribe only while they are open.
+        let _ = self.reloads.send(());
+    }
 }
 
 struct ScriptedRenderer {
@@ -255,6 +226,7 @@ struct ScriptedRenderer {
     templates: Vec<ColumnTemplate>,
     engine: Arc<rhai::Engine>,
     ast: Arc<rhai::AST>,
+    diagnostics: Arc<ScriptDiagnostics>,
 }
 
 impl ResourceRenderer for ScriptedRenderer {
@@ -287,49 +259,80 @@ impl ResourceRenderer for ScriptedRenderer {
         obj: &kube::api::DynamicObject,
     ) -> Result<Vec<Result<ResourcePresentationField, String>>, crate::frontend_types::BackendError>
     {
-        let obj =
-            rhai::serde::to_dynamic(obj).expect("failed to convert Kubernetes resource to dynamic");
+        let uid = obj.metadata.uid.clone();
+        let obj = rhai::serde::to_dynamic(obj)
+            .map_err(|e| format!("Failed to convert Kubernetes resource to a script value: {e}"))?;
 
         Ok(self
             .templates
             .iter()
             .map(|t| {
-                t.render
-                    .call::<rhai::Dynamic>(&self.engine, &self.ast, (obj.clone(),))
-                    .map_err(|e| e.to_string())
-                    .map(|value| {
-                        if value.is::<Text>() {
-                            return ResourcePresentationField::Text(value.cast::<Text>());
-                        }
-
-                        if value.is::<Hyperlink>() {
-                            return ResourcePresentationField::Hyperlink(value.cast::<Hyperlink>());
-                        }
-
-                        if value.is::<RelativeTime>() {
-                            return ResourcePresentationField::RelativeTime(
-                                value.cast::<RelativeTime>(),
-                            );
-                        }
-
-                        if value.is::<ColoredBox>() {
-                            return ResourcePresentationField::ColoredBox(
-                                value.cast::<ColoredBox>(),
-                            );
-                        }
-
-                        if value.is::<ColoredBoxes>() {
-                            return ResourcePresentationField::ColoredBoxes(
-                                value.cast::<ColoredBoxes>(),
-                            );
-                        }
-
-                        ResourcePresentationField::Text(Text {
-                            content: value.to_string(),
-                            properties: None,
-                        })
-                    })
+                limits::with_time_limit(limits::RENDER_TIME_LIMIT, || {
+                    t.render
+                        .call::<rhai::Dynamic>(&self.engine, &self.ast, (obj.clone(),))
+                })
+                .map_err(|e| {
+                    let diagnostic = ScriptDiagnostic::from_eval_error(
+                        DiagnosticKind::Render,
+                        &e,
+                        self.ast.source(),
+                    )
+                    .with_presentation(&self.title)
+                    .with_column_title(&t.title)
+                    .with_resource_uid(uid.clone());
+
+                    let message = diagnostic.to_string();
+                    self.diagnostics.report(diagnostic);
+
+                    message
+                })
+                .map(ResourcePresentationField::from_dynamic)
             })
             .collect())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scripting::test_fixtures::TempPackages;
+
+    fn facade() -> Arc<ResourcePresentationFacade> {
+        ResourcePresentationFacade::new(
+            ClusterMetrics::new(),
+            RelatedResources::new(),
+            ScriptDiagnostics::new(),
+            ScriptEnvironment::default(),
+        )
+    }
+
+    fn presentation_script(title: &str) -> String {
+        format!(
+            r#"register_resource_presentation(ResourcePresentation(#{{ title: "{title}", columns: [] }}));"#
+        )
+    }
+
+    #[test]
+    pub fn test_failed_reload_keeps_previous_presentations() {
+        let packages = TempPackages::new();
+        let gvk = GroupVersionKind::gvk("", "v1", "Pod");
+        let facade = facade();
+
+        let entrypoint = packages.write(
+            "core/presentations/pods.rhai",
+            &presentation_script("First"),
+        );
+        facade.load_script(&entrypoint).unwrap();
+
+        packages.write(
+            "core/presentations/pods.rhai",
+            &(presentation_script("Second") + r#" throw "broken";"#),
+        );
+
+        assert!(facade.load_script(&entrypoint).is_err());
+        assert_eq!(
+            vec!["First", "Simple list"],
+            facade.get_renderers(&gvk, false).unwrap()
+        );
+    }
+}
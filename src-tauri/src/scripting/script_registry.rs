@@ -0,0 +1,272 @@
+use std::{
+    collections::BTreeMap,
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex, RwLock, RwLockReadGuard},
+};
+
+use crate::{
+    cluster_profiles::ClusterProfileId,
+    scripting::{
+        capabilities::Capabilities,
+        diagnostics::{DiagnosticKind, ScriptDiagnostic, ScriptDiagnostics},
+        environment::{self, ScriptEnvironment},
+        hot_reload, limits,
+        modules::storage,
+        scripts_provider::{self, ScriptType, ScriptsProvider},
+    },
+};
+
+/// Something that a script registered, e.g. a presentation, along with the script it came from.
+pub struct Registered<T> {
+    pub script: PathBuf,
+    /// The engine of the capabilities granted to the script's package, its callbacks must run in it
+    pub engine: Arc<rhai::Engine>,
+    pub ast: Arc<rhai::AST>,
+    pub definition: T,
+}
+
+/// Evaluates the scripts of one [ScriptType] and keeps what they registered, in the order it was registered.
+///
+/// The registration functions of the engine hand their definitions to [Self::stage] while a script is being
+/// evaluated. They replace what the script had registered before only once its evaluation succeeded.
+pub struct ScriptRegistry<T> {
+    script_type: ScriptType,
+    environment: ScriptEnvironment,
+    /// Builds the engine for a set of capabilities
+    make_engine: Box<dyn Fn(&Capabilities) -> rhai::Engine + Send + Sync>,
+    /// One engine per set of capabilities, built on demand. Each script runs in the engine of its package's grants.
+    engines: RwLock<BTreeMap<Capabilities, Arc<rhai::Engine>>>,
+    registered: RwLock<Vec<Registered<T>>>,
+    /// Definitions registered by the script that is currently being evaluated
+    staged: Mutex<Vec<T>>,
+    /// Serializes script evaluations, so that staged definitions end up with the right script
+    evaluation: Mutex<()>,
+    diagnostics: Arc<ScriptDiagnostics>,
+}
+
+impl<T> ScriptRegistry<T> {
+    pub fn new(
+        script_type: ScriptType,
+        environment: ScriptEnvironment,
+        diagnostics: Arc<ScriptDiagnostics>,
+        make_engine: impl Fn(&Capabilities) -> rhai::Engine + Send + Sync + 'static,
+    ) -> Self {
+        Self {
+            script_type,
+            environment,
+            make_engine: Box::new(make_engine),
+            engines: RwLock::new(BTreeMap::new()),
+            registered: RwLock::new(Vec::new()),
+            staged: Mutex::new(Vec::new()),
+            evaluation: Mutex::new(()),
+            diagnostics,
+        }
+    }
+
+    /// Returns the engine for scripts with the given capabilities, building it on first use.
+    fn engine_for(&self, capabilities: &Capabilities) -> Arc<rhai::Engine> {
+        if let Some(engine) = self.engines.read().unwrap().get(capabilities) {
+            return Arc::clone(engine);
+        }
+
+        let mut engines = self.engines.write().unwrap();
+
+        Arc::clone(
+            engines
+                .entry(capabilities.clone())
+                .or_insert_with(|| Arc::new((self.make_engine)(capabilities))),
+        )
+    }
+
+    /// Adds a definition of the script that is currently being evaluated.
+    pub fn stage(&self, definition: T) {
+        self.staged.lock().unwrap().push(definition);
+    }
+
+    /// Everything registered by the loaded scripts, in the order of evaluation.
+    pub fn registered(&self) -> RwLockReadGuard<'_, Vec<Registered<T>>> {
+        self.registered.read().unwrap()
+    }
+
+    /// Loads all scripts of the active packages of `profile`.
+    pub fn evaluate(
+        &self,
+        scripts_provider: &ScriptsProvider,
+        profile: &ClusterProfileId,
+    ) -> Result<(), Error> {
+        let scripts = scripts_provider.get_scripts_for_type(self.script_type, profile)?;
+
+        for entrypoint in &scripts {
+            tracing::info!("Evaluating {}", entrypoint.to_string_lossy());
+
+            // The failure has been reported to the scripting console, the other scripts still load.
+            if let Err(e) = self.load_script(entrypoint) {
+                tracing::debug!("Skipped {}: {e}", entrypoint.display());
+            }
+        }
+
+        Ok(())
+    }
+
+    /// (Re-)compiles and evaluates a single script and replaces everything it had registered before.
+    ///
+    /// If the script fails to compile or to evaluate, its previous definitions stay in place.
+    pub fn load_script(&self, entrypoint: &Path) -> Result<(), Error> {
+        let _evaluation = self.evaluation.lock().unwrap();
+
+        let engine = self.engine_for(&self.environment.capabilities_of(entrypoint));
+
+        let ast = environment::compile_file(&engine, entrypoint)
+            .map(Arc::new)
+            .map_err(|e| {
+                self.diagnostics.report(ScriptDiagnostic::from_eval_error(
+                    DiagnosticKind::Compile,
+                    &e,
+                    entrypoint.to_str(),
+                ));
+                Error::Compilation(e)
+            })?;
+
+        self.staged.lock().unwrap().clear();
+        let result = storage::evaluating(entrypoint, || {
+            limits::with_time_limit(limits::EVALUATION_TIME_LIMIT, || {
+                engine.eval_ast::<()>(&ast)
+            })
+        })
+        .inspect_err(|e| {
+            self.diagnostics.report(ScriptDiagnostic::from_eval_error(
+                DiagnosticKind::Evaluation,
+                e,
+                ast.source(),
+            ));
+        });
+        let staged = std::mem::take(&mut *self.staged.lock().unwrap());
+
+        result?;
+
+        let staged = staged
+            .into_iter()
+            .map(|definition| Registered {
+                script: entrypoint.to_owned(),
+                engine: Arc::clone(&engine),
+                ast: Arc::clone(&ast),
+                definition,
+            })
+            .collect();
+
+        hot_reload::splice_script_entries(
+            &mut self.registered.write().unwrap(),
+            entrypoint,
+            staged,
+            |r| &r.script,
+        );
+
+        Ok(())
+    }
+
+    /// Removes everything registered by a script that no longer exists.
+    pub fn unload_script(&self, entrypoint: &Path) {
+        let _evaluation = self.evaluation.lock().unwrap();
+
+        self.registered
+            .write()
+            .unwrap()
+            .retain(|r| r.script != entrypoint);
+    }
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum Error {
+    #[error("Error evaluating script: {0}")]
+    Evaluation(#[from] Box<rhai::EvalAltResult>),
+
+    #[error("The script has a compilation error: {0}")]
+    Compilation(Box<rhai::EvalAltResult>),
+
+    #[error(transparent)]
+    ScriptDirectoryResolution(#[from] scripts_provider::Error),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scripting::test_fixtures::TempPackages;
+
+    fn registry() -> Arc<ScriptRegistry<String>> {
+        Arc::new_cyclic(|weak: &std::sync::Weak<ScriptRegistry<String>>| {
+            let weak = weak.clone();
+
+            ScriptRegistry::new(
+                ScriptType::Presentation,
+                ScriptEnvironment::default(),
+                ScriptDiagnostics::new(),
+                move |_| {
+                    let weak = weak.clone();
+                    let mut engine = rhai::Engine::new();
+                    engine.register_fn("register", move |name: &str| {
+                        weak.upgrade().unwrap().stage(name.to_owned());
+                    });
+
+                    engine
+                },
+            )
+        })
+    }
+
+    fn names(registry: &ScriptRegistry<String>) -> Vec<String> {
+        registry
+            .registered()
+            .iter()
+            .map(|r| r.definition.clone())
+            .collect()
+    }
+
+    #[test]
+    pub fn test_reload_replaces_in_place() {
+        let packages = TempPackages::new();
+        let a = packages.write("core/presentations/a.rhai", r#"register("a1");"#);
+        let b = packages.write("core/presentations/b.rhai", r#"register("b1");"#);
+        let registry = registry();
+
+        registry.load_script(&a).unwrap();
+        registry.load_script(&b).unwrap();
+
+        packages.write(
+            "core/presentations/a.rhai",
+            r#"register("a2"); register("a3");"#,
+        );
+        registry.load_script(&a).unwrap();
+
+        assert_eq!(vec!["a2", "a3", "b1"], names(&registry));
+
+        registry.unload_script(&a);
+
+        assert_eq!(vec!["b1"], names(&registry));
+    }
+
+    #[test]
+    pub fn test_failed_evaluation_keeps_previous_definitions() {
+        let packages = TempPackages::new();
+        let a = packages.write("core/presentations/a.rhai", r#"register("a1");"#);
+        let registry = registry();
+
+        registry.load_script(&a).unwrap();
+
+        packages.write(
+            "core/presentations/a.rhai",
+            r#"register("a2"); throw "broken";"#,
+        );
+        assert!(matches!(
+            registry.load_script(&a),
+            Err(Error::Evaluation(_))
+        ));
+
+        packages.write("core/presentations/a.rhai", r#"register("a3""#);
+        assert!(matches!(
+            registry.load_script(&a),
+            Err(Error::Compilation(_))
+        ));
+
+        assert_eq!(vec!["a1"], names(&registry));
+    }
+}
@@ -1,22 +1,229 @@
-use std::{io::ErrorKind, path::PathBuf, sync::Arc};
+use std::{
+    collections::HashSet,
+    io::ErrorKind,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
 
 use scan_dir::ScanDir;
+use serde::{Deserialize, Serialize};
 use tauri::Manager as _;
 
-use crate::app_state::ManagedState;
+use crate::{
+    app_state::{ManagedState, ManagerExt},
+    cluster_profiles::ClusterProfileId,
+    persistence::cluster_profile_service::{self, ClusterProfileService},
+    scripting::package_manifest::{self, PackageManifest},
+};
 
 const DIR_NAME_MENUS: &str = "menus";
 const DIR_NAME_PRESENTATIONS: &str = "presentations";
+const DIR_NAME_DETAILS: &str = "details";
+const DIR_NAME_DASHBOARDS: &str = "dashboards";
+const DIR_NAME_LIB: &str = "lib";
+
+/// The builtin package whose libraries are importable by the scripts of all packages
+const CORE_PACKAGE_DIR: &str = "core";
 
 /// A ScriptType determines which capabilties are available in the scripting engine
 /// at runtime. This avoids unexpected side-effects of poorly-written scripts.
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy)]
 pub enum ScriptType {
     /// A script that contributes to a Kubernetes resource context menu
     Menu,
 
     // A script that provides a custom column-based presentation of a Kubernetes resource
     Presentation,
+
+    /// A script that contributes sections to the details panel of a selected Kubernetes resource
+    Details,
+
+    /// A script that contributes widgets to the overview page of a cluster
+    Dashboard,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[serde(rename_all = "camelCase")]
+pub enum PackageSource {
+    /// Shipped with Hyprkube
+    Builtin,
+    /// Installed by the user into the `extensions` directory
+    Extension,
+}
+
+#[derive(Debug, Clone, Serialize, PartialEq)]
+#[serde(rename_all = "camelCase", tag = "state", content = "reason")]
+pub enum PackageStatus {
+    /// The scripts of the package are loaded
+    Active,
+    /// The package has been disabled for the cluster profile
+    Disabled,
+    /// Another package with the same name takes precedence, see [resolve_packages]
+    Overridden,
+    /// The package requires a newer version of Hyprkube
+    Incompatible(String),
+    /// The manifest of the package could not be read
+    Invalid(String),
+}
+
+/// Identifies a package across builtins and extensions, which may share a name.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[serde(rename_all = "camelCase")]
+pub struct PackageId {
+    pub source: PackageSource,
+    pub name: String,
+}
+
+/// A directory containing scripts, along with its manifest and whether its scripts are loaded.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScriptPackage {
+    pub manifest: PackageManifest,
+    pub source: PackageSource,
+    pub path: PathBuf,
+    pub status: PackageStatus,
+}
+
+impl ScriptPackage {
+    pub fn id(&self) -> PackageId {
+        PackageId {
+            source: self.source,
+            name: self.manifest.name.clone(),
+        }
+    }
+
+    fn read(path: PathBuf, source: PackageSource) -> Self {
+        match PackageManifest::read(&path, source) {
+            Ok(manifest) => {
+                let hyprkube_version = package_manifest::hyprkube_version();
+
+                let status = match &manifest.min_hyprkube_version {
+                    Some(min) if !manifest.is_compatible_with(&hyprkube_version) => {
+                        PackageStatus::Incompatible(format!(
+                            "Requires Hyprkube {min} or newer, this is {hyprkube_version}"
+                        ))
+                    }
+                    _ => PackageStatus::Active,
+                };
+
+                Self {
+                    manifest,
+                    source,
+                    path,
+                    status,
+                }
+            }
+            Err(e) => Self {
+                manifest: PackageManifest::unversioned(&path, source),
+                source,
+                path,
+                status: PackageStatus::Invalid(e.to_string()),
+            },
+        }
+    }
+}
+
+/// The package directory of a script, e.g. `extensions/fleet` for `extensions/fleet/menus/gitrepo.rhai`.
+pub fn package_dir_of(script: &Path) -> Option<&Path> {
+    script.parent()?.parent()
+}
+
+/// The directory of the libraries that the scripts of a package can `import`.
+pub fn lib_dir_of(package: &Path) -> PathBuf {
+    package.join(DIR_NAME_LIB)
+}
+
+/// The library directory of the core package in the source tree, for tests of the builtin scripts.
+#[cfg(test)]
+pub fn source_core_lib_dir() -> PathBuf {
+    lib_dir_of(
+        &Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("scripts")
+            .join(CORE_PACKAGE_DIR),
+    )
+}
+
+/// The entrypoints of the given type in a package, in the order they are evaluated.
+pub fn scripts_in_package(package: &Path, script_type: ScriptType) -> Vec<PathBuf> {
+    let mut package_path = package.to_owned();
+    let mut scripts: Vec<PathBuf> = Vec::new();
+
+    match script_type {
+        ScriptType::Menu => package_path.push(DIR_NAME_MENUS),
+        ScriptType::Presentation => package_path.push(DIR_NAME_PRESENTATIONS),
+        ScriptType::Details => package_path.push(DIR_NAME_DETAILS),
+        ScriptType::Dashboard => package_path.push(DIR_NAME_DASHBOARDS),
+    }
+
+    let result = ScanDir::files().read(&package_path, |iter| {
+        let mut entrypoints = iter
+            .filter(|(_, name)| name.ends_with(".rhai"))
+            .map(|(ref entry, _)| entry.path())
+            .collect::<Vec<PathBuf>>();
+
+        scripts.append(&mut entrypoints);
+    });
+
+    if let Err(e) = result {
+        if let scan_dir::Error::Io(err, _) = &e {
+            if err.kind() != ErrorKind::NotFound {
+                tracing::warn!("{e}");
+            }
+        }
+    }
+
+    scripts.sort();
+
+    scripts
+}
+
+/// Decides which packages are loaded.
+///
+/// Packages are identified by the name from their manifest. Of all enabled and valid packages with the same
+/// name, only one becomes active, in the following order of precedence:
+///
+/// 1. Extensions take precedence over builtin packages, so a builtin package can be replaced as a whole by
+///    installing an extension with the same name. Scripts are never merged across packages.
+/// 2. Among packages of the same source, the highest version wins.
+///
+/// Disabling an overriding extension thus brings back the builtin package.
+pub fn resolve_packages(
+    mut packages: Vec<ScriptPackage>,
+    disabled: &HashSet<PackageId>,
+) -> Vec<ScriptPackage> {
+    for package in &mut packages {
+        if package.status == PackageStatus::Active && disabled.contains(&package.id()) {
+            package.status = PackageStatus::Disabled;
+        }
+    }
+
+    let precedence = |package: &ScriptPackage| {
+        (
+            package.source == PackageSource::Extension,
+            package.manifest.version.clone(),
+        )
+    };
+
+    for i in 0..packages.len() {
+        if packages[i].status != PackageStatus::Active {
+            continue;
+        }
+
+        let overridden = packages.iter().enumerate().any(|(j, other)| {
+            j != i
+                && other.status == PackageStatus::Active
+                && other.manifest.name == packages[i].manifest.name
+                // Equal precedence keeps the first of the two, in directory order
+                && (precedence(other) > precedence(&packages[i])
+                    || (precedence(other) == precedence(&packages[i]) && j < i))
+        });
+
+        if overridden {
+            packages[i].status = PackageStatus::Overridden;
+        }
+    }
+
+    packages
 }
 
 pub struct ScriptsProvider {
@@ -54,71 +261,63 @@ impl ScriptsProvider {
         packages
     }
 
-    fn get_builtin_packages(&self) -> Result<Vec<PathBuf>, Error> {
-        let builtins_base = self.app.path().resource_dir().map(|mut dir| {
+    pub fn get_builtin_packages_dir(&self) -> Result<PathBuf, Error> {
+        Ok(self.app.path().resource_dir().map(|mut dir| {
             dir.push("scripts");
             dir
-        })?;
-
-        Ok(self.list_packages_in(&builtins_base))
+        })?)
     }
 
-    fn get_extension_packages(&self) -> Result<Vec<PathBuf>, Error> {
-        let extensions_base = self
-            .app
+    pub fn get_extension_packages_dir(&self) -> Result<PathBuf, Error> {
+        self.app
             .path()
             .app_data_dir()
             .map(|mut dir| {
                 dir.push("extensions");
                 dir
             })
-            .map_err(Error::TauriDir)?;
-
-        Ok(self.list_packages_in(&extensions_base))
+            .map_err(Error::TauriDir)
     }
 
-    fn get_scripts_in_package(
-        &self,
-        mut package_path: PathBuf,
-        script_type: &ScriptType,
-    ) -> Vec<PathBuf> {
-        let mut scripts: Vec<PathBuf> = Vec::new();
-
-        match script_type {
-            ScriptType::Menu => package_path.push(DIR_NAME_MENUS),
-            ScriptType::Presentation => package_path.push(DIR_NAME_PRESENTATIONS),
-        }
-
-        let result = ScanDir::files().read(&package_path, |iter| {
-            let mut entrypoints = iter
-                .filter(|(_, name)| name.ends_with(".rhai"))
-                .map(|(ref entry, _)| entry.path())
-                .collect::<Vec<PathBuf>>();
+    /// The library directory of the builtin core package, see [crate::scripting::module_resolver::LibraryResolver].
+    pub fn get_core_lib_dir(&self) -> Result<PathBuf, Error> {
+        Ok(lib_dir_of(
+            &self.get_builtin_packages_dir()?.join(CORE_PACKAGE_DIR),
+        ))
+    }
 
-            scripts.append(&mut entrypoints);
-        });
+    /// Lists all builtin and extension packages along with their status for the given cluster profile.
+    pub fn list_packages(&self, profile: &ClusterProfileId) -> Result<Vec<ScriptPackage>, Error> {
+        let builtins = self
+            .list_packages_in(&self.get_builtin_packages_dir()?)
+            .into_iter()
+            .map(|path| ScriptPackage::read(path, PackageSource::Builtin));
 
-        if let Err(e) = result {
-            if let scan_dir::Error::Io(err, _) = &e {
-                if err.kind() != ErrorKind::NotFound {
-                    tracing::warn!("{e}");
-                }
-            }
-        }
+        let extensions = self
+            .list_packages_in(&self.get_extension_packages_dir()?)
+            .into_iter()
+            .map(|path| ScriptPackage::read(path, PackageSource::Extension));
 
-        scripts.sort();
+        let disabled = ManagerExt::state::<ClusterProfileService>(&self.app)
+            .list_disabled_script_packages(profile)?;
 
-        scripts
+        Ok(resolve_packages(
+            builtins.chain(extensions).collect(),
+            &disabled.into_iter().collect(),
+        ))
     }
 
-    pub fn get_scripts_for_type(&self, script_type: ScriptType) -> Result<Vec<PathBuf>, Error> {
-        let builtins = self.get_builtin_packages()?;
-        let extensions = self.get_extension_packages()?;
-
-        Ok(builtins
+    /// Returns the scripts of all active packages for the given cluster profile.
+    pub fn get_scripts_for_type(
+        &self,
+        script_type: ScriptType,
+        profile: &ClusterProfileId,
+    ) -> Result<Vec<PathBuf>, Error> {
+        Ok(self
+            .list_packages(profile)?
             .into_iter()
-            .chain(extensions)
-            .flat_map(|pkg| self.get_scripts_in_package(pkg, &script_type))
+            .filter(|pkg| pkg.status == PackageStatus::Active)
+            .flat_map(|pkg| scripts_in_package(&pkg.path, script_type))
             .collect())
     }
 }
@@ -127,4 +326,137 @@ impl ScriptsProvider {
 pub enum Error {
     #[error("Error resolving directory: {0}")]
     TauriDir(#[from] tauri::Error),
+
+    #[error(transparent)]
+    ClusterProfile(#[from] cluster_profile_service::Error),
+}
+
+impl Serialize for Error {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::ser::Serializer,
+    {
+        serializer.serialize_str(self.to_string().as_ref())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn package(name: &str, version: &str, source: PackageSource) -> ScriptPackage {
+        ScriptPackage {
+            manifest: PackageManifest {
+                name: name.to_owned(),
+                version: semver::Version::parse(version).unwrap(),
+                description: None,
+                min_hyprkube_version: None,
+                capabilities: Vec::new(),
+            },
+            source,
+            path: PathBuf::from(format!("{source:?}/{name}-{version}")),
+            status: PackageStatus::Active,
+        }
+    }
+
+    fn statuses(packages: &[ScriptPackage]) -> Vec<(&str, &PackageStatus)> {
+        packages
+            .iter()
+            .map(|p| (p.path.to_str().unwrap(), &p.status))
+            .collect()
+    }
+
+    #[test]
+    pub fn test_extension_overrides_builtin() {
+        let packages = resolve_packages(
+            vec![
+                package("core", "1.0.0", PackageSource::Builtin),
+                package("core", "0.1.0", PackageSource::Extension),
+                package("fleet", "1.0.0", PackageSource::Extension),
+            ],
+            &HashSet::new(),
+        );
+
+        assert_eq!(
+            vec![
+                ("Builtin/core-1.0.0", &PackageStatus::Overridden),
+                ("Extension/core-0.1.0", &PackageStatus::Active),
+                ("Extension/fleet-1.0.0", &PackageStatus::Active),
+            ],
+            statuses(&packages)
+        );
+    }
+
+    #[test]
+    pub fn test_highest_version_wins() {
+        let packages = resolve_packages(
+            vec![
+                package("fleet", "1.0.0", PackageSource::Extension),
+                package("fleet", "1.10.0", PackageSource::Extension),
+                package("fleet", "1.2.0", PackageSource::Extension),
+            ],
+            &HashSet::new(),
+        );
+
+        assert_eq!(
+            vec![
+                ("Extension/fleet-1.0.0", &PackageStatus::Overridden),
+                ("Extension/fleet-1.10.0", &PackageStatus::Active),
+                ("Extension/fleet-1.2.0", &PackageStatus::Overridden),
+            ],
+            statuses(&packages)
+        );
+    }
+
+    #[test]
+    pub fn test_disabled_packages_do_not_override() {
+        let mut broken = package("core", "2.0.0", PackageSource::Extension);
+        broken.status = PackageStatus::Invalid("broken".to_owned());
+
+        let packages = resolve_packages(
+            vec![
+                package("core", "1.0.0", PackageSource::Builtin),
+                broken,
+                package("fleet", "1.0.0", PackageSource::Extension),
+            ],
+            &HashSet::from([PackageId {
+                source: PackageSource::Extension,
+                name: "fleet".to_owned(),
+            }]),
+        );
+
+        assert_eq!(
+            vec![
+                ("Builtin/core-1.0.0", &PackageStatus::Active),
+                (
+                    "Extension/core-2.0.0",
+                    &PackageStatus::Invalid("broken".to_owned())
+                ),
+                ("Extension/fleet-1.0.0", &PackageStatus::Disabled),
+            ],
+            statuses(&packages)
+        );
+    }
+
+    #[test]
+    pub fn test_disabling_an_override_brings_back_the_builtin() {
+        let packages = resolve_packages(
+            vec![
+                package("core", "1.0.0", PackageSource::Builtin),
+                package("core", "0.1.0", PackageSource::Extension),
+            ],
+            &HashSet::from([PackageId {
+                source: PackageSource::Extension,
+                name: "core".to_owned(),
+            }]),
+        );
+
+        assert_eq!(
+            vec![
+                ("Builtin/core-1.0.0", &PackageStatus::Active),
+                ("Extension/core-0.1.0", &PackageStatus::Disabled),
+            ],
+            statuses(&packages)
+        );
+    }
 }
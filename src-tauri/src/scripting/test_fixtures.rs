@@ -0,0 +1,34 @@
+use std::path::{Path, PathBuf};
+
+/// A temporary directory for script packages, which is removed again when dropped.
+pub struct TempPackages {
+    root: PathBuf,
+}
+
+impl TempPackages {
+    pub fn new() -> Self {
+        let root = std::env::temp_dir().join(format!("hyprkube-test-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&root).unwrap();
+
+        Self { root }
+    }
+
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    /// Writes a file relative to the root, e.g. `core/lib/helpers.rhai`, creating its directories.
+    pub fn write(&self, path: &str, content: &str) -> PathBuf {
+        let path = self.root.join(path);
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(&path, content).unwrap();
+
+        path
+    }
+}
+
+impl Drop for TempPackages {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.root);
+    }
+}
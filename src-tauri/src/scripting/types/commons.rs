@@ -1,17 +1,8 @@
 use std::sync::Arc;
 
-use rhai::{EvalAltResult, FnPtr, AST};
+use rhai::{FnPtr, AST};
 
-pub struct ContentScript {
-    /// If Some, holds the compilation result of the script
-    pub ast: Option<Result<Arc<AST>, Box<EvalAltResult>>>,
-}
-
-impl ContentScript {
-    pub fn new() -> Self {
-        Self { ast: None }
-    }
-}
+use crate::scripting::host::ScriptHost;
 
 #[derive(Debug, Clone)]
 pub struct FnPtrWithAst {
@@ -25,17 +16,14 @@ impl FnPtrWithAst {
     }
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone)]
 pub struct CallbackContext {
-    pub app_handle: tauri::AppHandle,
+    pub host: Arc<dyn ScriptHost>,
     pub frontend_tab: String,
 }
 
 impl CallbackContext {
-    pub fn new(app_handle: tauri::AppHandle, frontend_tab: String) -> Self {
-        Self {
-            app_handle,
-            frontend_tab,
-        }
+    pub fn new(host: Arc<dyn ScriptHost>, frontend_tab: String) -> Self {
+        Self { host, frontend_tab }
     }
 }
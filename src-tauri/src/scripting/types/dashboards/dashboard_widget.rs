@@ -0,0 +1,103 @@
+use kube::api::GroupVersionKind;
+use rhai::EvalAltResult;
+
+use crate::scripting::types::ResourceKind;
+
+/// A dashboard tile that summarizes all resources of one kind, e.g. the Pods that are not running.
+///
+/// `render` receives an array of all resources of `source` in the cluster and is called again whenever they changed.
+#[derive(Clone, Debug, rhai::CustomType)]
+#[rhai_type(extra = Self::build_extra)]
+pub struct DashboardWidget {
+    #[rhai_type(readonly)]
+    pub title: String,
+
+    #[rhai_type(skip)]
+    pub source: GroupVersionKind,
+
+    #[rhai_type(readonly)]
+    pub render: rhai::FnPtr,
+}
+
+impl DashboardWidget {
+    fn build_extra(builder: &mut rhai::TypeBuilder<Self>) {
+        builder.with_fn("DashboardWidget", <rhai::Map as TryInto<Self>>::try_into);
+    }
+}
+
+impl TryFrom<rhai::Map> for DashboardWidget {
+    type Error = Box<EvalAltResult>;
+
+    fn try_from(mut value: rhai::Map) -> Result<Self, Self::Error> {
+        let title = value
+            .remove("title")
+            .ok_or_else(|| "DashboardWidget: missing `title`".to_owned())?
+            .into_string()
+            .map_err(|_| "DashboardWidget: `title` must be a string".to_owned())?;
+
+        let source = value
+            .remove("source")
+            .ok_or_else(|| "DashboardWidget: missing `source`".to_owned())?
+            .try_cast::<ResourceKind>()
+            .ok_or("DashboardWidget: `source` must be a ResourceKind".to_owned())?
+            .try_into()
+            .map_err(|e| format!("DashboardWidget: invalid `source`: {e}"))?;
+
+        let render = value
+            .remove("render")
+            .ok_or_else(|| "DashboardWidget: missing `render`".to_owned())?
+            .try_cast::<rhai::FnPtr>()
+            .ok_or("DashboardWidget: `render` must be a function".to_owned())?;
+
+        Ok(Self {
+            title,
+            source,
+            render,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pods() -> rhai::Dynamic {
+        rhai::Dynamic::from(ResourceKind {
+            api_version: "v1".into(),
+            kind: "Pod".into(),
+        })
+    }
+
+    #[test]
+    pub fn test_all_keys_given() {
+        let map = rhai::Map::from_iter([
+            ("title".into(), "Pods not Running".into()),
+            ("source".into(), pods()),
+            ("render".into(), rhai::FnPtr::new("x").unwrap().into()),
+        ]);
+
+        let widget: DashboardWidget = map.try_into().unwrap();
+
+        assert_eq!("Pods not Running", widget.title);
+        assert_eq!(GroupVersionKind::gvk("", "v1", "Pod"), widget.source);
+    }
+
+    #[test]
+    pub fn test_err_on_missing_or_invalid_keys() {
+        let without_source = rhai::Map::from_iter([
+            ("title".into(), "Pods".into()),
+            ("render".into(), rhai::FnPtr::new("x").unwrap().into()),
+        ]);
+        let invalid_source = rhai::Map::from_iter([
+            ("title".into(), "Pods".into()),
+            ("source".into(), "v1/Pod".into()),
+            ("render".into(), rhai::FnPtr::new("x").unwrap().into()),
+        ]);
+        let without_render =
+            rhai::Map::from_iter([("title".into(), "Pods".into()), ("source".into(), pods())]);
+
+        assert!(TryInto::<DashboardWidget>::try_into(without_source).is_err());
+        assert!(TryInto::<DashboardWidget>::try_into(invalid_source).is_err());
+        assert!(TryInto::<DashboardWidget>::try_into(without_render).is_err());
+    }
+}
@@ -0,0 +1,18 @@
+mod dashboard_widget;
+mod stat;
+mod widget_component;
+
+pub use dashboard_widget::*;
+pub use stat::*;
+pub use widget_component::*;
+
+use crate::scripting::types::resource_details::{KeyValueGrid, ResourceLinkList};
+
+/// Registers the types that dashboard scripts use to describe their widgets.
+pub fn register_types(engine: &mut rhai::Engine) {
+    engine
+        .build_type::<DashboardWidget>()
+        .build_type::<Stat>()
+        .build_type::<KeyValueGrid>()
+        .build_type::<ResourceLinkList>();
+}
@@ -0,0 +1,58 @@
+use rhai::{CustomType, EvalAltResult};
+use serde::Serialize;
+
+use crate::scripting::types::{
+    resource_details::ResourceLinkList, resource_presentations::PresentationComponent, Properties,
+};
+
+/// A single prominent value, e.g. the number of NotReady nodes, optionally with links to the resources it counts.
+///
+/// ```rhai
+/// Stat(failing.len(), #{ color: "crimson", items: failing.map(|pod| ResourceRef(pod)) })
+/// ```
+#[derive(Clone, Serialize, CustomType)]
+#[rhai_type(extra = Self::build_extra)]
+pub struct Stat {
+    pub value: String,
+    pub items: Vec<PresentationComponent>,
+    pub properties: Option<Properties>,
+}
+
+impl Stat {
+    pub fn new_with_options(
+        value: rhai::Dynamic,
+        mut options: rhai::Map,
+    ) -> Result<Self, Box<EvalAltResult>> {
+        let items = match options.remove("items") {
+            None => Vec::new(),
+            Some(items) => {
+                let items = items
+                    .into_array()
+                    .map_err(|_| "Stat: `items` must be an array".to_owned())?;
+
+                ResourceLinkList::new(items)
+                    .map_err(|e| format!("Stat: invalid `items`: {e}"))?
+                    .links
+            }
+        };
+
+        Ok(Self {
+            value: value.to_string(),
+            items,
+            properties: Some(options.into()),
+        })
+    }
+
+    pub fn new(value: rhai::Dynamic) -> Self {
+        Self {
+            value: value.to_string(),
+            items: Vec::new(),
+            properties: None,
+        }
+    }
+
+    fn build_extra(builder: &mut rhai::TypeBuilder<Self>) {
+        builder.with_fn("Stat", Self::new_with_options);
+        builder.with_fn("Stat", Self::new);
+    }
+}
@@ -0,0 +1,68 @@
+use serde::Serialize;
+
+use crate::scripting::types::{
+    dashboards::Stat,
+    resource_details::{KeyValueGrid, ResourceLinkList},
+};
+
+/// What the `render` function of a [DashboardWidget](super::DashboardWidget) returned.
+#[derive(Clone, Serialize)]
+#[serde(tag = "kind", content = "args")]
+pub enum WidgetComponent {
+    Stat(Stat),
+    KeyValueGrid(KeyValueGrid),
+    ResourceLinkList(ResourceLinkList),
+}
+
+impl WidgetComponent {
+    /// Numbers and strings are displayed as [Stat] and maps as [KeyValueGrid].
+    pub fn from_dynamic(value: rhai::Dynamic) -> Result<Self, String> {
+        if value.is::<Stat>() {
+            return Ok(Self::Stat(value.cast::<Stat>()));
+        }
+
+        if value.is::<KeyValueGrid>() {
+            return Ok(Self::KeyValueGrid(value.cast::<KeyValueGrid>()));
+        }
+
+        if value.is::<ResourceLinkList>() {
+            return Ok(Self::ResourceLinkList(value.cast::<ResourceLinkList>()));
+        }
+
+        if value.is_int() || value.is_float() || value.is_string() {
+            return Ok(Self::Stat(Stat::new(value)));
+        }
+
+        if value.is_map() {
+            return Ok(Self::KeyValueGrid(KeyValueGrid::from_map(value.cast())));
+        }
+
+        Err(format!(
+            "A dashboard widget must render a Stat, KeyValueGrid, ResourceLinkList, number, string or map, got {}",
+            value.type_name()
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    pub fn test_plain_values() {
+        let count = WidgetComponent::from_dynamic(3.into()).unwrap();
+        let grid = WidgetComponent::from_dynamic(
+            rhai::Map::from_iter([("Warnings".into(), 2.into())]).into(),
+        )
+        .unwrap();
+
+        assert_eq!(
+            json!({ "kind": "Stat", "args": { "value": "3", "items": [], "properties": null } }),
+            serde_json::to_value(count).unwrap()
+        );
+        assert!(matches!(grid, WidgetComponent::KeyValueGrid(_)));
+        assert!(WidgetComponent::from_dynamic(true.into()).is_err());
+    }
+}
@@ -3,7 +3,9 @@ mod resource_kind;
 mod resource_ref;
 
 pub(super) mod commons;
+pub mod dashboards;
 pub mod resource_context_menus;
+pub mod resource_details;
 pub mod resource_presentations;
 
 pub use properties::*;
@@ -1,5 +1,9 @@
 use rhai::EvalAltResult;
 
+/// A section of the context menu of a resource.
+///
+/// `items` builds the menu of a single resource, `multi_items` the menu of a selection of resources of the same kind.
+/// At least one of them must be given.
 #[derive(Clone, Debug, rhai::CustomType)]
 #[rhai_type(extra = Self::build_extra)]
 pub struct MenuSection {
@@ -10,7 +14,10 @@ pub struct MenuSection {
     pub matcher: Option<rhai::FnPtr>,
 
     #[rhai_type(readonly)]
-    pub items: rhai::FnPtr,
+    pub items: Option<rhai::FnPtr>,
+
+    #[rhai_type(readonly)]
+    pub multi_items: Option<rhai::FnPtr>,
 }
 
 impl MenuSection {
@@ -41,14 +48,29 @@ impl TryFrom<rhai::Map> for MenuSection {
 
         let items = value
             .remove("items")
-            .ok_or_else(|| "MenuSection: missing `items`".to_owned())?
-            .try_cast::<rhai::FnPtr>()
-            .ok_or("MenuSection: `items` must be a function".to_owned())?;
+            .map(|v| {
+                v.try_cast::<rhai::FnPtr>()
+                    .ok_or("MenuSection: `items` must be a function".to_owned())
+            })
+            .transpose()?;
+
+        let multi_items = value
+            .remove("multi_items")
+            .map(|v| {
+                v.try_cast::<rhai::FnPtr>()
+                    .ok_or("MenuSection: `multi_items` must be a function".to_owned())
+            })
+            .transpose()?;
+
+        if items.is_none() && multi_items.is_none() {
+            return Err("MenuSection: missing `items` or `multi_items`".into());
+        }
 
         Ok(Self {
             title,
             matcher,
             items,
+            multi_items,
         })
     }
 }
@@ -81,12 +103,26 @@ mod tests {
 
         assert!(section.title.is_none());
         assert!(section.matcher.is_none());
+        assert!(section.multi_items.is_none());
+    }
+
+    #[test]
+    pub fn test_multi_items_only() {
+        let map =
+            rhai::Map::from_iter([("multi_items".into(), rhai::FnPtr::new("y").unwrap().into())]);
+
+        let section: MenuSection = map.try_into().unwrap();
+
+        assert!(section.items.is_none());
+        assert!(section.multi_items.is_some());
     }
 
     #[test]
     pub fn test_err_on_missing_required_keys() {
         let without_items = rhai::Map::from_iter([("items".into(), Vec::<Dynamic>::new().into())]);
+        let without_any_items = rhai::Map::from_iter([("title".into(), "Fancy section".into())]);
 
         assert!(TryInto::<MenuSection>::try_into(without_items).is_err());
+        assert!(TryInto::<MenuSection>::try_into(without_any_items).is_err());
     }
 }
@@ -0,0 +1,80 @@
+use rhai::{CustomType, EvalAltResult};
+use serde::{Deserialize, Serialize};
+
+/// A single entry of `status.conditions`.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct Condition {
+    #[serde(rename = "type")]
+    pub kind: String,
+    pub status: String,
+    #[serde(default)]
+    pub reason: Option<String>,
+    #[serde(default)]
+    pub message: Option<String>,
+    #[serde(default)]
+    pub last_transition_time: Option<String>,
+}
+
+/// Displays the `status.conditions` of a resource as a table. `()` means no conditions.
+#[derive(Clone, Serialize, CustomType)]
+#[rhai_type(extra = Self::build_extra)]
+pub struct ConditionTable {
+    pub conditions: Vec<Condition>,
+}
+
+impl ConditionTable {
+    pub fn new(conditions: rhai::Dynamic) -> Result<Self, Box<EvalAltResult>> {
+        if conditions.is_unit() {
+            return Ok(Self {
+                conditions: Vec::new(),
+            });
+        }
+
+        let conditions = rhai::serde::from_dynamic(&conditions)
+            .map_err(|e| format!("ConditionTable: invalid conditions: {e}"))?;
+
+        Ok(Self { conditions })
+    }
+
+    fn build_extra(builder: &mut rhai::TypeBuilder<Self>) {
+        builder.with_fn("ConditionTable", Self::new);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    pub fn test_conditions_from_status() {
+        let conditions = rhai::serde::to_dynamic(json!([
+            { "type": "Available", "status": "True", "lastTransitionTime": "2024-01-01T00:00:00Z" },
+            { "type": "Progressing", "status": "False", "reason": "ProgressDeadlineExceeded" }
+        ]))
+        .unwrap();
+
+        let table = ConditionTable::new(conditions).unwrap();
+
+        assert_eq!(2, table.conditions.len());
+        assert_eq!("Available", table.conditions[0].kind);
+        assert_eq!(
+            Some("ProgressDeadlineExceeded".to_owned()),
+            table.conditions[1].reason
+        );
+        assert!(ConditionTable::new(rhai::Dynamic::UNIT)
+            .unwrap()
+            .conditions
+            .is_empty());
+    }
+
+    #[test]
+    pub fn test_err_on_invalid_conditions() {
+        let without_status = rhai::serde::to_dynamic(json!([{ "type": "Available" }])).unwrap();
+
+        assert!(ConditionTable::new(without_status).is_err());
+        assert!(ConditionTable::new("Available".into()).is_err());
+    }
+}
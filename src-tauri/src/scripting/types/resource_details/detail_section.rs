@@ -0,0 +1,38 @@
+use rhai::EvalAltResult;
+
+/// A titled section of a details panel. `render` receives the resource and returns a [SectionComponent].
+#[derive(Clone, Debug, rhai::CustomType)]
+#[rhai_type(extra = Self::build_extra)]
+pub struct DetailSection {
+    #[rhai_type(readonly)]
+    pub title: String,
+
+    #[rhai_type(readonly)]
+    pub render: rhai::FnPtr,
+}
+
+impl DetailSection {
+    fn build_extra(builder: &mut rhai::TypeBuilder<Self>) {
+        builder.with_fn("DetailSection", <rhai::Map as TryInto<Self>>::try_into);
+    }
+}
+
+impl TryFrom<rhai::Map> for DetailSection {
+    type Error = Box<EvalAltResult>;
+
+    fn try_from(mut value: rhai::Map) -> Result<Self, Self::Error> {
+        let title = value
+            .remove("title")
+            .ok_or_else(|| "DetailSection: missing `title`".to_owned())?
+            .into_string()
+            .map_err(|_| "DetailSection: `title` must be a string".to_owned())?;
+
+        let render = value
+            .remove("render")
+            .ok_or_else(|| "DetailSection: missing `render`".to_owned())?
+            .try_cast::<rhai::FnPtr>()
+            .ok_or("DetailSection: `render` must be a function".to_owned())?;
+
+        Ok(Self { title, render })
+    }
+}
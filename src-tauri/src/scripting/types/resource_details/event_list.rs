@@ -0,0 +1,65 @@
+use rhai::{CustomType, EvalAltResult};
+use serde::Serialize;
+
+const DEFAULT_MAX_EVENTS: usize = 20;
+
+/// A Kubernetes event that concerns the displayed resource.
+#[derive(Clone, Debug, Serialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct EventRow {
+    #[serde(rename = "type")]
+    pub kind: Option<String>,
+    pub reason: Option<String>,
+    pub message: Option<String>,
+    pub count: Option<i32>,
+    pub last_seen: Option<String>,
+}
+
+/// Displays the most recent events of the resource.
+///
+/// Scripts only declare the section; the events are fetched after rendering, see
+/// [`crate::frontend_commands::render_resource_details`].
+#[derive(Clone, Serialize, CustomType)]
+#[rhai_type(extra = Self::build_extra)]
+pub struct EventList {
+    pub max: usize,
+    pub events: Vec<EventRow>,
+}
+
+impl Default for EventList {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl EventList {
+    pub fn new() -> Self {
+        Self {
+            max: DEFAULT_MAX_EVENTS,
+            events: Vec::new(),
+        }
+    }
+
+    pub fn new_with_options(options: rhai::Map) -> Result<Self, Box<EvalAltResult>> {
+        let max = options
+            .get("max")
+            .map(|v| {
+                v.as_int()
+                    .ok()
+                    .and_then(|max| usize::try_from(max).ok())
+                    .ok_or("EventList: `max` must be a positive integer".to_owned())
+            })
+            .transpose()?
+            .unwrap_or(DEFAULT_MAX_EVENTS);
+
+        Ok(Self {
+            max,
+            events: Vec::new(),
+        })
+    }
+
+    fn build_extra(builder: &mut rhai::TypeBuilder<Self>) {
+        builder.with_fn("EventList", Self::new);
+        builder.with_fn("EventList", Self::new_with_options);
+    }
+}
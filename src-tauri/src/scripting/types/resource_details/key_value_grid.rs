@@ -0,0 +1,107 @@
+use rhai::{CustomType, EvalAltResult};
+use serde::Serialize;
+
+use crate::scripting::types::resource_presentations::{
+    PresentationComponent, ResourcePresentationField,
+};
+
+#[derive(Clone, Serialize)]
+pub struct KeyValueRow {
+    pub key: String,
+    pub value: PresentationComponent,
+}
+
+/// Displays labelled values, e.g. the node and IP of a Pod. Values may be any presentation component.
+///
+/// Rows are taken from a map (sorted by key) or from an array of `[key, value]` pairs (in the given order).
+/// Rows with a value of `()` are left out, so optional fields can be used as they are.
+#[derive(Clone, Serialize, CustomType)]
+#[rhai_type(extra = Self::build_extra)]
+pub struct KeyValueGrid {
+    pub rows: Vec<KeyValueRow>,
+}
+
+impl KeyValueGrid {
+    pub fn from_map(entries: rhai::Map) -> Self {
+        Self::from_rows(
+            entries
+                .into_iter()
+                .map(|(key, value)| (key.to_string(), value)),
+        )
+    }
+
+    pub fn from_pairs(pairs: rhai::Array) -> Result<Self, Box<EvalAltResult>> {
+        let pairs = pairs
+            .into_iter()
+            .map(|pair| {
+                let pair = pair
+                    .into_array()
+                    .ok()
+                    .filter(|pair| pair.len() == 2)
+                    .ok_or("KeyValueGrid: rows must be `[key, value]` pairs".to_owned())?;
+
+                let [key, value]: [rhai::Dynamic; 2] = pair.try_into().unwrap();
+
+                Ok((key.to_string(), value))
+            })
+            .collect::<Result<Vec<_>, Box<EvalAltResult>>>()?;
+
+        Ok(Self::from_rows(pairs))
+    }
+
+    fn from_rows(rows: impl IntoIterator<Item = (String, rhai::Dynamic)>) -> Self {
+        Self {
+            rows: rows
+                .into_iter()
+                .filter(|(_, value)| !value.is_unit())
+                .map(|(key, value)| KeyValueRow {
+                    key,
+                    value: ResourcePresentationField::from_dynamic(value).into(),
+                })
+                .collect(),
+        }
+    }
+
+    fn build_extra(builder: &mut rhai::TypeBuilder<Self>) {
+        builder.with_fn("KeyValueGrid", Self::from_map);
+        builder.with_fn("KeyValueGrid", Self::from_pairs);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::scripting::types::resource_presentations::Text;
+
+    use super::*;
+
+    #[test]
+    pub fn test_pairs_keep_order_and_skip_unit() {
+        let grid = KeyValueGrid::from_pairs(rhai::Array::from([
+            rhai::Array::from(["Node".into(), "node-1".into()]).into(),
+            rhai::Array::from(["IP".into(), rhai::Dynamic::UNIT]).into(),
+            rhai::Array::from([
+                "Phase".into(),
+                rhai::Dynamic::from(Text::new("Running".into())),
+            ])
+            .into(),
+        ]))
+        .unwrap();
+
+        let rows: Vec<(&str, &str)> = grid
+            .rows
+            .iter()
+            .map(|row| (row.key.as_str(), row.value.sortable_value.as_str()))
+            .collect();
+
+        assert_eq!(vec![("Node", "node-1"), ("Phase", "Running")], rows);
+    }
+
+    #[test]
+    pub fn test_err_on_invalid_pairs() {
+        let single = rhai::Array::from([rhai::Array::from(["Node".into()]).into()]);
+        let not_an_array = rhai::Array::from(["Node".into()]);
+
+        assert!(KeyValueGrid::from_pairs(single).is_err());
+        assert!(KeyValueGrid::from_pairs(not_an_array).is_err());
+    }
+}
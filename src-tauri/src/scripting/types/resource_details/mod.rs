@@ -0,0 +1,26 @@
+mod condition_table;
+mod detail_section;
+mod event_list;
+mod key_value_grid;
+mod resource_detail;
+mod resource_link_list;
+mod section_component;
+
+pub use condition_table::*;
+pub use detail_section::*;
+pub use event_list::*;
+pub use key_value_grid::*;
+pub use resource_detail::*;
+pub use resource_link_list::*;
+pub use section_component::*;
+
+/// Registers the types that detail scripts use to describe their sections.
+pub fn register_types(engine: &mut rhai::Engine) {
+    engine
+        .build_type::<ResourceDetails>()
+        .build_type::<DetailSection>()
+        .build_type::<KeyValueGrid>()
+        .build_type::<ConditionTable>()
+        .build_type::<ResourceLinkList>()
+        .build_type::<EventList>();
+}
@@ -0,0 +1,104 @@
+use rhai::EvalAltResult;
+
+use crate::scripting::types::resource_details::DetailSection;
+
+#[derive(Clone, Debug, rhai::CustomType)]
+#[rhai_type(extra = Self::build_extra)]
+pub struct ResourceDetails {
+    #[rhai_type(readonly)]
+    pub title: String,
+
+    #[rhai_type(readonly)]
+    pub matcher: Option<rhai::FnPtr>,
+
+    #[rhai_type(readonly)]
+    pub sections: Vec<DetailSection>,
+}
+
+impl ResourceDetails {
+    fn build_extra(builder: &mut rhai::TypeBuilder<Self>) {
+        builder.with_fn("ResourceDetails", <rhai::Map as TryInto<Self>>::try_into);
+    }
+}
+
+impl TryFrom<rhai::Map> for ResourceDetails {
+    type Error = Box<EvalAltResult>;
+
+    fn try_from(mut value: rhai::Map) -> Result<Self, Self::Error> {
+        let title = value
+            .remove("title")
+            .ok_or_else(|| "ResourceDetails: missing `title`".to_owned())?
+            .into_string()
+            .map_err(|_| "ResourceDetails: `title` must be a string".to_owned())?;
+
+        let matcher = value
+            .remove("matcher")
+            .map(|v| {
+                v.try_cast::<rhai::FnPtr>()
+                    .ok_or("ResourceDetails: `matcher` must be a function".to_owned())
+            })
+            .transpose()?;
+
+        let sections = value
+            .remove("sections")
+            .ok_or_else(|| "ResourceDetails: missing `sections`".to_owned())?
+            .into_array()
+            .map_err(|_| {
+                "ResourceDetails: `sections` must be an array of DetailSection".to_owned()
+            })?
+            .into_iter()
+            .map(|section| {
+                section
+                    .try_cast::<DetailSection>()
+                    .ok_or("ResourceDetails: `sections` must only contain DetailSection".to_owned())
+            })
+            .collect::<Result<_, _>>()?;
+
+        Ok(Self {
+            title,
+            matcher,
+            sections,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn section() -> rhai::Dynamic {
+        rhai::Dynamic::from(DetailSection {
+            title: "Conditions".into(),
+            render: rhai::FnPtr::new("y").unwrap(),
+        })
+    }
+
+    #[test]
+    pub fn test_all_keys_given() {
+        let map = rhai::Map::from_iter([
+            ("title".into(), "Deployment".into()),
+            ("matcher".into(), rhai::FnPtr::new("x").unwrap().into()),
+            ("sections".into(), rhai::Array::from([section()]).into()),
+        ]);
+
+        let details: ResourceDetails = map.try_into().unwrap();
+
+        assert_eq!("Deployment", details.title);
+        assert!(details.matcher.is_some());
+        assert_eq!(1, details.sections.len());
+    }
+
+    #[test]
+    pub fn test_err_on_missing_or_invalid_keys() {
+        let without_title = rhai::Map::from_iter([("sections".into(), rhai::Array::new().into())]);
+        let without_sections = rhai::Map::from_iter([("title".into(), "Deployment".into())]);
+        let invalid_section = rhai::Map::from_iter([
+            ("title".into(), "Deployment".into()),
+            ("sections".into(), rhai::Array::from(["x".into()]).into()),
+        ]);
+
+        assert!(TryInto::<ResourceDetails>::try_into(without_title).is_err());
+        assert!(TryInto::<ResourceDetails>::try_into(without_sections).is_err());
+        assert!(TryInto::<ResourceDetails>::try_into(invalid_section).is_err());
+    }
+}
@@ -0,0 +1,43 @@
+use rhai::{CustomType, EvalAltResult};
+use serde::Serialize;
+
+use crate::scripting::types::{
+    resource_presentations::{PresentationComponent, ResourceLink},
+    ResourceRef,
+};
+
+/// Displays links to other resources, e.g. the Secrets a Pod mounts.
+///
+/// Items are [ResourceRef]s or [ResourceLink]s, the latter to show something other than the name.
+#[derive(Clone, Serialize, CustomType)]
+#[rhai_type(extra = Self::build_extra)]
+pub struct ResourceLinkList {
+    pub links: Vec<PresentationComponent>,
+}
+
+impl ResourceLinkList {
+    pub fn new(items: rhai::Array) -> Result<Self, Box<EvalAltResult>> {
+        let links = items
+            .into_iter()
+            .map(|item| {
+                if item.is::<ResourceLink>() {
+                    return Ok(item.cast::<ResourceLink>().into());
+                }
+
+                item.try_cast::<ResourceRef>()
+                    .map(|resource_ref| ResourceLink::new(resource_ref).into())
+                    .ok_or_else(|| {
+                        "ResourceLinkList: items must be ResourceRef or ResourceLink"
+                            .to_owned()
+                            .into()
+                    })
+            })
+            .collect::<Result<_, Box<EvalAltResult>>>()?;
+
+        Ok(Self { links })
+    }
+
+    fn build_extra(builder: &mut rhai::TypeBuilder<Self>) {
+        builder.with_fn("ResourceLinkList", Self::new);
+    }
+}
@@ -0,0 +1,45 @@
+use serde::Serialize;
+
+use crate::scripting::types::resource_details::{
+    ConditionTable, EventList, KeyValueGrid, ResourceLinkList,
+};
+
+/// What the `render` function of a [DetailSection](super::DetailSection) returned.
+#[derive(Clone, Serialize)]
+#[serde(tag = "kind", content = "args")]
+pub enum SectionComponent {
+    KeyValueGrid(KeyValueGrid),
+    ConditionTable(ConditionTable),
+    ResourceLinkList(ResourceLinkList),
+    EventList(EventList),
+}
+
+impl SectionComponent {
+    /// A plain map is displayed as [KeyValueGrid], anything else that is not a section component is an error.
+    pub fn from_dynamic(value: rhai::Dynamic) -> Result<Self, String> {
+        if value.is::<KeyValueGrid>() {
+            return Ok(Self::KeyValueGrid(value.cast::<KeyValueGrid>()));
+        }
+
+        if value.is::<ConditionTable>() {
+            return Ok(Self::ConditionTable(value.cast::<ConditionTable>()));
+        }
+
+        if value.is::<ResourceLinkList>() {
+            return Ok(Self::ResourceLinkList(value.cast::<ResourceLinkList>()));
+        }
+
+        if value.is::<EventList>() {
+            return Ok(Self::EventList(value.cast::<EventList>()));
+        }
+
+        if value.is_map() {
+            return Ok(Self::KeyValueGrid(KeyValueGrid::from_map(value.cast())));
+        }
+
+        Err(format!(
+            "A detail section must render a KeyValueGrid, ConditionTable, ResourceLinkList, EventList or map, got {}",
+            value.type_name()
+        ))
+    }
+}
@@ -0,0 +1,245 @@
+use rhai::{CustomType, EvalAltResult};
+use serde::Serialize;
+use serde_json::json;
+
+use crate::scripting::types::{resource_presentations::PresentationComponent, Properties};
+
+/// A single `key=value` pair of a [Chips] component.
+#[derive(Clone, Debug, Serialize, PartialEq)]
+pub struct Chip {
+    pub key: String,
+    pub value: String,
+}
+
+impl Chip {
+    /// The label selector that matches this chip, e.g. `app=web`.
+    pub fn selector(&self) -> String {
+        format!("{}={}", self.key, self.value)
+    }
+}
+
+/// Displays the entries of a map (e.g. labels or annotations) as a list of chips.
+///
+/// Keys can be filtered with include/exclude glob patterns (`*` matches any sequence of characters) and the
+/// number of visible chips can be limited. Clicking a chip filters the column by its `key=value` selector.
+#[derive(Clone, Serialize, CustomType)]
+#[rhai_type(extra = Self::build_extra)]
+pub struct Chips {
+    pub chips: Vec<Chip>,
+    pub max_visible: Option<usize>,
+    pub properties: Option<Properties>,
+}
+
+impl Chips {
+    pub fn new_with_options(
+        entries: rhai::Dynamic,
+        options: rhai::Map,
+    ) -> Result<Self, Box<EvalAltResult>> {
+        let include = patterns(&options, "include")?;
+        let exclude = patterns(&options, "exclude")?;
+
+        let max_visible = options
+            .get("max")
+            .map(|v| {
+                v.as_int()
+                    .ok()
+                    .and_then(|max| usize::try_from(max).ok())
+                    .ok_or("Chips: `max` must be a positive integer".to_owned())
+            })
+            .transpose()?;
+
+        let chips = Self::new(entries)?
+            .chips
+            .into_iter()
+            .filter(|chip| include.is_empty() || include.iter().any(|p| glob_match(p, &chip.key)))
+            .filter(|chip| !exclude.iter().any(|p| glob_match(p, &chip.key)))
+            .collect();
+
+        Ok(Self {
+            chips,
+            max_visible,
+            properties: Some(options.into()),
+        })
+    }
+
+    pub fn new(entries: rhai::Dynamic) -> Result<Self, Box<EvalAltResult>> {
+        // Missing labels or annotations are common, so `()` just means "no chips"
+        if entries.is_unit() {
+            return Ok(Self {
+                chips: Vec::new(),
+                max_visible: None,
+                properties: None,
+            });
+        }
+
+        let entries = entries
+            .try_cast::<rhai::Map>()
+            .ok_or("Chips: entries must be a map".to_owned())?;
+
+        // rhai::Map is a BTreeMap, so chips are already sorted by key
+        let chips = entries
+            .into_iter()
+            .map(|(key, value)| Chip {
+                key: key.to_string(),
+                value: value.to_string(),
+            })
+            .collect();
+
+        Ok(Self {
+            chips,
+            max_visible: None,
+            properties: None,
+        })
+    }
+
+    fn build_extra(builder: &mut rhai::TypeBuilder<Self>) {
+        builder.with_fn("Chips", Self::new_with_options);
+        builder.with_fn("Chips", Self::new);
+    }
+}
+
+impl From<Chips> for PresentationComponent {
+    fn from(value: Chips) -> Self {
+        let visible = value.max_visible.unwrap_or(value.chips.len());
+        let hidden = value.chips.len().saturating_sub(visible);
+
+        // All selectors, including truncated ones, so that column filters still find them
+        let selectors: Vec<String> = value.chips.iter().map(Chip::selector).collect();
+
+        Self {
+            kind: "Chips",
+            sortable_value: selectors.join(", "),
+            args: json!({
+                "chips": value.chips.into_iter().take(visible).collect::<Vec<_>>(),
+                "hidden": hidden,
+                "selectors": selectors,
+            }),
+            properties: value.properties,
+        }
+    }
+}
+
+fn patterns(options: &rhai::Map, key: &str) -> Result<Vec<String>, Box<EvalAltResult>> {
+    let Some(value) = options.get(key) else {
+        return Ok(Vec::new());
+    };
+
+    if value.is_string() {
+        return Ok(vec![value.to_string()]);
+    }
+
+    value
+        .as_array_ref()
+        .map_err(|_| format!("Chips: `{key}` must be a string or an array of strings"))?
+        .iter()
+        .map(|pattern| {
+            pattern
+                .clone()
+                .into_string()
+                .map_err(|_| format!("Chips: `{key}` must only contain strings").into())
+        })
+        .collect()
+}
+
+/// Matches `text` against a glob `pattern` in which `*` matches any (possibly empty) sequence.
+fn glob_match(pattern: &str, text: &str) -> bool {
+    let mut parts = pattern.split('*');
+
+    let Some(prefix) = parts.next() else {
+        return true;
+    };
+
+    let Some(mut rest) = text.strip_prefix(prefix) else {
+        return false;
+    };
+
+    let parts: Vec<&str> = parts.collect();
+
+    let Some((suffix, middle)) = parts.split_last() else {
+        // No `*` at all, the whole text must have been consumed by the prefix
+        return rest.is_empty();
+    };
+
+    for part in middle {
+        match rest.find(part) {
+            Some(position) => rest = &rest[position + part.len()..],
+            None => return false,
+        }
+    }
+
+    rest.ends_with(suffix)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn labels() -> rhai::Dynamic {
+        rhai::Map::from_iter([
+            ("app".into(), "web".into()),
+            ("app.kubernetes.io/name".into(), "shop".into()),
+            ("app.kubernetes.io/version".into(), "1.2.3".into()),
+            ("pod-template-hash".into(), "5d8f7c".into()),
+        ])
+        .into()
+    }
+
+    #[test]
+    pub fn test_glob_match() {
+        assert!(glob_match("app", "app"));
+        assert!(!glob_match("app", "apps"));
+        assert!(glob_match("app.kubernetes.io/*", "app.kubernetes.io/name"));
+        assert!(glob_match("*hash", "pod-template-hash"));
+        assert!(glob_match("*kubernetes*", "app.kubernetes.io/name"));
+        assert!(!glob_match("*kubernetes*name", "app.kubernetes.io/version"));
+        assert!(glob_match("*", ""));
+    }
+
+    #[test]
+    pub fn test_unit_means_no_chips() {
+        let chips = Chips::new(rhai::Dynamic::UNIT).unwrap();
+
+        assert!(chips.chips.is_empty());
+    }
+
+    #[test]
+    pub fn test_include_and_exclude() {
+        let options = rhai::Map::from_iter([
+            ("include".into(), "app*".into()),
+            (
+                "exclude".into(),
+                rhai::Array::from(["*/version".into()]).into(),
+            ),
+        ]);
+
+        let chips = Chips::new_with_options(labels(), options).unwrap();
+        let keys: Vec<&str> = chips.chips.iter().map(|c| c.key.as_str()).collect();
+
+        assert_eq!(vec!["app", "app.kubernetes.io/name"], keys);
+    }
+
+    #[test]
+    pub fn test_truncation_keeps_all_selectors_sortable() {
+        let options = rhai::Map::from_iter([("max".into(), 1.into())]);
+
+        let component: PresentationComponent =
+            Chips::new_with_options(labels(), options).unwrap().into();
+
+        assert_eq!(1, component.args["chips"].as_array().unwrap().len());
+        assert_eq!(3, component.args["hidden"]);
+        assert!(component
+            .sortable_value
+            .contains("pod-template-hash=5d8f7c"));
+        assert_eq!(4, component.args["selectors"].as_array().unwrap().len());
+        assert_eq!("pod-template-hash=5d8f7c", component.args["selectors"][3]);
+    }
+
+    #[test]
+    pub fn test_err_on_invalid_options() {
+        let negative_max = rhai::Map::from_iter([("max".into(), (-1).into())]);
+        let invalid_include = rhai::Map::from_iter([("include".into(), 5.into())]);
+
+        assert!(Chips::new_with_options(labels(), negative_max).is_err());
+        assert!(Chips::new_with_options(labels(), invalid_include).is_err());
+    }
+}
@@ -1,17 +1,36 @@
+mod chips;
 mod colored_box;
 mod colored_boxes;
 mod hyperlink;
 mod presentation_component;
+mod progress;
 mod relative_time;
+mod resource_link;
 mod resource_presentation;
 mod resource_presentation_field;
 mod text;
 
+pub use chips::*;
 pub use colored_box::*;
 pub use colored_boxes::*;
 pub use hyperlink::*;
 pub use presentation_component::*;
+pub use progress::*;
 pub use relative_time::*;
+pub use resource_link::*;
 pub use resource_presentation::*;
 pub use resource_presentation_field::*;
 pub use text::*;
+
+/// Registers the components that presentations and detail panels can display.
+pub fn register_components(engine: &mut rhai::Engine) {
+    engine
+        .build_type::<Text>()
+        .build_type::<Hyperlink>()
+        .build_type::<RelativeTime>()
+        .build_type::<ColoredBox>()
+        .build_type::<ColoredBoxes>()
+        .build_type::<Progress>()
+        .build_type::<Chips>()
+        .build_type::<ResourceLink>();
+}
@@ -0,0 +1,221 @@
+use rhai::{CustomType, EvalAltResult};
+use serde::Serialize;
+use serde_json::json;
+
+use crate::scripting::types::{resource_presentations::PresentationComponent, Properties};
+
+/// A color that applies once the filled fraction of a [Progress] reaches `from` (0.0 to 1.0).
+#[derive(Clone, Debug, Serialize, PartialEq)]
+pub struct ProgressThreshold {
+    pub from: f64,
+    pub color: String,
+}
+
+impl TryFrom<rhai::Dynamic> for ProgressThreshold {
+    type Error = Box<EvalAltResult>;
+
+    fn try_from(value: rhai::Dynamic) -> Result<Self, Self::Error> {
+        let value = value
+            .try_cast::<rhai::Map>()
+            .ok_or("Progress: `thresholds` must be an array of maps".to_owned())?;
+
+        let from = value
+            .get("from")
+            .and_then(as_number)
+            .ok_or("Progress: threshold `from` must be a number".to_owned())?;
+
+        let color = value
+            .get("color")
+            .ok_or_else(|| "Progress: threshold is missing `color`".to_owned())?
+            .clone()
+            .into_string()
+            .map_err(|_| "Progress: threshold `color` must be a string".to_owned())?;
+
+        Ok(Self { from, color })
+    }
+}
+
+/// Displays a bar that is filled according to `value` in relation to `max`, e.g. ready vs. desired replicas.
+#[derive(Clone, Serialize, CustomType)]
+#[rhai_type(extra = Self::build_extra)]
+pub struct Progress {
+    pub value: f64,
+    pub max: f64,
+    pub label: Option<String>,
+    pub thresholds: Vec<ProgressThreshold>,
+    pub properties: Option<Properties>,
+}
+
+impl Progress {
+    pub fn new_with_options(
+        value: rhai::Dynamic,
+        max: rhai::Dynamic,
+        mut options: rhai::Map,
+    ) -> Result<Self, Box<EvalAltResult>> {
+        // What remains of the options after taking out the Progress' own ones are its presentation properties
+        let label = options
+            .remove("label")
+            .map(|v| v.to_string())
+            .filter(|label| !label.is_empty());
+
+        let mut thresholds = options
+            .remove("thresholds")
+            .map(|v| {
+                v.as_array_ref()
+                    .map_err(|_| "Progress: `thresholds` must be an array".to_owned())?
+                    .iter()
+                    .cloned()
+                    .map(ProgressThreshold::try_from)
+                    .collect::<Result<Vec<_>, _>>()
+            })
+            .transpose()?
+            .unwrap_or_default();
+
+        thresholds.sort_by(|a, b| a.from.total_cmp(&b.from));
+
+        Ok(Self {
+            label,
+            thresholds,
+            properties: Some(options.into()),
+            ..Self::new(value, max)?
+        })
+    }
+
+    pub fn new(value: rhai::Dynamic, max: rhai::Dynamic) -> Result<Self, Box<EvalAltResult>> {
+        let value = as_number(&value).ok_or("Progress: `value` must be a number".to_owned())?;
+        let max = as_number(&max).ok_or("Progress: `max` must be a number".to_owned())?;
+
+        Ok(Self {
+            value,
+            max,
+            label: None,
+            thresholds: Vec::new(),
+            properties: None,
+        })
+    }
+
+    /// The filled fraction, clamped to 0.0 to 1.0. An empty `max` counts as empty bar.
+    pub fn fraction(&self) -> f64 {
+        if self.max <= 0.0 {
+            return 0.0;
+        }
+
+        (self.value / self.max).clamp(0.0, 1.0)
+    }
+
+    /// The color of the highest threshold that has been reached, if any.
+    pub fn threshold_color(&self) -> Option<&str> {
+        let fraction = self.fraction();
+
+        self.thresholds
+            .iter()
+            .rev()
+            .find(|t| fraction >= t.from)
+            .map(|t| t.color.as_str())
+    }
+
+    fn build_extra(builder: &mut rhai::TypeBuilder<Self>) {
+        builder.with_fn("Progress", Self::new_with_options);
+        builder.with_fn("Progress", Self::new);
+    }
+}
+
+impl From<Progress> for PresentationComponent {
+    fn from(value: Progress) -> Self {
+        let fraction = value.fraction();
+
+        Self {
+            kind: "Progress",
+            args: json!({
+                "value": value.value,
+                "max": value.max,
+                "label": value.label,
+                "thresholds": value.thresholds,
+                "color": value.threshold_color(),
+            }),
+            properties: value.properties,
+            // Integer basis points, so the frontend's numeric string comparison sorts correctly
+            sortable_value: format!("{:.0}", fraction * 10000.0),
+        }
+    }
+}
+
+fn as_number(value: &rhai::Dynamic) -> Option<f64> {
+    value
+        .as_int()
+        .map(|v| v as f64)
+        .or_else(|_| value.as_float())
+        .ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn thresholds() -> rhai::Dynamic {
+        rhai::Array::from([
+            rhai::Map::from_iter([("from".into(), 0.9.into()), ("color".into(), "red".into())])
+                .into(),
+            rhai::Map::from_iter([
+                ("from".into(), 0.5.into()),
+                ("color".into(), "orange".into()),
+            ])
+            .into(),
+        ])
+        .into()
+    }
+
+    #[test]
+    pub fn test_new_accepts_ints_and_floats() {
+        let progress = Progress::new(3.into(), 4.0.into()).unwrap();
+
+        assert_eq!(0.75, progress.fraction());
+        assert!(Progress::new("3".into(), 4.into()).is_err());
+    }
+
+    #[test]
+    pub fn test_fraction_is_clamped() {
+        assert_eq!(1.0, Progress::new(5.into(), 4.into()).unwrap().fraction());
+        assert_eq!(0.0, Progress::new(5.into(), 0.into()).unwrap().fraction());
+    }
+
+    #[test]
+    pub fn test_threshold_color() {
+        let options = rhai::Map::from_iter([("thresholds".into(), thresholds())]);
+
+        let progress = |value: i64| {
+            Progress::new_with_options(value.into(), 100.into(), options.clone()).unwrap()
+        };
+
+        assert_eq!(None, progress(10).threshold_color());
+        assert_eq!(Some("orange"), progress(50).threshold_color());
+        assert_eq!(Some("red"), progress(95).threshold_color());
+    }
+
+    #[test]
+    pub fn test_options_are_not_repeated_as_properties() {
+        let options = rhai::Map::from_iter([
+            ("label".into(), "3/4".into()),
+            ("thresholds".into(), thresholds()),
+            ("title".into(), "Ready replicas".into()),
+        ]);
+
+        let component: PresentationComponent =
+            Progress::new_with_options(3.into(), 4.into(), options)
+                .unwrap()
+                .into();
+
+        assert_eq!(
+            json!({ "color": null, "title": "Ready replicas" }),
+            serde_json::to_value(component.properties).unwrap()
+        );
+        assert_eq!(json!("3/4"), component.args["label"]);
+    }
+
+    #[test]
+    pub fn test_sortable_value_is_numeric() {
+        let component: PresentationComponent = Progress::new(1.into(), 3.into()).unwrap().into();
+
+        assert_eq!("3333", component.sortable_value);
+    }
+}
@@ -0,0 +1,117 @@
+use rhai::CustomType;
+use serde::Serialize;
+use serde_json::json;
+
+use crate::scripting::types::{
+    resource_presentations::PresentationComponent, Properties, ResourceRef,
+};
+
+/// Displays a link that navigates to another resource of the same cluster, e.g. the Node a Pod runs on.
+///
+/// Without an explicit display text the name of the referenced resource is shown.
+#[derive(Clone, Serialize, CustomType)]
+#[rhai_type(extra = Self::build_extra)]
+pub struct ResourceLink {
+    pub resource_ref: ResourceRef,
+    pub content: String,
+    pub properties: Option<Properties>,
+}
+
+impl ResourceLink {
+    pub fn new_with_content(resource_ref: ResourceRef, content: String, props: rhai::Map) -> Self {
+        ResourceLink {
+            resource_ref,
+            content,
+            properties: Some(props.into()),
+        }
+    }
+
+    pub fn new_with_props(resource_ref: ResourceRef, props: rhai::Map) -> Self {
+        ResourceLink {
+            content: resource_ref.name.clone(),
+            resource_ref,
+            properties: Some(props.into()),
+        }
+    }
+
+    pub fn new(resource_ref: ResourceRef) -> Self {
+        ResourceLink {
+            content: resource_ref.name.clone(),
+            resource_ref,
+            properties: None,
+        }
+    }
+
+    fn build_extra(builder: &mut rhai::TypeBuilder<Self>) {
+        builder.with_fn("ResourceLink", Self::new_with_content);
+        builder.with_fn("ResourceLink", Self::new_with_props);
+        builder.with_fn("ResourceLink", Self::new);
+    }
+}
+
+impl From<ResourceLink> for PresentationComponent {
+    fn from(value: ResourceLink) -> Self {
+        let ResourceRef {
+            api_version,
+            kind,
+            namespace,
+            name,
+        } = value.resource_ref;
+
+        // `v1` belongs to the core group, everything else is `<group>/<version>`
+        let (group, version) = api_version.split_once('/').unwrap_or(("", &api_version));
+
+        Self {
+            kind: "ResourceLink",
+            args: json!({
+                "gvk": { "group": group, "version": version, "kind": kind },
+                "namespace": namespace,
+                "name": name,
+                "content": value.content,
+            }),
+            properties: value.properties,
+            sortable_value: value.content,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn resource_ref(api_version: &str, namespace: Option<&str>) -> ResourceRef {
+        ResourceRef {
+            api_version: api_version.to_owned(),
+            kind: "Thing".to_owned(),
+            namespace: namespace.map(ToOwned::to_owned),
+            name: "my-thing".to_owned(),
+        }
+    }
+
+    #[test]
+    pub fn test_core_group() {
+        let component: PresentationComponent = ResourceLink::new(resource_ref("v1", None)).into();
+
+        assert_eq!("", component.args["gvk"]["group"]);
+        assert_eq!("v1", component.args["gvk"]["version"]);
+        assert!(component.args["namespace"].is_null());
+        assert_eq!("my-thing", component.sortable_value);
+    }
+
+    #[test]
+    pub fn test_named_group() {
+        let component: PresentationComponent = ResourceLink::new_with_content(
+            resource_ref("apps/v1", Some("default")),
+            "owner".to_owned(),
+            rhai::Map::new(),
+        )
+        .into();
+
+        assert_eq!("apps", component.args["gvk"]["group"]);
+        assert_eq!("v1", component.args["gvk"]["version"]);
+        assert_eq!("Thing", component.args["gvk"]["kind"]);
+        assert_eq!("default", component.args["namespace"]);
+        assert_eq!("my-thing", component.args["name"]);
+        assert_eq!("owner", component.args["content"]);
+    }
+}
@@ -1,7 +1,8 @@
 use serde::Serialize;
 
 use crate::scripting::types::resource_presentations::{
-    ColoredBox, ColoredBoxes, Hyperlink, PresentationComponent, RelativeTime, Text,
+    Chips, ColoredBox, ColoredBoxes, Hyperlink, PresentationComponent, Progress, RelativeTime,
+    ResourceLink, Text,
 };
 
 #[derive(Clone, Serialize)]
@@ -11,6 +12,51 @@ pub enum ResourcePresentationField {
     Hyperlink(Hyperlink),
     ColoredBox(ColoredBox),
     ColoredBoxes(ColoredBoxes),
+    Progress(Progress),
+    Chips(Chips),
+    ResourceLink(ResourceLink),
+}
+
+impl ResourcePresentationField {
+    /// Picks the component a script returned, anything else is displayed as text.
+    pub fn from_dynamic(value: rhai::Dynamic) -> Self {
+        if value.is::<Text>() {
+            return Self::Text(value.cast::<Text>());
+        }
+
+        if value.is::<Hyperlink>() {
+            return Self::Hyperlink(value.cast::<Hyperlink>());
+        }
+
+        if value.is::<RelativeTime>() {
+            return Self::RelativeTime(value.cast::<RelativeTime>());
+        }
+
+        if value.is::<ColoredBox>() {
+            return Self::ColoredBox(value.cast::<ColoredBox>());
+        }
+
+        if value.is::<ColoredBoxes>() {
+            return Self::ColoredBoxes(value.cast::<ColoredBoxes>());
+        }
+
+        if value.is::<Progress>() {
+            return Self::Progress(value.cast::<Progress>());
+        }
+
+        if value.is::<Chips>() {
+            return Self::Chips(value.cast::<Chips>());
+        }
+
+        if value.is::<ResourceLink>() {
+            return Self::ResourceLink(value.cast::<ResourceLink>());
+        }
+
+        Self::Text(Text {
+            content: value.to_string(),
+            properties: None,
+        })
+    }
 }
 
 impl From<ResourcePresentationField> for PresentationComponent {
@@ -21,6 +67,9 @@ impl From<ResourcePresentationField> for PresentationComponent {
             ResourcePresentationField::Hyperlink(value) => value.into(),
             ResourcePresentationField::ColoredBox(value) => value.into(),
             ResourcePresentationField::ColoredBoxes(value) => value.into(),
+            ResourcePresentationField::Progress(value) => value.into(),
+            ResourcePresentationField::Chips(value) => value.into(),
+            ResourcePresentationField::ResourceLink(value) => value.into(),
         }
     }
 }
@@ -0,0 +1,11 @@
+import { invoke } from "@tauri-apps/api/core";
+
+export type PromptValue = string | number | boolean | null;
+
+export type PromptAnswer =
+    | { outcome: 'submitted', values: Record<string, PromptValue> }
+    | { outcome: 'cancelled' };
+
+export default function answerMenuPrompt(promptId: string, answer: PromptAnswer) {
+    return invoke<void>('answer_menu_prompt', { promptId, answer });
+}
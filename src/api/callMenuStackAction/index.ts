@@ -1,6 +1,19 @@
 import { invoke } from "@tauri-apps/api/core";
 import { KubeContextSource } from "../../hooks/useContextDiscovery";
 
+/**
+ * How a menu action ended. Script errors point at the line that failed.
+ */
+export type ActionOutcome =
+    | { status: 'ok' }
+    | { status: 'error', message: string, script?: string, line?: number, column?: number }
+    | { status: 'batch', results: BatchItemOutcome[] };
+
+/**
+ * How an action of a multi-selection menu ended for one of the selected resources.
+ */
+export type BatchItemOutcome = { namespace: string | null, name: string } & Exclude<ActionOutcome, { status: 'batch' }>;
+
 export default function callMenustackAction(contextSource: KubeContextSource, menustackId: string, actionRef: string) {
-    return invoke<void>("call_menustack_action", { contextSource, menustackId, actionRef });
-}
\ No newline at end of file
+    return invoke<ActionOutcome>("call_menustack_action", { contextSource, menustackId, actionRef });
+}
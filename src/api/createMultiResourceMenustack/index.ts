@@ -0,0 +1,21 @@
+import { invoke } from "@tauri-apps/api/core";
+import { KubeContextSource } from "../../hooks/useContextDiscovery";
+import { Gvk } from "../../model/k8s";
+import { MenuBlueprint } from "../createResourceMenustack";
+
+export type SelectedResource = {
+    namespace: string,
+    name: string,
+};
+
+/**
+ * Builds the menu of batch actions for a selection of resources of the same kind.
+ */
+export default function createMultiResourceMenustack(contextSource: KubeContextSource, tabId: string, gvk: Gvk, resources: SelectedResource[]) {
+    return invoke<MenuBlueprint>("create_multi_resource_menustack", {
+        contextSource,
+        tabId,
+        gvk,
+        resources,
+    });
+}
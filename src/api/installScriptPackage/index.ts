@@ -0,0 +1,7 @@
+import { invoke } from "@tauri-apps/api/core";
+import { ScriptPackage } from "../listScriptPackages";
+
+/** Installs a package from a directory, a `.zip` or a `.tar.gz` archive. */
+export default function installScriptPackage(source: string) {
+    return invoke<ScriptPackage['manifest']>('install_script_package', { source });
+}
@@ -0,0 +1,28 @@
+import { invoke } from "@tauri-apps/api/core";
+import { ClusterProfileId } from "../listClusterProfiles";
+
+export type ScriptPackageCapability = 'kube-read' | 'kube-write' | 'kube-delete' | 'clipboard' | 'frontend';
+
+export type ScriptPackageStatus =
+    | { state: 'active' }
+    | { state: 'disabled' }
+    | { state: 'overridden' }
+    | { state: 'incompatible', reason: string }
+    | { state: 'invalid', reason: string };
+
+export type ScriptPackage = {
+    manifest: {
+        name: string,
+        version: string,
+        description: string | null,
+        minHyprkubeVersion: string | null,
+        capabilities: ScriptPackageCapability[],
+    },
+    source: 'builtin' | 'extension',
+    path: string,
+    status: ScriptPackageStatus,
+};
+
+export default function listScriptPackages(profile: ClusterProfileId) {
+    return invoke<ScriptPackage[]>('cluster_profile_list_script_packages', { profile });
+}
@@ -0,0 +1,46 @@
+import { invoke } from "@tauri-apps/api/core";
+import { KubeContextSource } from "../../hooks/useContextDiscovery";
+import { PresentationComponent } from "../../hooks/useResourceWatch";
+import { Gvk } from "../../model/k8s";
+
+export type Condition = {
+    type: string,
+    status: string,
+    reason: string | null,
+    message: string | null,
+    lastTransitionTime: string | null,
+};
+
+export type EventRow = {
+    type: string | null,
+    reason: string | null,
+    message: string | null,
+    count: number | null,
+    lastSeen: string | null,
+};
+
+export type SectionComponent =
+    | { kind: "KeyValueGrid", args: { rows: { key: string, value: PresentationComponent }[] } }
+    | { kind: "ConditionTable", args: { conditions: Condition[] } }
+    | { kind: "ResourceLinkList", args: { links: PresentationComponent[] } }
+    | { kind: "EventList", args: { max: number, events: EventRow[] } };
+
+export type RenderedSection = {
+    title: string,
+    component: SectionComponent | null,
+    error: string | null,
+};
+
+export type RenderedDetails = {
+    title: string,
+    sections: RenderedSection[],
+};
+
+export default function renderResourceDetails(
+    contextSource: KubeContextSource,
+    gvk: Gvk,
+    namespace: string,
+    name: string,
+) {
+    return invoke<RenderedDetails[]>("render_resource_details", { contextSource, gvk, namespace, name });
+}
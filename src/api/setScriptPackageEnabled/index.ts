@@ -0,0 +1,9 @@
+import { invoke } from "@tauri-apps/api/core";
+import { ClusterProfileId } from "../listClusterProfiles";
+import { ScriptPackage } from "../listScriptPackages";
+
+export default function setScriptPackageEnabled(profile: ClusterProfileId, source: ScriptPackage['source'], packageName: string, enabled: boolean) {
+    return invoke<void>('cluster_profile_set_script_package_enabled', {
+        profile, source, package: packageName, enabled
+    });
+}
@@ -0,0 +1,5 @@
+import { invoke } from "@tauri-apps/api/core";
+
+export default function uninstallScriptPackage(name: string) {
+    return invoke<void>('uninstall_script_package', { name });
+}
@@ -0,0 +1,6 @@
+import { invoke } from "@tauri-apps/api/core";
+import { ScriptPackage } from "../listScriptPackages";
+
+export default function upgradeScriptPackage(source: string) {
+    return invoke<ScriptPackage['manifest']>('upgrade_script_package', { source });
+}
@@ -0,0 +1,63 @@
+import { Alert, Card, Skeleton, Statistic } from "antd";
+import { KubeContextSource } from "../../hooks/useContextDiscovery";
+import useDashboard, { WidgetComponent } from "../../hooks/useDashboard";
+import EmojiHint from "../EmojiHint";
+import { SectionComponentView } from "../ResourceDetailsPanel";
+import { PresentationView } from "../ResourceList/CustomCell";
+import classes from './styles.module.css';
+
+export interface DashboardProps {
+    contextSource: KubeContextSource,
+}
+
+/**
+ * Overview of a cluster, built from the widgets that `dashboards` scripts registered.
+ */
+const Dashboard: React.FC<DashboardProps> = ({ contextSource }) => {
+    const widgets = useDashboard(contextSource);
+
+    if (widgets.length === 0) {
+        return <EmojiHint emoji="👈">Select a resource to get started.</EmojiHint>;
+    }
+
+    return (
+        <div className={classes.grid}>
+            {
+                widgets.map(widget => (
+                    <Card key={widget.id} size="small" title={widget.title}>
+                        {
+                            widget.error !== null
+                                ? <Alert type="error" message={widget.error} />
+                                : widget.component
+                                    ? <WidgetComponentView component={widget.component} />
+                                    : <Skeleton active paragraph={{ rows: 1 }} />
+                        }
+                    </Card>
+                ))
+            }
+        </div>
+    );
+};
+
+const WidgetComponentView: React.FC<{ component: WidgetComponent }> = ({ component }) => {
+    if (component.kind === "Stat") {
+        const { value, items, properties } = component.args;
+
+        return (
+            <>
+                <Statistic value={value} valueStyle={{ color: properties?.color }} />
+                {
+                    items.length > 0 && (
+                        <ul className={classes.items}>
+                            {items.map((item, idx) => <li key={idx}><PresentationView component={item} /></li>)}
+                        </ul>
+                    )
+                }
+            </>
+        );
+    }
+
+    return <SectionComponentView component={component} />;
+};
+
+export default Dashboard;
@@ -0,0 +1,95 @@
+import { EventCallback } from "@tauri-apps/api/event";
+import { notification, Progress } from "antd";
+import { useCallback } from "react";
+import { ActionOutcome, BatchItemOutcome } from "../../api/callMenuStackAction";
+import { useTauriEventListener } from "../../hooks/useTauriEventListener";
+
+type FrontendNotification = { tabId: string, level: 'info' | 'success' | 'warning' | 'error', message: string };
+type FrontendProgress = { tabId: string, message: string, percent: number | null };
+type FrontendActionFinished = { tabId: string, outcome: ActionOutcome };
+
+const PROGRESS_KEY = 'hyprkube:menu:progress';
+
+export interface MenuNotificationsProps {
+    tabId: string,
+}
+
+function location(outcome: Extract<ActionOutcome | BatchItemOutcome, { status: 'error' }>) {
+    return [outcome.script, outcome.line, outcome.column]
+        .filter(part => part !== undefined && part !== null)
+        .join(':');
+}
+
+/**
+ * Shows the toasts and progress of `frontend::notify` and `frontend::progress`, and errors of menu actions of this tab.
+ */
+const MenuNotifications: React.FC<MenuNotificationsProps> = ({ tabId }) => {
+    const [api, contextHolder] = notification.useNotification();
+
+    const onNotify = useCallback<EventCallback<FrontendNotification>>((event) => {
+        const { level, message } = event.payload;
+        api[level]({ message });
+    }, [api]);
+
+    const onProgress = useCallback<EventCallback<FrontendProgress>>((event) => {
+        const { message, percent } = event.payload;
+
+        api.open({
+            key: PROGRESS_KEY,
+            message,
+            description: percent === null ? undefined : <Progress percent={percent} size="small" />,
+            duration: 0,
+        });
+    }, [api]);
+
+    const onActionFinished = useCallback<EventCallback<FrontendActionFinished>>((event) => {
+        const { outcome } = event.payload;
+
+        api.destroy(PROGRESS_KEY);
+
+        if (outcome.status === 'error') {
+            api.error({
+                message: 'Menu action failed',
+                description: <>
+                    <div>{outcome.message}</div>
+                    <code>{location(outcome)}</code>
+                </>,
+                duration: 0,
+            });
+        }
+
+        if (outcome.status === 'batch') {
+            const failed = outcome.results.filter(result => result.status === 'error');
+
+            if (failed.length === 0) {
+                api.success({ message: `Done for ${outcome.results.length} resources` });
+                return;
+            }
+
+            api.error({
+                message: `Menu action failed for ${failed.length} of ${outcome.results.length} resources`,
+                description: (
+                    <ul>
+                        {
+                            failed.map(result => (
+                                <li key={`${result.namespace}/${result.name}`}>
+                                    <strong>{result.namespace ? `${result.namespace}/${result.name}` : result.name}</strong>: {result.message}
+                                    {' '}<code>{location(result)}</code>
+                                </li>
+                            ))
+                        }
+                    </ul>
+                ),
+                duration: 0,
+            });
+        }
+    }, [api]);
+
+    useTauriEventListener<FrontendNotification>('hyprkube:menu:notify', tabId, onNotify);
+    useTauriEventListener<FrontendProgress>('hyprkube:menu:progress', tabId, onProgress);
+    useTauriEventListener<FrontendActionFinished>('hyprkube:menu:action_finished', tabId, onActionFinished);
+
+    return contextHolder;
+};
+
+export default MenuNotifications;
@@ -0,0 +1,112 @@
+import { EventCallback } from "@tauri-apps/api/event";
+import { Checkbox, Form, Input, InputNumber, Modal, Select } from "antd";
+import { useCallback, useEffect, useState } from "react";
+import answerMenuPrompt, { PromptAnswer, PromptValue } from "../../api/answerMenuPrompt";
+import { useTauriEventListener } from "../../hooks/useTauriEventListener";
+
+export type PromptField = {
+    name: string,
+    type: 'text' | 'number' | 'select' | 'checkbox',
+    label?: string,
+    value?: PromptValue,
+    options: string[],
+    required: boolean,
+};
+
+type FrontendPrompt = { tabId: string, promptId: string, title: string, message?: string, fields: PromptField[] };
+type FrontendPromptExpired = { tabId: string, promptId: string };
+
+export interface MenuPromptProps {
+    tabId: string,
+}
+
+/**
+ * Shows the prompts of `frontend::prompt` that menu actions of this tab are waiting on.
+ *
+ * Actions of several menus can wait at the same time, their prompts are shown one after the other.
+ */
+const MenuPrompt: React.FC<MenuPromptProps> = ({ tabId }) => {
+    const [prompts, setPrompts] = useState<FrontendPrompt[]>([]);
+    const [form] = Form.useForm<Record<string, PromptValue>>();
+
+    const prompt = prompts.length > 0 ? prompts[0] : null;
+
+    useEffect(() => {
+        form.resetFields();
+    }, [form, prompt?.promptId]);
+
+    const onPrompt = useCallback<EventCallback<FrontendPrompt>>((event) => {
+        setPrompts(current => [...current, event.payload]);
+    }, []);
+
+    const onPromptExpired = useCallback<EventCallback<FrontendPromptExpired>>((event) => {
+        setPrompts(current => current.filter(p => p.promptId !== event.payload.promptId));
+    }, []);
+
+    useTauriEventListener<FrontendPrompt>('hyprkube:menu:prompt', tabId, onPrompt);
+    useTauriEventListener<FrontendPromptExpired>('hyprkube:menu:prompt_expired', tabId, onPromptExpired);
+
+    const answer = useCallback((answer: PromptAnswer) => {
+        if (!prompt) return;
+
+        answerMenuPrompt(prompt.promptId, answer)
+            .catch(e => alert(JSON.stringify(e)))
+            .finally(() => setPrompts(current => current.filter(p => p.promptId !== prompt.promptId)));
+    }, [prompt]);
+
+    const submit = useCallback(() => {
+        form.validateFields()
+            .then(values => answer({ outcome: 'submitted', values }))
+            .catch(() => undefined);
+    }, [answer, form]);
+
+    if (!prompt) return null;
+
+    return (
+        <Modal
+            key={prompt.promptId}
+            open
+            title={prompt.title}
+            onOk={submit}
+            onCancel={() => answer({ outcome: 'cancelled' })}
+            destroyOnHidden
+        >
+            {prompt.message ? <p>{prompt.message}</p> : null}
+            <Form
+                form={form}
+                layout="vertical"
+                initialValues={Object.fromEntries(prompt.fields.map(field => [field.name, field.value ?? null]))}
+                onFinish={submit}
+            >
+                {
+                    prompt.fields.map(field => (
+                        <Form.Item
+                            key={field.name}
+                            name={field.name}
+                            label={field.type === 'checkbox' ? undefined : field.label ?? field.name}
+                            rules={[{ required: field.required, message: `${field.label ?? field.name} is required` }]}
+                            valuePropName={field.type === 'checkbox' ? 'checked' : 'value'}
+                        >
+                            {renderInput(field)}
+                        </Form.Item>
+                    ))
+                }
+            </Form>
+        </Modal>
+    );
+};
+
+function renderInput(field: PromptField) {
+    switch (field.type) {
+        case 'number':
+            return <InputNumber style={{ width: '100%' }} />;
+        case 'select':
+            return <Select options={field.options.map(option => ({ label: option, value: option }))} />;
+        case 'checkbox':
+            return <Checkbox>{field.label ?? field.name}</Checkbox>;
+        default:
+            return <Input />;
+    }
+}
+
+export default MenuPrompt;
@@ -3,6 +3,7 @@ import useModal from "antd/es/modal/useModal";
 import { PropsWithChildren, use, useRef } from "react";
 import { createPortal } from "react-dom";
 import callMenustackAction from "../../api/callMenuStackAction";
+import createMultiResourceMenustack, { SelectedResource } from "../../api/createMultiResourceMenustack";
 import createResourceMenustack, { ActionButton, MenuItem, ResourceRef } from "../../api/createResourceMenustack";
 import dropResourceMenustack from "../../api/dropResourceMenustack";
 import { MegaTabContext } from "../../contexts/MegaTab";
@@ -14,11 +15,13 @@ export interface ResourceContextMenuProps {
     contextSource: KubeContextSource,
     gvk: Gvk,
     namespace: string,
-    name: string
+    name: string,
+    /** Other selected resources of the same kind; with more than one, the menu offers batch actions for all of them */
+    selection?: SelectedResource[],
 }
 
 export default function ResourceContextMenu({
-    contextSource, gvk, namespace, name, children
+    contextSource, gvk, namespace, name, selection, children
 }: PropsWithChildren<ResourceContextMenuProps>) {
     const lockRef = useRef<Promise<void>>(null);
     const menuIdRef = useRef<string>(null);
@@ -67,14 +70,16 @@ export default function ResourceContextMenu({
     }
 
     async function loadTopLevelBlueprint(gvk: Gvk, ns: string, n: string) {
-        const blueprint = await createResourceMenustack(
-            undefined,
-            contextSource,
-            tabIdentifier.toString(),
-            gvk,
-            ns,
-            n
-        );
+        const blueprint = (selection && selection.length > 1)
+            ? await createMultiResourceMenustack(contextSource, tabIdentifier.toString(), gvk, selection)
+            : await createResourceMenustack(
+                undefined,
+                contextSource,
+                tabIdentifier.toString(),
+                gvk,
+                ns,
+                n
+            );
 
         menuIdRef.current = blueprint.id;
         return blueprint;
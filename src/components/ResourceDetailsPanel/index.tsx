@@ -0,0 +1,127 @@
+import { useQuery } from "@tanstack/react-query";
+import { Alert, Descriptions, Empty, Table, Tabs, Tag } from "antd";
+import { RenderedSection, SectionComponent } from "../../api/renderResourceDetails";
+import { KubeContextSource } from "../../hooks/useContextDiscovery";
+import { Gvk } from "../../model/k8s";
+import renderResourceDetailsQuery from "../../queries/renderResourceDetails";
+import RelativeTime from "../RelativeTime";
+import { PresentationView } from "../ResourceList/CustomCell";
+import classes from './styles.module.css';
+
+export interface ResourceDetailsPanelProps {
+    contextSource: KubeContextSource,
+    gvk: Gvk,
+    namespace: string,
+    name: string,
+}
+
+/**
+ * Shows the sections that `details` scripts registered for the selected resource.
+ */
+const ResourceDetailsPanel: React.FC<ResourceDetailsPanelProps> = ({ contextSource, gvk, namespace, name }) => {
+    const details = useQuery(renderResourceDetailsQuery(contextSource, gvk, namespace, name));
+
+    if (details.isError) {
+        return <Alert type="error" message={String(details.error)} />;
+    }
+
+    if (!details.data || details.data.length === 0) {
+        return null;
+    }
+
+    return (
+        <div className={classes.container}>
+            <Tabs
+                size="small"
+                items={details.data.map(({ title, sections }) => ({
+                    key: title,
+                    label: title,
+                    children: sections.map((section, idx) => <DetailSectionView key={idx} section={section} />),
+                }))}
+            />
+        </div>
+    );
+};
+
+const DetailSectionView: React.FC<{ section: RenderedSection }> = ({ section }) => {
+    return (
+        <section className={classes.section}>
+            <h4>{section.title}</h4>
+            {
+                section.error !== null
+                    ? <Alert type="error" message={section.error} />
+                    : section.component && <SectionComponentView component={section.component} />
+            }
+        </section>
+    );
+};
+
+export const SectionComponentView: React.FC<{ component: SectionComponent }> = ({ component }) => {
+    if (component.kind === "KeyValueGrid") {
+        return (
+            <Descriptions
+                size="small"
+                column={2}
+                items={component.args.rows.map(({ key, value }) => ({
+                    key,
+                    label: key,
+                    children: <PresentationView component={value} />,
+                }))}
+            />
+        );
+    }
+
+    if (component.kind === "ConditionTable") {
+        return (
+            <Table
+                size="small"
+                pagination={false}
+                rowKey="type"
+                dataSource={component.args.conditions}
+                locale={{ emptyText: <Empty image={Empty.PRESENTED_IMAGE_SIMPLE} description="No conditions" /> }}
+                columns={[
+                    { title: "Type", dataIndex: "type" },
+                    { title: "Status", dataIndex: "status", render: (status: string) => <Tag color={status === "True" ? "green" : status === "False" ? "red" : undefined}>{status}</Tag> },
+                    { title: "Reason", dataIndex: "reason" },
+                    { title: "Message", dataIndex: "message" },
+                    { title: "Last transition", dataIndex: "lastTransitionTime", render: (time: string | null) => time && <RelativeTime timestamp={time} /> },
+                ]}
+            />
+        );
+    }
+
+    if (component.kind === "ResourceLinkList") {
+        if (component.args.links.length === 0) {
+            return <Empty image={Empty.PRESENTED_IMAGE_SIMPLE} description="None" />;
+        }
+
+        return (
+            <ul className={classes.links}>
+                {component.args.links.map((link, idx) => <li key={idx}><PresentationView component={link} /></li>)}
+            </ul>
+        );
+    }
+
+    if (component.kind === "EventList") {
+        return (
+            <Table
+                size="small"
+                pagination={false}
+                rowKey={(_, idx) => String(idx)}
+                dataSource={component.args.events}
+                locale={{ emptyText: <Empty image={Empty.PRESENTED_IMAGE_SIMPLE} description="No events" /> }}
+                columns={[
+                    { title: "Type", dataIndex: "type", render: (type: string | null) => <Tag color={type === "Warning" ? "orange" : undefined}>{type}</Tag> },
+                    { title: "Reason", dataIndex: "reason" },
+                    { title: "Message", dataIndex: "message" },
+                    { title: "Count", dataIndex: "count" },
+                    { title: "Last seen", dataIndex: "lastSeen", render: (time: string | null) => time && <RelativeTime timestamp={time} /> },
+                ]}
+            />
+        );
+    }
+
+    return null;
+};
+
+export default ResourceDetailsPanel;
@@ -1,8 +1,11 @@
 import { CellContext } from "@tanstack/react-table";
+import { emit } from "@tauri-apps/api/event";
 import { open } from '@tauri-apps/plugin-shell';
+import { Progress, Tag } from "antd";
 import type { ResourceTableFeatures } from "./tableFeatures";
 import dayjs from "dayjs";
-import React from "react";
+import React, { use } from "react";
+import { MegaTabContext } from "../../contexts/MegaTab";
 import { DisplayableResource, PresentationComponent } from "../../hooks/useResourceWatch";
 import RelativeTime from "../RelativeTime";
 import styles from './CustomCell.module.css';
@@ -10,8 +13,23 @@ import styles from './CustomCell.module.css';
 
 export const CustomCell: React.FC<CellContext<ResourceTableFeatures, [string, DisplayableResource], unknown>> = (props) => {
     const component = (props.getValue() as PresentationComponent);
+
+    return <PresentationView component={component} onFilter={(selector) => props.column.setFilterValue(selector)} />;
+}
+
+export interface PresentationViewProps {
+    component: PresentationComponent,
+    /** Called with the `key=value` selector of a clicked chip */
+    onFilter?: (selector: string) => void,
+}
+
+/**
+ * Displays a single presentation component, e.g. in a table cell or a details panel.
+ */
+export const PresentationView: React.FC<PresentationViewProps> = ({ component, onFilter }) => {
     const style = { color: component.properties?.color };
     const title = component.properties?.title;
+    const { tabIdentifier } = use(MegaTabContext)!;
     let inner = <>(Unhandled)</>;
 
     const { kind } = component;
@@ -59,5 +77,64 @@ export const CustomCell: React.FC<CellContext<ResourceTableFeatures, [string, Di
         }</>;
     }
 
+    if (kind === "Progress") {
+        const { value, max, label, color } = component.args;
+        const percent = max > 0 ? Math.min(100, Math.max(0, value / max * 100)) : 0;
+
+        inner = <Progress
+            className={styles.progress}
+            percent={percent}
+            size="small"
+            strokeColor={color ?? component.properties?.color}
+            format={() => label ?? `${Math.round(percent)}%`}
+        />;
+    }
+
+    if (kind === "Chips") {
+        const { chips, hidden } = component.args;
+
+        function chipHandler(e: React.MouseEvent<HTMLElement>, selector: string) {
+            e.stopPropagation();
+            onFilter?.(selector);
+        }
+
+        inner = <span className={styles.chips}>
+            {
+                chips.map(({ key, value }) =>
+                    <Tag
+                        key={key}
+                        className={styles.chip}
+                        color={component.properties?.color}
+                        title={`${key}=${value}`}
+                        onClick={(e) => chipHandler(e, `${key}=${value}`)}
+                    >
+                        {key}={value}
+                    </Tag>
+                )
+            }
+            {hidden > 0 && <Tag className={styles.chip} title={component.sortableValue}>+{hidden}</Tag>}
+        </span>;
+    }
+
+    if (kind === "ResourceLink") {
+        const { gvk, namespace, name, content } = component.args;
+        const tabId = tabIdentifier.toString();
+
+        function resourceLinkHandler(e: React.MouseEvent<HTMLAnchorElement>) {
+            e.stopPropagation();
+
+            // Same events as the scripted context menu actions, so the cluster view handles navigation
+            void emit('hyprkube:menu:resource:pick_gvk', { tabId, gvk });
+            if (namespace) {
+                void emit('hyprkube:menu:resource:pick_namespace', { tabId, namespace });
+            }
+            void emit('hyprkube:menu:resource:trigger_edit', { tabId, gvk, namespace: namespace ?? '', name });
+        }
+
+        const target = namespace ? `${gvk.kind} ${namespace}/${name}` : `${gvk.kind} ${name}`;
+
+        inner = <a style={{ cursor: "pointer" }} onClick={resourceLinkHandler} title={target}>↪&nbsp;{content}</a>;
+    }
+
     return <span style={style} title={title}>{inner}</span>;
 }
\ No newline at end of file
@@ -61,7 +61,14 @@ function createColumns(columnDefinitions: ColumnDefinition[]) {
                 return valueA.localeCompare(valueB, undefined, { numeric: true });
             },
             filterFn: (row, columnId, filterValue) => {
-                return row.getValue<PresentationComponent>(columnId).sortableValue.includes(filterValue as string);
+                const component = row.getValue<PresentationComponent>(columnId);
+
+                // A selector like `app=web` must not also match `app=web-canary`
+                if (component.kind === "Chips" && (filterValue as string).includes("=")) {
+                    return component.args.selectors.includes(filterValue as string);
+                }
+
+                return component.sortableValue.includes(filterValue as string);
             },
             enableColumnFilter: filterable,
             enableSorting: true, // TODO: View in backend should decide this
@@ -170,6 +177,9 @@ const ResourceList: React.FC<ResourcePresentationProps> = (props) => {
         setRowSelection(newSelection);
     }, [data, rowSelection]);
 
+    // Right-clicking one of several selected rows opens the batch menu of the whole selection
+    const selectedResources = table.getSelectedRowModel().rows.map(({ original: [, { namespace, name }] }) => ({ namespace, name }));
+
     const tableContainerRef = useRef<HTMLDivElement>(null);
 
     // Width of one monospace character (≈ the `ch` unit), measured once.
@@ -314,6 +324,7 @@ const ResourceList: React.FC<ResourcePresentationProps> = (props) => {
                                             <ResourceContextMenu
                                                 key={row.id}
                                                 contextSource={contextSource}
+                                                selection={row.getIsSelected() ? selectedResources : undefined}
                                                 {...{ namespace, name, gvk }}
                                             >
                                                 <tr
@@ -0,0 +1,145 @@
+import { useQuery, useQueryClient } from "@tanstack/react-query";
+import { confirm, open } from '@tauri-apps/plugin-dialog';
+import { Alert, Button, Dropdown, Flex, Switch, Table, Tag } from "antd";
+import { useCallback, useState } from "react";
+import installScriptPackage from "../../api/installScriptPackage";
+import { ClusterProfileId } from "../../api/listClusterProfiles";
+import { ScriptPackage, ScriptPackageStatus } from "../../api/listScriptPackages";
+import setScriptPackageEnabled from "../../api/setScriptPackageEnabled";
+import uninstallScriptPackage from "../../api/uninstallScriptPackage";
+import upgradeScriptPackage from "../../api/upgradeScriptPackage";
+import listScriptPackagesQuery from "../../queries/listScriptPackages";
+import classes from './styles.module.css';
+
+export interface ScriptPackagesProps {
+    clusterProfile: ClusterProfileId,
+}
+
+/**
+ * Lists the builtin and extension script packages, which can be enabled or disabled per cluster profile.
+ * Extensions are installed and upgraded from a directory or an archive, and can be uninstalled again.
+ *
+ * Connected clusters pick up the changes through their script watcher.
+ */
+const ScriptPackages: React.FC<ScriptPackagesProps> = ({ clusterProfile }) => {
+    const queryClient = useQueryClient();
+    const packages = useQuery(listScriptPackagesQuery(clusterProfile));
+
+    const refresh = useCallback(() => {
+        void queryClient.invalidateQueries({ queryKey: listScriptPackagesQuery(clusterProfile).queryKey });
+    }, [clusterProfile, queryClient]);
+
+    const setEnabled = useCallback((pkg: ScriptPackage, enabled: boolean) => {
+        setScriptPackageEnabled(clusterProfile, pkg.source, pkg.manifest.name, enabled)
+            .then(refresh)
+            .catch(e => alert("Error changing script package: " + JSON.stringify(e)));
+    }, [clusterProfile, refresh]);
+
+    const [busy, setBusy] = useState(false);
+
+    const installFrom = useCallback(async (operation: typeof installScriptPackage, directory: boolean) => {
+        const source = await open({
+            directory,
+            filters: directory ? undefined : [{ name: 'Package archives', extensions: ['zip', 'gz', 'tgz'] }],
+        });
+
+        if (source === null) {
+            return;
+        }
+
+        setBusy(true);
+        operation(source)
+            .then(refresh)
+            .catch(e => alert("Error installing script package: " + JSON.stringify(e)))
+            .finally(() => setBusy(false));
+    }, [refresh]);
+
+    const uninstall = useCallback((pkg: ScriptPackage) => {
+        confirm(`Its scripts are unloaded from all clusters.`, { kind: 'warning', title: `Uninstall ${pkg.manifest.name}?` })
+            .then(confirmed => {
+                if (!confirmed) return;
+
+                setBusy(true);
+                return uninstallScriptPackage(pkg.manifest.name)
+                    .then(refresh)
+                    .finally(() => setBusy(false));
+            })
+            .catch(e => alert("Error uninstalling script package: " + JSON.stringify(e)));
+    }, [refresh]);
+
+    const sourceMenu = (operation: typeof installScriptPackage) => ({
+        items: [
+            { key: 'archive', label: 'From archive…', onClick: () => void installFrom(operation, false) },
+            { key: 'directory', label: 'From directory…', onClick: () => void installFrom(operation, true) },
+        ],
+    });
+
+    if (packages.isError) {
+        return <Alert type="error" message={String(packages.error)} />;
+    }
+
+    return (
+        <div className={classes.container}>
+            <Flex gap="middle" align="center" justify="flex-end">
+                <Dropdown menu={sourceMenu(installScriptPackage)} disabled={busy}>
+                    <Button icon="📥">Install</Button>
+                </Dropdown>
+                <Dropdown menu={sourceMenu(upgradeScriptPackage)} disabled={busy}>
+                    <Button icon="⬆️">Upgrade</Button>
+                </Dropdown>
+            </Flex>
+            <Table
+                size="small"
+                pagination={false}
+                loading={packages.isPending || busy}
+                rowKey={pkg => `${pkg.source}/${pkg.path}`}
+                dataSource={packages.data}
+                columns={[
+                    {
+                        title: 'Enabled',
+                        key: 'enabled',
+                        render: (_, pkg) => (
+                            <Switch size="small"
+                                checked={pkg.status.state !== 'disabled'}
+                                onChange={(checked) => setEnabled(pkg, checked)}
+                            />
+                        ),
+                    },
+                    { title: 'Name', key: 'name', render: (_, pkg) => pkg.manifest.name },
+                    { title: 'Version', key: 'version', render: (_, pkg) => pkg.manifest.version },
+                    { title: 'Source', dataIndex: 'source' },
+                    { title: 'Status', key: 'status', render: (_, pkg) => <StatusTag status={pkg.status} /> },
+                    {
+                        title: 'Capabilities',
+                        key: 'capabilities',
+                        render: (_, pkg) => pkg.manifest.capabilities.map(c => <Tag key={c}>{c}</Tag>),
+                    },
+                    { title: 'Description', key: 'description', render: (_, pkg) => pkg.manifest.description },
+                    {
+                        key: 'actions',
+                        render: (_, pkg) => pkg.source === 'extension' && (
+                            <Button size="small" icon="🗑️" disabled={busy} onClick={() => uninstall(pkg)}>Uninstall</Button>
+                        ),
+                    },
+                ]}
+            />
+        </div>
+    );
+};
+
+const StatusTag: React.FC<{ status: ScriptPackageStatus }> = ({ status }) => {
+    switch (status.state) {
+        case 'active':
+            return <Tag color="green">active</Tag>;
+        case 'disabled':
+            return <Tag>disabled</Tag>;
+        case 'overridden':
+            return <Tag title="Another package with the same name takes precedence">overridden</Tag>;
+        case 'incompatible':
+            return <Tag color="orange" title={status.reason}>incompatible</Tag>;
+        case 'invalid':
+            return <Tag color="red" title={status.reason}>invalid</Tag>;
+    }
+};
+
+export default ScriptPackages;
@@ -0,0 +1,56 @@
+import React, { useMemo, useState } from 'react';
+
+import { Button, Checkbox, Flex, Input } from 'antd';
+import dayjs from 'dayjs';
+import { KubeContextSource } from '../../hooks/useContextDiscovery';
+import useScriptDiagnostics, { ScriptDiagnostic } from '../../hooks/useScriptDiagnostics';
+import classes from './component.module.css';
+
+export interface ScriptingConsoleProps {
+    contextSource: KubeContextSource,
+}
+
+const ScriptingConsole: React.FC<ScriptingConsoleProps> = ({ contextSource }) => {
+    const [diagnostics, clear] = useScriptDiagnostics(contextSource);
+    const [search, setSearch] = useState('');
+    const [showOutput, setShowOutput] = useState(true);
+
+    const visible = useMemo(() => diagnostics
+        .filter(d => showOutput || (d.kind !== 'print' && d.kind !== 'debug'))
+        .filter(d => formatDiagnostic(d).includes(search)),
+        [diagnostics, search, showOutput]);
+
+    return (
+        <div className={classes.container}>
+            <Flex gap="middle" align="center" justify="flex-end">
+                <Button onClick={clear}>Clear</Button>
+                <Checkbox checked={showOutput} onChange={(e) => setShowOutput(e.target.checked)}>Show print/debug</Checkbox>
+                <Input type="search" style={{ width: "300px" }} placeholder="Filter messages" value={search} onChange={(e) => setSearch(e.target.value)} />
+            </Flex>
+            <div className={classes.entries}>
+                {
+                    visible.map((diagnostic, idx) => (
+                        <pre key={idx} className={classes[diagnostic.kind]} title={diagnostic.resourceUid ?? undefined}>
+                            <span className={classes.timestamp}>{dayjs(diagnostic.timestamp).format('HH:mm:ss')}</span>
+                            {formatDiagnostic(diagnostic)}
+                        </pre>
+                    ))
+                }
+            </div>
+        </div>
+    );
+}
+
+function formatDiagnostic(diagnostic: ScriptDiagnostic): string {
+    const { kind, script, line, column, presentation, columnTitle, message } = diagnostic;
+
+    const location = script === null
+        ? ''
+        : [script, line, column].filter(part => part !== null).join(':') + ': ';
+
+    const context = [presentation, columnTitle].filter(part => part !== null).join(' / ');
+
+    return `[${kind}] ${location}${context && `(${context}) `}${message}`;
+}
+
+export default ScriptingConsole;
@@ -10,7 +10,12 @@ import getDefaultNamespace from "../../api/getDefaultNamespace";
 import getResourceYaml from "../../api/getResourceYaml";
 import setDefaultNamespace from "../../api/setDefaultNamespace";
 import LogPanel from "../../components/LogPanel";
+import MenuNotifications from "../../components/MenuNotifications";
+import MenuPrompt from "../../components/MenuPrompt";
+import ResourceDetailsPanel from "../../components/ResourceDetailsPanel";
 import ResourceList from "../../components/ResourceList";
+import ScriptingConsole from "../../components/ScriptingConsole";
+import ScriptPackages from "../../components/ScriptPackages";
 import { Tab } from "../../components/TabView";
 import { TabElement } from "../../components/TabView/hooks";
 import HyprkubeTerminal from "../../components/Terminal";
@@ -194,6 +199,26 @@ const ResourceListInspector: React.FC<ResourceListInspectorProps> = (props) => {
         }
     }, [contextSource, pushBottomTab, resources]);
 
+    const openScriptingConsole = useCallback(() => {
+        pushBottomTab(
+            <Tab title="Scripting console">
+                {
+                    () => <ScriptingConsole contextSource={contextSource} />
+                }
+            </Tab>
+        );
+    }, [contextSource, pushBottomTab]);
+
+    const openScriptPackages = useCallback(() => {
+        pushBottomTab(
+            <Tab title="Script packages">
+                {
+                    () => <ScriptPackages clusterProfile={clusterProfile} />
+                }
+            </Tab>
+        );
+    }, [clusterProfile, pushBottomTab]);
+
     const resourceScope = findResourceScope(discovery, gvk);
     const resourceNamePlural = findResourcePlural(discovery, gvk);
 
@@ -235,8 +260,12 @@ const ResourceListInspector: React.FC<ResourceListInspectorProps> = (props) => {
                         ? null
                         : <Button icon="🗑️" onClick={deleteSelectedResources}> Delete {selectedResources.length}</Button>
                 }
+                <Button icon="📜" title="Script errors and output" onClick={openScriptingConsole}>Scripting console</Button>
+                <Button icon="📦" title="Enable, disable and install script packages" onClick={openScriptPackages}>Script packages</Button>
                 <div ref={searchbarRef}></div>
             </div>
+            <MenuPrompt tabId={tabIdentifier.toString()} />
+            <MenuNotifications tabId={tabIdentifier.toString()} />
             <div className={classes.tableArea}>
                 <ResourceList
                     contextSource={contextSource}
@@ -250,6 +279,16 @@ const ResourceListInspector: React.FC<ResourceListInspectorProps> = (props) => {
                     onSelectionChanged={setSelectedResources}
                 />
             </div>
+            {
+                selectedResources.length === 1
+                    ? <ResourceDetailsPanel
+                        contextSource={contextSource}
+                        gvk={gvk}
+                        namespace={selectedResources[0][1].namespace}
+                        name={selectedResources[0][1].name}
+                    />
+                    : null
+            }
         </div>
     );
 };
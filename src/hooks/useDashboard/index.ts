@@ -0,0 +1,61 @@
+import { Channel, invoke } from "@tauri-apps/api/core";
+import { useEffect, useState } from "react";
+import { SectionComponent } from "../../api/renderResourceDetails";
+import { Gvk } from "../../model/k8s";
+import { KubeContextSource } from "../useContextDiscovery";
+import { PresentationComponent, Properties } from "../useResourceWatch";
+
+export type Stat = {
+    value: string,
+    items: PresentationComponent[],
+    properties: Properties | null,
+};
+
+export type WidgetComponent =
+    | { kind: "Stat", args: Stat }
+    | Extract<SectionComponent, { kind: "KeyValueGrid" | "ResourceLinkList" }>;
+
+export type DashboardWidget = {
+    id: string,
+    title: string,
+    gvk: Gvk,
+    component: WidgetComponent | null,
+    error: string | null,
+};
+
+type DashboardEvent =
+    | { event: "announceWidgets", data: { widgets: { id: string, title: string, gvk: Gvk }[] } }
+    | { event: "widgetUpdated", data: { id: string, component: WidgetComponent | null, error: string | null } };
+
+/**
+ * Streams the widgets of the scripted cluster dashboard. Widgets that have not been rendered yet have neither a
+ * component nor an error.
+ */
+export default function useDashboard(contextSource: KubeContextSource): DashboardWidget[] {
+    const [widgets, setWidgets] = useState<DashboardWidget[]>([]);
+
+    useEffect(() => {
+        const channel = new Channel<DashboardEvent>();
+
+        channel.onmessage = (message) => {
+            if (message.event === "announceWidgets") {
+                setWidgets(message.data.widgets.map(widget => ({ ...widget, component: null, error: null })));
+            } else if (message.event === "widgetUpdated") {
+                const { id, component, error } = message.data;
+                setWidgets(widgets => widgets.map(widget => widget.id === id ? { ...widget, component, error } : widget));
+            }
+        };
+
+        invoke('watch_dashboard', { contextSource, channel })
+            .catch(e => {
+                if (e === 'BackgroundTaskRejected') return;
+                alert(JSON.stringify(e));
+            });
+
+        return () => {
+            void invoke('cleanup_channel', { channel });
+        };
+    }, [contextSource]);
+
+    return widgets;
+}
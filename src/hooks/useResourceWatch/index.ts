@@ -3,7 +3,7 @@ import { useEffect, useState } from "react";
 import { Gvk } from "../../model/k8s";
 import { KubeContextSource } from "../useContextDiscovery";
 
-type Properties = {
+export type Properties = {
     color?: string,
     title?: string,
 }
@@ -48,6 +48,37 @@ export type PresentationComponent =
         args: {
             color: string
         }
+    } & CommonFields
+    |
+    {
+        kind: "Progress",
+        args: {
+            value: number,
+            max: number,
+            label: string | null,
+            thresholds: { from: number, color: string }[],
+            color: string | null
+        }
+    } & CommonFields
+    |
+    {
+        kind: "Chips",
+        args: {
+            chips: { key: string, value: string }[],
+            hidden: number,
+            /** `key=value` selectors of all chips, including hidden ones */
+            selectors: string[]
+        }
+    } & CommonFields
+    |
+    {
+        kind: "ResourceLink",
+        args: {
+            gvk: Gvk,
+            namespace: string | null,
+            name: string,
+            content: string
+        }
     } & CommonFields;
 
 export type OkData = { "Ok": PresentationComponent };
@@ -0,0 +1,51 @@
+import { Channel, invoke } from "@tauri-apps/api/core";
+import { useEffect, useState } from "react";
+import { KubeContextSource } from "../useContextDiscovery";
+
+export type ScriptDiagnosticKind = 'compile' | 'evaluation' | 'matcher' | 'render' | 'action' | 'print' | 'debug';
+
+export type ScriptDiagnostic = {
+    kind: ScriptDiagnosticKind,
+    timestamp: string,
+    message: string,
+    script: string | null,
+    line: number | null,
+    column: number | null,
+    presentation: string | null,
+    columnTitle: string | null,
+    resourceUid: string | null,
+};
+
+export default function useScriptDiagnostics(contextSource: KubeContextSource): [ScriptDiagnostic[], () => void] {
+    const [diagnostics, setDiagnostics] = useState<ScriptDiagnostic[]>([]);
+
+    useEffect(() => {
+        const channel = new Channel<ScriptDiagnostic>();
+
+        channel.onmessage = (diagnostic) => {
+            setDiagnostics(diagnostics => [...diagnostics, diagnostic]);
+        };
+
+        // We really want to reset the state at this point:
+        // eslint-disable-next-line react-hooks/set-state-in-effect
+        setDiagnostics([]);
+
+        invoke('watch_script_diagnostics', { contextSource, channel })
+            .catch(e => {
+                if (e === 'BackgroundTaskRejected') return;
+                alert(JSON.stringify(e));
+            });
+
+        return () => {
+            void invoke('cleanup_channel', { channel });
+        };
+    }, [contextSource]);
+
+    const clear = () => {
+        invoke('clear_script_diagnostics', { contextSource })
+            .then(() => setDiagnostics([]))
+            .catch(e => alert(JSON.stringify(e)));
+    };
+
+    return [diagnostics, clear];
+}
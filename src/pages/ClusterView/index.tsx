@@ -2,6 +2,7 @@ import { EventCallback } from '@tauri-apps/api/event';
 import { Splitter } from 'antd';
 import { use, useCallback, useState } from 'react';
 import { ErrorBoundary } from 'react-error-boundary';
+import Dashboard from '../../components/Dashboard';
 import EmojiHint from '../../components/EmojiHint';
 import RotatingSpinner from '../../components/RotatingSpinner';
 import TabView from '../../components/TabView';
@@ -111,7 +112,7 @@ const ClusterView: React.FC<ClusterViewProps> = ({ contextSource, preSelectedGvk
                         >
                             {
                                 !activeGvk
-                                    ? <EmojiHint emoji="👈">Select a resource to get started.</EmojiHint>
+                                    ? <Dashboard contextSource={contextSource} />
                                     : (
                                         <ResourceListInspector
                                             discovery={discovery}
@@ -0,0 +1,10 @@
+import { queryOptions } from "@tanstack/react-query";
+import { ClusterProfileId } from "../../api/listClusterProfiles";
+import listScriptPackages from "../../api/listScriptPackages";
+
+export default function listScriptPackagesQuery(profile: ClusterProfileId) {
+    return queryOptions({
+        queryKey: ['listScriptPackages', profile],
+        queryFn: () => listScriptPackages(profile),
+    });
+}
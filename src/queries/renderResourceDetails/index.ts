@@ -0,0 +1,15 @@
+import { queryOptions } from "@tanstack/react-query";
+import renderResourceDetails from "../../api/renderResourceDetails";
+import { KubeContextSource } from "../../hooks/useContextDiscovery";
+import { Gvk } from "../../model/k8s";
+
+/** Details are rendered again periodically, so that related objects and events show up once they are known */
+const REFETCH_INTERVAL = 5000;
+
+export default function renderResourceDetailsQuery(contextSource: KubeContextSource, gvk: Gvk, namespace: string, name: string) {
+    return queryOptions({
+        queryKey: ['renderResourceDetails', contextSource, gvk, namespace, name],
+        queryFn: () => renderResourceDetails(contextSource, gvk, namespace, name),
+        refetchInterval: REFETCH_INTERVAL,
+    });
+}